@@ -102,6 +102,27 @@ fn glyph_index_u41(bencher: &mut bencher::Bencher) {
     })
 }
 
+fn glyph_index_ascii(bencher: &mut bencher::Bencher) {
+    let font_data = std::fs::read("fonts/SourceSansPro-Regular.ttf").unwrap();
+    let font = ttf::Font::from_data(&font_data, 0).unwrap();
+    bencher.iter(|| {
+        for c in ' '..='~' {
+            bencher::black_box(font.glyph_index(c));
+        }
+    })
+}
+
+fn glyph_index_cursor_ascii(bencher: &mut bencher::Bencher) {
+    let font_data = std::fs::read("fonts/SourceSansPro-Regular.ttf").unwrap();
+    let font = ttf::Font::from_data(&font_data, 0).unwrap();
+    bencher.iter(|| {
+        let mut cursor = font.glyph_index_cursor();
+        for c in ' '..='~' {
+            bencher::black_box(cursor.glyph_index(c));
+        }
+    })
+}
+
 struct Builder(usize);
 
 impl ttf_parser::OutlineBuilder for Builder {
@@ -144,6 +165,8 @@ bencher::benchmark_group!(perf,
     glyph_name_8,
     glyph_name_276,
     family_name,
-    glyph_index_u41
+    glyph_index_u41,
+    glyph_index_ascii,
+    glyph_index_cursor_ascii
 );
 bencher::benchmark_main!(perf);
@@ -291,8 +291,8 @@ pub extern "C" fn ttfp_get_weight(font: *const ttfp_font) -> u16 {
 
 /// @brief Returns font's width.
 ///
-/// @return Font's width in a 1..9 range or `5` when OS/2 table is not present
-///         or when value is invalid.
+/// @return Font's width, usually in a 1..9 range, or `5` when OS/2 table
+///         is not present.
 #[no_mangle]
 pub extern "C" fn ttfp_get_width(font: *const ttfp_font) -> u16 {
     font_from_ptr(font).width().to_number()
@@ -388,6 +388,16 @@ pub extern "C" fn ttfp_get_x_height(font: *const ttfp_font) -> i16 {
     font_from_ptr(font).x_height().unwrap_or(0)
 }
 
+/// @brief Returns font's capital height.
+///
+/// This function is affected by variation axes.
+///
+/// @return capital height or 0 when OS/2 table is not present or when its version is < 2.
+#[no_mangle]
+pub extern "C" fn ttfp_get_capital_height(font: *const ttfp_font) -> i16 {
+    font_from_ptr(font).capital_height().unwrap_or(0)
+}
+
 /// @brief Returns font's underline metrics.
 ///
 /// This function is affected by variation axes.
@@ -507,7 +517,12 @@ pub extern "C" fn ttfp_get_glyph_var_index(
         let get = || {
             let c = char::try_from(codepoint).ok()?;
             let v = char::try_from(variation).ok()?;
-            font_from_ptr(font).glyph_variation_index(c, v).map(|gid| gid.0)
+            let font = font_from_ptr(font);
+            match font.glyph_variation_index(c, v) {
+                ttf_parser::GlyphVariationResult::Found(id) => Some(id.0),
+                ttf_parser::GlyphVariationResult::UseDefault => font.glyph_index(c).map(|id| id.0),
+                ttf_parser::GlyphVariationResult::NotFound => None,
+            }
         };
 
         get().unwrap_or(0)
@@ -695,9 +710,11 @@ pub extern "C" fn ttfp_get_glyph_bbox(
 /// A font can define a glyph using a raster or a vector image instead of a simple outline.
 /// Which is primarily used for emojis. This method should be used to access those images.
 ///
-/// `pixels_per_em` allows selecting a preferred image size. While the chosen size will
-/// be closer to an upper one. So when font has 64px and 96px images and `pixels_per_em`
-/// is set to 72, 96px image will be returned.
+/// `pixels_per_em` allows selecting a preferred image size.
+/// For `sbix`, the largest image that is not larger than `pixels_per_em` will be returned,
+/// or the smallest one when all images are larger. So when font has 64px and 96px images
+/// and `pixels_per_em` is set to 72, 64px image will be returned.
+/// For `CBLC`+`CBDT`, the chosen size will be closer to an upper one instead.
 /// To get the largest image simply use `SHRT_MAX`.
 /// This property has no effect in case of SVG.
 ///
@@ -810,4 +827,110 @@ mod tests {
 
         assert_eq!(std::mem::size_of::<ttf_parser::ScriptMetrics>(), 8);
     }
+
+    #[test]
+    fn table_names_are_in_sync() {
+        use ttf_parser::TableName;
+
+        // Will fail to compile when a new table name was added,
+        // so the list below and the C header must be updated as well.
+        fn is_listed(name: TableName) -> bool {
+            match name {
+                TableName::AxisVariations
+                | TableName::CharacterToGlyphIndexMapping
+                | TableName::Color
+                | TableName::ColorBitmapData
+                | TableName::ColorBitmapLocation
+                | TableName::ColorPalette
+                | TableName::CompactFontFormat
+                | TableName::CompactFontFormat2
+                | TableName::ControlValueVariations
+                | TableName::FontVariations
+                | TableName::GlyphData
+                | TableName::GlyphDefinition
+                | TableName::GlyphPositioning
+                | TableName::GlyphSubstitution
+                | TableName::GlyphVariations
+                | TableName::Header
+                | TableName::HorizontalHeader
+                | TableName::HorizontalMetrics
+                | TableName::HorizontalMetricsVariations
+                | TableName::IndexToLocation
+                | TableName::Kerning
+                | TableName::MaximumProfile
+                | TableName::MetricsVariations
+                | TableName::Naming
+                | TableName::PostScript
+                | TableName::ScalableVectorGraphics
+                | TableName::StandardBitmapGraphics
+                | TableName::VerticalHeader
+                | TableName::VerticalMetrics
+                | TableName::VerticalMetricsVariations
+                | TableName::VerticalOrigin
+                | TableName::WindowsMetrics => true,
+            }
+        }
+
+        let names = [
+            TableName::AxisVariations,
+            TableName::CharacterToGlyphIndexMapping,
+            TableName::Color,
+            TableName::ColorBitmapData,
+            TableName::ColorBitmapLocation,
+            TableName::ColorPalette,
+            TableName::CompactFontFormat,
+            TableName::CompactFontFormat2,
+            TableName::ControlValueVariations,
+            TableName::FontVariations,
+            TableName::GlyphData,
+            TableName::GlyphDefinition,
+            TableName::GlyphPositioning,
+            TableName::GlyphSubstitution,
+            TableName::GlyphVariations,
+            TableName::Header,
+            TableName::HorizontalHeader,
+            TableName::HorizontalMetrics,
+            TableName::HorizontalMetricsVariations,
+            TableName::IndexToLocation,
+            TableName::Kerning,
+            TableName::MaximumProfile,
+            TableName::MetricsVariations,
+            TableName::Naming,
+            TableName::PostScript,
+            TableName::ScalableVectorGraphics,
+            TableName::StandardBitmapGraphics,
+            TableName::VerticalHeader,
+            TableName::VerticalMetrics,
+            TableName::VerticalMetricsVariations,
+            TableName::VerticalOrigin,
+            TableName::WindowsMetrics,
+        ];
+
+        let header = include_str!("ttfparser.h");
+        let start = header.find("typedef enum {").unwrap();
+        let end = start + header[start..].find("} ttfp_table_name;").unwrap();
+        let c_names: Vec<&str> = header[start..end]
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| line.starts_with("TTFP_TABLE_NAME_"))
+            .map(|line| line.trim_end_matches(',').trim_end_matches(" = 0"))
+            .collect();
+
+        assert_eq!(c_names.len(), names.len());
+        for (i, (name, c_name)) in names.iter().zip(c_names).enumerate() {
+            assert!(is_listed(*name));
+            assert_eq!(*name as usize, i);
+
+            // Convert `CamelCase` into `TTFP_TABLE_NAME_CAMEL_CASE`.
+            let mut expected = String::from("TTFP_TABLE_NAME");
+            for c in format!("{:?}", name).chars() {
+                if c.is_ascii_uppercase() {
+                    expected.push('_');
+                }
+                expected.push(c.to_ascii_uppercase());
+            }
+
+            assert_eq!(c_name, expected);
+        }
+    }
 }
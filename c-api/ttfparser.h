@@ -21,13 +21,18 @@
 typedef enum {
     TTFP_TABLE_NAME_AXIS_VARIATIONS = 0,
     TTFP_TABLE_NAME_CHARACTER_TO_GLYPH_INDEX_MAPPING,
+    TTFP_TABLE_NAME_COLOR,
     TTFP_TABLE_NAME_COLOR_BITMAP_DATA,
     TTFP_TABLE_NAME_COLOR_BITMAP_LOCATION,
+    TTFP_TABLE_NAME_COLOR_PALETTE,
     TTFP_TABLE_NAME_COMPACT_FONT_FORMAT,
     TTFP_TABLE_NAME_COMPACT_FONT_FORMAT2,
+    TTFP_TABLE_NAME_CONTROL_VALUE_VARIATIONS,
     TTFP_TABLE_NAME_FONT_VARIATIONS,
     TTFP_TABLE_NAME_GLYPH_DATA,
     TTFP_TABLE_NAME_GLYPH_DEFINITION,
+    TTFP_TABLE_NAME_GLYPH_POSITIONING,
+    TTFP_TABLE_NAME_GLYPH_SUBSTITUTION,
     TTFP_TABLE_NAME_GLYPH_VARIATIONS,
     TTFP_TABLE_NAME_HEADER,
     TTFP_TABLE_NAME_HORIZONTAL_HEADER,
@@ -306,8 +311,8 @@ uint16_t ttfp_get_weight(const ttfp_font *font);
 /**
  * @brief Returns font's width.
  *
- * @return Font's width in a 1..9 range or `5` when OS/2 table is not present
- *         or when value is invalid.
+ * @return Font's width, usually in a 1..9 range, or `5` when OS/2 table
+ *         is not present.
  */
 uint16_t ttfp_get_width(const ttfp_font *font);
 
@@ -391,6 +396,15 @@ uint16_t ttfp_get_units_per_em(const ttfp_font *font);
  */
 int16_t ttfp_get_x_height(const ttfp_font *font);
 
+/**
+ * @brief Returns font's capital height.
+ *
+ * This function is affected by variation axes.
+ *
+ * @return capital height or 0 when OS/2 table is not present or when its version is < 2.
+ */
+int16_t ttfp_get_capital_height(const ttfp_font *font);
+
 /**
  * @brief Returns font's underline metrics.
  *
@@ -571,9 +585,11 @@ bool ttfp_get_glyph_bbox(const ttfp_font *font, uint16_t glyph_id, ttfp_rect *bb
  * A font can define a glyph using a raster or a vector image instead of a simple outline.
  * Which is primarily used for emojis. This method should be used to access those images.
  *
- * `pixels_per_em` allows selecting a preferred image size. While the chosen size will
- * be closer to an upper one. So when font has 64px and 96px images and `pixels_per_em`
- * is set to 72, 96px image will be returned.
+ * `pixels_per_em` allows selecting a preferred image size.
+ * For `sbix`, the largest image that is not larger than `pixels_per_em` will be returned,
+ * or the smallest one when all images are larger. So when font has 64px and 96px images
+ * and `pixels_per_em` is set to 72, 64px image will be returned.
+ * For `CBLC`+`CBDT`, the chosen size will be closer to an upper one instead.
  * To get the largest image simply use `SHRT_MAX`.
  * This property has no effect in case of SVG.
  *
@@ -205,9 +205,9 @@ class TtfFixed(TtfType):
         return 4
 
     def print(self, offset: int) -> None:
-        print(f'i32::from_be_bytes(['
+        print(f'crate::parser::Fixed::from_bits(i32::from_be_bytes(['
               f'    self.data[{offset}], self.data[{offset + 1}], self.data[{offset + 2}], self.data[{offset + 3}]'
-              f']) as f32 / 65536.0')
+              f'])).0')
 
 
 # unsupported
@@ -331,13 +331,14 @@ NAME_RECORD_TABLE = [
     TableRow(TtfUInt16(),   'offset'),
 ]
 
-# https://docs.microsoft.com/en-us/typography/opentype/spec/kern
-# In the kern table, coverage is stored as uint16, but we are using two uint8 to simply the code.
-KERN_COVERAGE = [
-    TableRow(TtfUInt8(),    'coverage'),
-    TableRow(TtfUInt8(),    'format'),
+# https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1
+NAME_LANG_TAG_RECORD = [
+    TableRow(TtfUInt16(),   'length'),
+    TableRow(TtfUInt16(),   'offset'),
 ]
 
+# https://docs.microsoft.com/en-us/typography/opentype/spec/kern
+# In the kern table, coverage is stored as uint16, but we are using two uint8 to simply the code.
 # https://docs.microsoft.com/en-us/typography/opentype/spec/kern
 # In the kern table, a kerning pair is stored as two uint16, but we are using one uint32
 # so we can use binary search.
@@ -454,6 +455,13 @@ GDEF_RANGE_RECORD = [
     TableRow(TtfUInt16(),                   'startCoverageIndex'),
 ]
 
+# https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#script-list-table-and-script-record
+# Also used by LangSysRecord and FeatureRecord.
+GDEF_TAG_RECORD = [
+    TableRow(TtfTag(),      'tag'),
+    TableRow(TtfOffset16(), 'offset'),
+]
+
 # https://docs.microsoft.com/en-us/typography/opentype/spec/avar#table-formats
 AVAR_AXIS_VALUE_MAP_RECORD = [
     TableRow(TtfInt16(),    'fromCoordinate'),  # Actually F2DOT14.
@@ -498,6 +506,27 @@ SVG_DOC_RECORD = [
     TableRow(TtfUInt32(),           'svgDocLength'),
 ]
 
+# https://docs.microsoft.com/en-us/typography/opentype/spec/colr#baseglyph-and-layer-records
+COLR_BASE_GLYPH_RECORD = [
+    TableRow(TtfGlyphId(),  'glyphID'),
+    TableRow(TtfUInt16(),   'firstLayerIndex'),
+    TableRow(TtfUInt16(),   'numLayers'),
+]
+
+# https://docs.microsoft.com/en-us/typography/opentype/spec/colr#baseglyph-and-layer-records
+COLR_LAYER_RECORD = [
+    TableRow(TtfGlyphId(),  'glyphID'),
+    TableRow(TtfUInt16(),   'paletteIndex'),
+]
+
+# https://docs.microsoft.com/en-us/typography/opentype/spec/cpal#color-records-array
+CPAL_COLOR_RECORD = [
+    TableRow(TtfUInt8(),    'blue'),
+    TableRow(TtfUInt8(),    'green'),
+    TableRow(TtfUInt8(),    'red'),
+    TableRow(TtfUInt8(),    'alpha'),
+]
+
 # https://docs.microsoft.com/en-us/typography/opentype/spec/post
 POST_TABLE = [
     TableRow(TtfFixed(),    'version'),
@@ -610,8 +639,14 @@ generate_table(TABLE_RECORD, 'TableRecord')
 print()
 print('pub mod head {')
 print(f'pub const TABLE_SIZE: usize = {find_struct_size(HEAD_TABLE)};')
+table_field_offset(HEAD_TABLE, 'magicNumber')
+table_field_offset(HEAD_TABLE, 'flags')
 table_field_offset(HEAD_TABLE, 'unitsPerEm')
+table_field_offset(HEAD_TABLE, 'created')
+table_field_offset(HEAD_TABLE, 'modified')
+table_field_offset(HEAD_TABLE, 'macStyle')
 table_field_offset(HEAD_TABLE, 'indexToLocFormat')
+table_field_offset(HEAD_TABLE, 'glyphDataFormat')
 print('}')
 print()
 print('pub mod hhea {')
@@ -638,8 +673,10 @@ print('}')
 print()
 print('pub mod post {')
 print(f'pub const TABLE_SIZE: usize = {find_struct_size(POST_TABLE)};')
+table_field_offset(POST_TABLE, 'italicAngle')
 table_field_offset(POST_TABLE, 'underlinePosition')
 table_field_offset(POST_TABLE, 'underlineThickness')
+table_field_offset(POST_TABLE, 'isFixedPitch')
 print('}')
 print()
 print('pub mod cmap {')
@@ -666,35 +703,40 @@ table_field_offset(OS_2_TABLE, 'ySubscriptXSize')
 table_field_offset(OS_2_TABLE, 'ySuperscriptXSize')
 table_field_offset(OS_2_TABLE, 'yStrikeoutSize')
 table_field_offset(OS_2_TABLE, 'yStrikeoutPosition')
+table_field_offset(OS_2_TABLE, 'ulUnicodeRange1')
 table_field_offset(OS_2_TABLE, 'fsSelection')
 table_field_offset(OS_2_TABLE, 'sTypoAscender')
 table_field_offset(OS_2_TABLE, 'sTypoDescender')
 table_field_offset(OS_2_TABLE, 'sTypoLineGap')
+table_field_offset(OS_2_TABLE, 'ulCodePageRange1')
 table_field_offset(OS_2_TABLE, 'sxHeight')
+table_field_offset(OS_2_TABLE, 'sCapHeight')
 print('}')
 print()
 print('pub mod name {')
 print('use crate::parser::FromData;')
 print()
 generate_table(NAME_RECORD_TABLE, 'NameRecord')
+print()
+generate_table(NAME_LANG_TAG_RECORD, 'LangTagRecord')
 print('}')
 print()
 print('pub mod kern {')
 print('use crate::parser::FromData;')
 print()
-generate_table(KERN_COVERAGE, 'Coverage')
-print()
 generate_table(KERNING_RECORD, 'KerningRecord')
 print('}')
 print()
 print('pub mod gdef {')
 print('use core::ops::RangeInclusive;')
-print('use crate::GlyphId;')
-print('use crate::parser::FromData;')
+print('use crate::{GlyphId, Tag};')
+print('use crate::parser::{FromData, Offset16};')
 print()
 generate_table(GDEF_CLASS_RANGE_RECORD, 'ClassRangeRecord')
 print()
 generate_table(GDEF_RANGE_RECORD, 'RangeRecord')
+print()
+generate_table(GDEF_TAG_RECORD, 'TagRecord')
 print('}')
 print()
 print('pub mod avar {')
@@ -737,3 +779,18 @@ print()
 generate_table(SVG_DOC_RECORD, 'SvgDocumentRecord')
 print('}')
 print()
+print()
+print('pub mod colr {')
+print('use crate::GlyphId;')
+print('use crate::parser::FromData;')
+print()
+generate_table(COLR_BASE_GLYPH_RECORD, 'BaseGlyphRecord')
+print()
+generate_table(COLR_LAYER_RECORD, 'LayerRecord')
+print('}')
+print()
+print('pub mod cpal {')
+print('use crate::parser::FromData;')
+print()
+generate_table(CPAL_COLOR_RECORD, 'ColorRecord')
+print('}')
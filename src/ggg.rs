@@ -1,35 +1,52 @@
 //! Common types for GDEF, GPOS and GSUB tables.
 
-use crate::GlyphId;
+use crate::{GlyphId, Tag};
 use crate::parser::*;
+use crate::raw::gdef::TagRecord;
 
 
 /// A [Coverage Table](https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#coverage-table).
+///
+/// Supports format 1 (a sorted glyph array) and format 2 (glyph ranges).
+/// Unknown formats do not contain any glyphs.
 #[derive(Clone, Copy, Debug)]
-pub(crate) struct CoverageTable<'a> {
+pub struct CoverageTable<'a> {
     data: &'a [u8],
 }
 
 impl<'a> CoverageTable<'a> {
+    /// Creates a new `CoverageTable` from raw data.
+    #[inline]
     pub fn new(data: &'a [u8]) -> Self {
         CoverageTable { data }
     }
 
+    /// Checks that glyph is present.
+    #[inline]
     pub fn contains(&self, glyph_id: GlyphId) -> bool {
-        let mut s = Stream::new(self.data);
-        let format: u16 = try_opt_or!(s.read(), false);
+        self.coverage_index(glyph_id).is_some()
+    }
 
+    /// Returns the coverage index of a glyph.
+    ///
+    /// Used to index arrays that are parallel to the coverage.
+    pub fn coverage_index(&self, glyph_id: GlyphId) -> Option<u16> {
+        let mut s = Stream::new(self.data);
+        let format: u16 = s.read()?;
         match format {
             1 => {
-                let count = try_opt_or!(s.read::<u16>(), false);
-                s.read_array16::<GlyphId>(count).unwrap().binary_search(&glyph_id).is_some()
+                let count: u16 = s.read()?;
+                let glyphs = s.read_array16::<GlyphId>(count)?;
+                glyphs.binary_search(&glyph_id).map(|(index, _)| index)
             }
             2 => {
-                let count = try_opt_or!(s.read::<u16>(), false);
-                let records = try_opt_or!(s.read_array16::<crate::raw::gdef::RangeRecord>(count), false);
-                records.into_iter().any(|r| r.range().contains(&glyph_id))
+                let count: u16 = s.read()?;
+                let records = s.read_array16::<crate::raw::gdef::RangeRecord>(count)?;
+                let record = records.into_iter().find(|r| r.range().contains(&glyph_id))?;
+                let offset = glyph_id.0 - record.range().start().0;
+                record.start_coverage_index().checked_add(offset)
             }
-            _ => false,
+            _ => None,
         }
     }
 }
@@ -48,17 +65,26 @@ impl FromData for Class {
 
 
 /// A [Class Definition Table](https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#class-definition-table).
-#[derive(Clone, Copy)]
-pub(crate) struct ClassDefinitionTable<'a> {
+///
+/// Supports format 1 (a class array for a continuous glyph range)
+/// and format 2 (class ranges).
+/// Unknown formats assign Class 0 to all glyphs.
+#[derive(Clone, Copy, Debug)]
+pub struct ClassDefinitionTable<'a> {
     data: &'a [u8],
 }
 
 impl<'a> ClassDefinitionTable<'a> {
+    /// Creates a new `ClassDefinitionTable` from raw data.
+    #[inline]
     pub fn new(data: &'a [u8]) -> Self {
         ClassDefinitionTable { data }
     }
 
+    /// Returns a glyph class.
+    ///
     /// Any glyph not included in the range of covered glyph IDs automatically belongs to Class 0.
+    /// Malformed tables are treated the same way.
     pub fn get(&self, glyph_id: GlyphId) -> Class {
         self.get_impl(glyph_id).unwrap_or(Class(0))
     }
@@ -89,3 +115,1021 @@ impl<'a> ClassDefinitionTable<'a> {
         }
     }
 }
+
+
+
+/// A list of glyph IDs.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct GlyphIds<'a> {
+    glyphs: LazyArray16<'a, GlyphId>,
+    index: u16,
+}
+
+impl<'a> GlyphIds<'a> {
+    #[inline]
+    pub(crate) fn new(glyphs: LazyArray16<'a, GlyphId>) -> Self {
+        GlyphIds { glyphs, index: 0 }
+    }
+
+    /// Returns a glyph ID at `index`.
+    #[inline]
+    pub fn get(&self, index: u16) -> Option<GlyphId> {
+        self.glyphs.get(index)
+    }
+
+    /// Returns the number of glyph IDs.
+    #[inline]
+    pub fn len(&self) -> u16 {
+        self.glyphs.len()
+    }
+
+    /// Checks if the list is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+}
+
+impl<'a> Iterator for GlyphIds<'a> {
+    type Item = GlyphId;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let glyph = self.glyphs.get(self.index)?;
+        self.index += 1;
+        Some(glyph)
+    }
+}
+
+
+/// A [Sequence Lookup Record](https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#sequence-lookup-record).
+///
+/// Describes a lookup that should be applied at a specific position
+/// in a matched input sequence.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SequenceLookupRecord {
+    /// An index into the input glyph sequence. The first glyph has index 0.
+    pub sequence_index: u16,
+    /// A lookup to apply at that position.
+    pub lookup_index: LookupIndex,
+}
+
+impl FromData for SequenceLookupRecord {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(SequenceLookupRecord {
+            sequence_index: s.read()?,
+            lookup_index: s.read()?,
+        })
+    }
+}
+
+
+/// A list of sequence lookup records.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct SequenceLookupRecords<'a> {
+    records: LazyArray16<'a, SequenceLookupRecord>,
+    index: u16,
+}
+
+impl<'a> SequenceLookupRecords<'a> {
+    #[inline]
+    pub(crate) fn new(records: LazyArray16<'a, SequenceLookupRecord>) -> Self {
+        SequenceLookupRecords { records, index: 0 }
+    }
+
+    /// Returns a record at `index`.
+    #[inline]
+    pub fn get(&self, index: u16) -> Option<SequenceLookupRecord> {
+        self.records.get(index)
+    }
+
+    /// Returns the number of records.
+    #[inline]
+    pub fn len(&self) -> u16 {
+        self.records.len()
+    }
+
+    /// Checks if the list is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+impl<'a> Iterator for SequenceLookupRecords<'a> {
+    type Item = SequenceLookupRecord;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.records.get(self.index)?;
+        self.index += 1;
+        Some(record)
+    }
+}
+
+
+/// A script index in the `GSUB`/`GPOS` script list.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ScriptIndex(pub u16);
+
+/// A feature index in the `GSUB`/`GPOS` feature list.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FeatureIndex(pub u16);
+
+impl FromData for FeatureIndex {
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        u16::parse(data).map(FeatureIndex)
+    }
+}
+
+/// A lookup index in the `GSUB`/`GPOS` lookup list.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LookupIndex(pub u16);
+
+impl FromData for LookupIndex {
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        u16::parse(data).map(LookupIndex)
+    }
+}
+
+
+/// A common interface for `GSUB` and `GPOS` tables.
+pub trait GlyphPosSubTable<'a> {
+    /// Returns an iterator over scripts.
+    fn scripts(&self) -> Scripts<'a>;
+
+    /// Returns an iterator over features.
+    fn features(&self) -> Features<'a>;
+
+    /// Returns an iterator over lookups.
+    fn lookups(&self) -> Lookups<'a>;
+
+    /// Returns a script with a specified tag.
+    fn script_by_tag(&self, tag: Tag) -> Option<(ScriptIndex, Script<'a>)> {
+        let scripts = self.scripts();
+        let index = scripts.records.find(tag)?;
+        Some((ScriptIndex(index), scripts.get(ScriptIndex(index))?))
+    }
+
+    /// Returns a feature with a specified tag.
+    ///
+    /// When multiple features share a tag, the first one is returned.
+    fn feature_by_tag(&self, tag: Tag) -> Option<(FeatureIndex, Feature<'a>)> {
+        let features = self.features();
+        let index = features.records.find(tag)?;
+        Some((FeatureIndex(index), features.get(FeatureIndex(index))?))
+    }
+}
+
+
+/// A common `GSUB` and `GPOS` header.
+#[derive(Clone, Copy)]
+pub(crate) struct LayoutTable<'a> {
+    pub scripts: Scripts<'a>,
+    pub features: Features<'a>,
+    pub lookups: Lookups<'a>,
+}
+
+impl<'a> LayoutTable<'a> {
+    /// `extension_lookup_type` is 7 for `GSUB` and 9 for `GPOS`.
+    pub fn parse(data: &'a [u8], extension_lookup_type: u16) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let major_version: u16 = s.read()?;
+        let minor_version: u16 = s.read()?;
+        if !(major_version == 1 && (minor_version == 0 || minor_version == 1)) {
+            return None;
+        }
+
+        let script_list_offset: Offset16 = s.read()?;
+        let feature_list_offset: Offset16 = s.read()?;
+        let lookup_list_offset: Offset16 = s.read()?;
+
+        // A zero offset is an empty list.
+        let scripts = match data.get(script_list_offset.to_usize()..) {
+            Some(data) if script_list_offset.0 != 0 => RecordList::parse(data)?,
+            _ => RecordList::default(),
+        };
+
+        let features = match data.get(feature_list_offset.to_usize()..) {
+            Some(data) if feature_list_offset.0 != 0 => RecordList::parse(data)?,
+            _ => RecordList::default(),
+        };
+
+        let lookups = match data.get(lookup_list_offset.to_usize()..) {
+            Some(data) if lookup_list_offset.0 != 0 => {
+                Lookups::parse(data, extension_lookup_type)?
+            }
+            _ => Lookups::default(),
+        };
+
+        Some(LayoutTable {
+            scripts: Scripts { records: scripts, index: 0 },
+            features: Features { records: features, index: 0 },
+            lookups,
+        })
+    }
+}
+
+
+/// A list of tagged records: scripts, languages or features.
+#[derive(Clone, Copy, Default)]
+struct RecordList<'a> {
+    data: &'a [u8],
+    records: LazyArray16<'a, TagRecord>,
+}
+
+impl<'a> RecordList<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let count: u16 = s.read()?;
+        let records = s.read_array16(count)?;
+        Some(RecordList { data, records })
+    }
+
+    fn get(&self, index: u16) -> Option<(Tag, &'a [u8])> {
+        let record = self.records.get(index)?;
+        Some((record.tag(), self.data.get(record.offset().to_usize()..)?))
+    }
+
+    fn find(&self, tag: Tag) -> Option<u16> {
+        // Records must be sorted by tag, but not all fonts follow this.
+        if let Some((mut index, _)) = self.records.binary_search_by_key(&tag, |r| r.tag()) {
+            // Step back to the first record with the same tag.
+            while index > 0 && self.records.get(index - 1)?.tag() == tag {
+                index -= 1;
+            }
+
+            return Some(index);
+        }
+
+        self.records.into_iter().position(|r| r.tag() == tag).map(|i| i as u16)
+    }
+}
+
+
+/// An iterator over [scripts](https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#script-list-table-and-script-record).
+///
+/// Malformed scripts are skipped.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct Scripts<'a> {
+    records: RecordList<'a>,
+    index: u16,
+}
+
+impl<'a> Scripts<'a> {
+    /// Returns the total number of scripts.
+    #[inline]
+    pub fn len(&self) -> u16 {
+        self.records.records.len()
+    }
+
+    /// Checks that the script list is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.records.records.is_empty()
+    }
+
+    /// Returns a script at a specified index.
+    pub fn get(&self, index: ScriptIndex) -> Option<Script<'a>> {
+        let (tag, data) = self.records.get(index.0)?;
+        Script::parse(tag, data)
+    }
+}
+
+impl<'a> Iterator for Scripts<'a> {
+    type Item = Script<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.len() {
+            self.index += 1;
+            if let Some(script) = self.get(ScriptIndex(self.index - 1)) {
+                return Some(script);
+            }
+        }
+
+        None
+    }
+}
+
+
+/// A [Script Table](https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#script-table-and-language-system-record).
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct Script<'a> {
+    tag: Tag,
+    default_language: Option<Language<'a>>,
+    languages: RecordList<'a>,
+}
+
+impl<'a> Script<'a> {
+    fn parse(tag: Tag, data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let default_lang_sys_offset: Option<Offset16> = s.read()?;
+        let languages = RecordList::parse(s.tail()?)?;
+        // Language offsets are from the beginning of the Script table.
+        let languages = RecordList { data, ..languages };
+
+        let default_language = default_lang_sys_offset
+            .and_then(|offset| data.get(offset.to_usize()..))
+            .and_then(|data| Language::parse(Tag::from_bytes(b"dflt"), data));
+
+        Some(Script { tag, default_language, languages })
+    }
+
+    /// Returns script's tag.
+    #[inline]
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// Returns a default language system.
+    #[inline]
+    pub fn default_language(&self) -> Option<Language<'a>> {
+        self.default_language
+    }
+
+    /// Returns a default language system or the first listed one.
+    ///
+    /// Useful when a text language is not specified.
+    /// Returns `None` only when a script has no language systems at all.
+    #[inline]
+    pub fn default_or_first_language(&self) -> Option<Language<'a>> {
+        self.default_language.or_else(|| self.languages().next())
+    }
+
+    /// Returns an iterator over language systems.
+    ///
+    /// The default language system is not included.
+    #[inline]
+    pub fn languages(&self) -> Languages<'a> {
+        Languages { records: self.languages, index: 0 }
+    }
+
+    /// Returns a language system with a specified tag.
+    pub fn language_by_tag(&self, tag: Tag) -> Option<Language<'a>> {
+        let index = self.languages.find(tag)?;
+        let (tag, data) = self.languages.get(index)?;
+        Language::parse(tag, data)
+    }
+}
+
+
+/// An iterator over language systems.
+///
+/// Malformed language systems are skipped.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct Languages<'a> {
+    records: RecordList<'a>,
+    index: u16,
+}
+
+impl<'a> Iterator for Languages<'a> {
+    type Item = Language<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.records.records.len() {
+            self.index += 1;
+            if let Some((tag, data)) = self.records.get(self.index - 1) {
+                if let Some(language) = Language::parse(tag, data) {
+                    return Some(language);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+
+/// A [Language System Table](https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#language-system-table).
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct Language<'a> {
+    tag: Tag,
+    required_feature: Option<FeatureIndex>,
+    features: LazyArray16<'a, FeatureIndex>,
+}
+
+impl<'a> Language<'a> {
+    fn parse(tag: Tag, data: &'a [u8]) -> Option<Self> {
+        const NO_REQUIRED_FEATURE: u16 = 0xFFFF;
+
+        let mut s = Stream::new(data);
+        s.skip::<Offset16>(); // lookupOrderOffset
+        let required_feature: u16 = s.read()?;
+        let count: u16 = s.read()?;
+        let features = s.read_array16(count)?;
+
+        let required_feature = if required_feature != NO_REQUIRED_FEATURE {
+            Some(FeatureIndex(required_feature))
+        } else {
+            None
+        };
+
+        Some(Language { tag, required_feature, features })
+    }
+
+    /// Returns language system's tag.
+    ///
+    /// The default language system has a `dflt` tag.
+    #[inline]
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// Returns a feature required by this language system.
+    #[inline]
+    pub fn required_feature(&self) -> Option<FeatureIndex> {
+        self.required_feature
+    }
+
+    /// Returns an iterator over indices of features used by this language system.
+    #[inline]
+    pub fn features(&self) -> impl Iterator<Item = FeatureIndex> + 'a {
+        self.features.into_iter()
+    }
+}
+
+
+/// An iterator over [features](https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#feature-list-table).
+///
+/// Malformed features are skipped.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct Features<'a> {
+    records: RecordList<'a>,
+    index: u16,
+}
+
+impl<'a> Features<'a> {
+    /// Returns the total number of features.
+    #[inline]
+    pub fn len(&self) -> u16 {
+        self.records.records.len()
+    }
+
+    /// Checks that the feature list is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.records.records.is_empty()
+    }
+
+    /// Returns a feature at a specified index.
+    pub fn get(&self, index: FeatureIndex) -> Option<Feature<'a>> {
+        let (tag, data) = self.records.get(index.0)?;
+        Feature::parse(tag, data)
+    }
+}
+
+impl<'a> Iterator for Features<'a> {
+    type Item = Feature<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.len() {
+            self.index += 1;
+            if let Some(feature) = self.get(FeatureIndex(self.index - 1)) {
+                return Some(feature);
+            }
+        }
+
+        None
+    }
+}
+
+
+/// A [Feature Table](https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#feature-table).
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct Feature<'a> {
+    tag: Tag,
+    lookups: LazyArray16<'a, LookupIndex>,
+    name_id: Option<u16>,
+}
+
+impl<'a> Feature<'a> {
+    fn parse(tag: Tag, data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let params_offset: Option<Offset16> = s.read()?;
+        let count: u16 = s.read()?;
+        let lookups = s.read_array16(count)?;
+
+        let name_id = match params_offset {
+            Some(offset) if is_stylistic_set(tag) => parse_stylistic_set_params(data, offset),
+            _ => None,
+        };
+
+        Some(Feature { tag, lookups, name_id })
+    }
+
+    /// Returns feature's tag.
+    #[inline]
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// Returns an iterator over indices of lookups used by this feature.
+    #[inline]
+    pub fn lookups(&self) -> impl Iterator<Item = LookupIndex> + 'a {
+        self.lookups.into_iter()
+    }
+
+    /// Returns a UI name ID of a stylistic set feature (`ss01`..`ss20`).
+    ///
+    /// Can be resolved via `Font::names()`.
+    ///
+    /// Returns `None` for non-parametric features or when parameters are malformed.
+    #[inline]
+    pub fn name_id(&self) -> Option<u16> {
+        self.name_id
+    }
+}
+
+fn is_stylistic_set(tag: Tag) -> bool {
+    let bytes = tag.to_bytes();
+    if &bytes[0..2] != b"ss" || !bytes[2].is_ascii_digit() || !bytes[3].is_ascii_digit() {
+        return false;
+    }
+
+    let n = (bytes[2] - b'0') * 10 + (bytes[3] - b'0');
+    (1..=20).contains(&n)
+}
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/features_pt#ss01--ss20
+fn parse_stylistic_set_params(data: &[u8], offset: Offset16) -> Option<u16> {
+    let mut s = Stream::new_at(data, offset.to_usize())?;
+    let version: u16 = s.read()?;
+    if version != 0 {
+        return None;
+    }
+
+    s.read()
+}
+
+
+/// An iterator over [lookups](https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#lookup-list-table).
+///
+/// Malformed lookups are skipped.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct Lookups<'a> {
+    data: &'a [u8],
+    offsets: LazyArray16<'a, Offset16>,
+    extension_lookup_type: u16,
+    index: u16,
+}
+
+impl<'a> Lookups<'a> {
+    pub(crate) fn parse(data: &'a [u8], extension_lookup_type: u16) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let count: u16 = s.read()?;
+        let offsets = s.read_array16(count)?;
+        Some(Lookups { data, offsets, extension_lookup_type, index: 0 })
+    }
+
+    /// Returns the total number of lookups.
+    #[inline]
+    pub fn len(&self) -> u16 {
+        self.offsets.len()
+    }
+
+    /// Checks that the lookup list is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns a lookup at a specified index.
+    pub fn get(&self, index: LookupIndex) -> Option<Lookup<'a>> {
+        let offset = self.offsets.get(index.0)?;
+        Lookup::parse(self.data.get(offset.to_usize()..)?, self.extension_lookup_type)
+    }
+}
+
+impl<'a> Iterator for Lookups<'a> {
+    type Item = Lookup<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.offsets.len() {
+            self.index += 1;
+            if let Some(lookup) = self.get(LookupIndex(self.index - 1)) {
+                return Some(lookup);
+            }
+        }
+
+        None
+    }
+}
+
+
+/// A [Lookup Table](https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#lookup-table).
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct Lookup<'a> {
+    data: &'a [u8],
+    lookup_type: u16,
+    lookup_flag: u16,
+    offsets: LazyArray16<'a, Offset16>,
+    mark_filtering_set: Option<u16>,
+    extension_lookup_type: u16,
+}
+
+impl<'a> Lookup<'a> {
+    const USE_MARK_FILTERING_SET_FLAG: u16 = 0x0010;
+    const MARK_ATTACHMENT_TYPE_MASK: u16 = 0xFF00;
+
+    fn parse(data: &'a [u8], extension_lookup_type: u16) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let lookup_type: u16 = s.read()?;
+        let lookup_flag: u16 = s.read()?;
+        let count: u16 = s.read()?;
+        let offsets = s.read_array16(count)?;
+
+        let mut mark_filtering_set = None;
+        if lookup_flag & Self::USE_MARK_FILTERING_SET_FLAG != 0 {
+            mark_filtering_set = Some(s.read()?);
+        }
+
+        Some(Lookup {
+            data,
+            lookup_type,
+            lookup_flag,
+            offsets,
+            mark_filtering_set,
+            extension_lookup_type,
+        })
+    }
+
+    /// Returns the lookup type.
+    ///
+    /// The meaning depends on the table the lookup belongs to (`GSUB` or `GPOS`).
+    #[inline]
+    pub fn lookup_type(&self) -> u16 {
+        self.lookup_type
+    }
+
+    /// Returns raw lookup qualifiers.
+    #[inline]
+    pub fn lookup_flag(&self) -> u16 {
+        self.lookup_flag
+    }
+
+    /// Returns a mark attachment type.
+    ///
+    /// When set, only marks with the same `GDEF` mark attachment class
+    /// (see `Font::glyph_mark_attachment_class`) should be processed by this lookup.
+    #[inline]
+    pub fn mark_attachment_type(&self) -> Option<Class> {
+        match (self.lookup_flag & Self::MARK_ATTACHMENT_TYPE_MASK) >> 8 {
+            0 => None,
+            n => Some(Class(n)),
+        }
+    }
+
+    /// Returns an index into the `GDEF` mark glyph sets.
+    ///
+    /// Present only when the `useMarkFilteringSet` lookup flag is set.
+    #[inline]
+    pub fn mark_filtering_set(&self) -> Option<u16> {
+        self.mark_filtering_set
+    }
+
+    /// Returns an iterator over raw subtables data.
+    ///
+    /// Subtables with an out of bounds offset are skipped.
+    ///
+    /// Extension subtables are returned as is. Use `resolved_subtables()` to unwrap them.
+    #[inline]
+    pub fn subtables(&self) -> LookupSubtables<'a> {
+        LookupSubtables {
+            data: self.data,
+            offsets: self.offsets,
+            index: 0,
+        }
+    }
+
+    /// Returns an iterator over subtables data along with their actual lookup type.
+    ///
+    /// Extension subtables (`GSUB` lookup type 7 and `GPOS` lookup type 9)
+    /// are replaced by the subtables they are pointing to.
+    /// For other lookups, the lookup type is simply the same as `lookup_type()`.
+    ///
+    /// Malformed extension subtables are skipped.
+    #[inline]
+    pub fn resolved_subtables(&self) -> ResolvedSubtables<'a> {
+        ResolvedSubtables {
+            subtables: self.subtables(),
+            lookup_type: self.lookup_type,
+            extension_lookup_type: self.extension_lookup_type,
+        }
+    }
+
+    /// Returns an iterator over subtables of the specified type, including extension ones.
+    #[inline]
+    pub(crate) fn subtables_of_type(&self, lookup_type: u16) -> impl Iterator<Item = &'a [u8]> {
+        self.resolved_subtables()
+            .filter(move |(kind, _)| *kind == lookup_type)
+            .map(|(_, data)| data)
+    }
+}
+
+
+/// An iterator over lookup subtables data.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct LookupSubtables<'a> {
+    data: &'a [u8],
+    offsets: LazyArray16<'a, Offset16>,
+    index: u16,
+}
+
+impl<'a> Iterator for LookupSubtables<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.offsets.len() {
+            let offset = self.offsets.get(self.index)?;
+            self.index += 1;
+            if let Some(data) = self.data.get(offset.to_usize()..) {
+                return Some(data);
+            }
+        }
+
+        None
+    }
+}
+
+
+/// An iterator over lookup subtables with extension subtables resolved.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct ResolvedSubtables<'a> {
+    subtables: LookupSubtables<'a>,
+    lookup_type: u16,
+    extension_lookup_type: u16,
+}
+
+impl<'a> Iterator for ResolvedSubtables<'a> {
+    type Item = (u16, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // A zero extension type is used by lookups without a parent table.
+        if self.extension_lookup_type == 0 || self.lookup_type != self.extension_lookup_type {
+            return self.subtables.next().map(|data| (self.lookup_type, data));
+        }
+
+        for data in &mut self.subtables {
+            if let Some(v) = parse_extension_subtable(data, self.extension_lookup_type) {
+                return Some(v);
+            }
+        }
+
+        None
+    }
+}
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/gsub#ES
+fn parse_extension_subtable(data: &[u8], extension_lookup_type: u16) -> Option<(u16, &[u8])> {
+    let mut s = Stream::new(data);
+    let format: u16 = s.read()?;
+    if format != 1 {
+        return None;
+    }
+
+    let lookup_type: u16 = s.read()?;
+    // An extension cannot point to another extension.
+    if lookup_type == extension_lookup_type {
+        return None;
+    }
+
+    let offset: Offset32 = s.read()?;
+    Some((lookup_type, data.get(offset.to_usize()..)?))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn coverage_format1() {
+        let data = writer::convert(&[
+            UInt16(1), // coverageFormat
+            UInt16(3), // glyphCount
+            UInt16(2), // glyphArray [0]
+            UInt16(7), // glyphArray [1]
+            UInt16(9), // glyphArray [2]
+        ]);
+
+        let table = CoverageTable::new(&data);
+        assert_eq!(table.coverage_index(GlyphId(2)), Some(0));
+        assert_eq!(table.coverage_index(GlyphId(9)), Some(2));
+        assert_eq!(table.coverage_index(GlyphId(8)), None);
+        assert!(table.contains(GlyphId(7)));
+        assert!(!table.contains(GlyphId(1)));
+    }
+
+    #[test]
+    fn coverage_format2() {
+        let data = writer::convert(&[
+            UInt16(2), // coverageFormat
+            UInt16(2), // rangeCount
+            UInt16(5), // startGlyphID
+            UInt16(7), // endGlyphID
+            UInt16(0), // startCoverageIndex
+            UInt16(20), // startGlyphID
+            UInt16(21), // endGlyphID
+            UInt16(3), // startCoverageIndex
+        ]);
+
+        let table = CoverageTable::new(&data);
+        assert_eq!(table.coverage_index(GlyphId(5)), Some(0));
+        assert_eq!(table.coverage_index(GlyphId(7)), Some(2));
+        assert_eq!(table.coverage_index(GlyphId(21)), Some(4));
+        assert_eq!(table.coverage_index(GlyphId(8)), None);
+    }
+
+    #[test]
+    fn coverage_truncated() {
+        let data = writer::convert(&[
+            UInt16(1), // coverageFormat
+            UInt16(3), // glyphCount
+            UInt16(2), // glyphArray [0]
+        ]);
+
+        assert!(!CoverageTable::new(&data).contains(GlyphId(2)));
+    }
+
+    #[test]
+    fn class_definition_format1() {
+        let data = writer::convert(&[
+            UInt16(1), // classFormat
+            UInt16(10), // startGlyphID
+            UInt16(2), // glyphCount
+            UInt16(3), // classValueArray [0]
+            UInt16(4), // classValueArray [1]
+        ]);
+
+        let table = ClassDefinitionTable::new(&data);
+        assert_eq!(table.get(GlyphId(9)), Class(0));
+        assert_eq!(table.get(GlyphId(10)), Class(3));
+        assert_eq!(table.get(GlyphId(11)), Class(4));
+        assert_eq!(table.get(GlyphId(12)), Class(0));
+    }
+
+    #[test]
+    fn class_definition_format2() {
+        let data = writer::convert(&[
+            UInt16(2), // classFormat
+            UInt16(2), // classRangeCount
+            UInt16(5), // startGlyphID
+            UInt16(7), // endGlyphID
+            UInt16(1), // class
+            UInt16(20), // startGlyphID
+            UInt16(20), // endGlyphID
+            UInt16(2), // class
+        ]);
+
+        let table = ClassDefinitionTable::new(&data);
+        assert_eq!(table.get(GlyphId(4)), Class(0));
+        assert_eq!(table.get(GlyphId(6)), Class(1));
+        assert_eq!(table.get(GlyphId(20)), Class(2));
+        assert_eq!(table.get(GlyphId(21)), Class(0));
+    }
+
+    #[test]
+    fn class_definition_malformed() {
+        let data = writer::convert(&[
+            UInt16(3), // classFormat
+        ]);
+
+        assert_eq!(ClassDefinitionTable::new(&data).get(GlyphId(0)), Class(0));
+    }
+
+    #[test]
+    fn lookups() {
+        let data = writer::convert(&[
+            UInt16(2), // lookupCount
+            UInt16(6), // lookupOffset [0]
+            UInt16(18), // lookupOffset [1]
+            // Lookup 0
+            UInt16(1), // lookupType
+            UInt16(0x0011), // lookupFlag: rightToLeft | useMarkFilteringSet
+            UInt16(2), // subTableCount
+            UInt16(10), // subtableOffset [0]
+            UInt16(100), // subtableOffset [1], out of bounds
+            UInt16(3), // markFilteringSet
+            // Lookup 1
+            UInt16(4), // lookupType
+            UInt16(0x0200), // lookupFlag: markAttachmentType 2
+            UInt16(0), // subTableCount
+        ]);
+
+        let lookups = Lookups::parse(&data, 7).unwrap();
+        assert_eq!(lookups.len(), 2);
+        assert_eq!(lookups.count(), 2);
+
+        let lookup = lookups.get(LookupIndex(0)).unwrap();
+        assert_eq!(lookup.lookup_type(), 1);
+        assert_eq!(lookup.lookup_flag(), 0x0011);
+        assert_eq!(lookup.mark_filtering_set(), Some(3));
+        assert_eq!(lookup.mark_attachment_type(), None);
+        assert_eq!(lookup.subtables().count(), 1);
+        assert_eq!(lookup.subtables().next().unwrap(), &data[16..]);
+
+        let lookup = lookups.get(LookupIndex(1)).unwrap();
+        assert_eq!(lookup.lookup_type(), 4);
+        assert_eq!(lookup.mark_filtering_set(), None);
+        assert_eq!(lookup.mark_attachment_type(), Some(Class(2)));
+        assert_eq!(lookup.subtables().count(), 0);
+
+        assert!(lookups.get(LookupIndex(2)).is_none());
+    }
+
+    #[test]
+    fn extension_subtables() {
+        let data = writer::convert(&[
+            UInt16(1), // lookupCount
+            UInt16(4), // lookupOffset [0]
+            // Lookup 0
+            UInt16(7), // lookupType
+            UInt16(0), // lookupFlag
+            UInt16(3), // subTableCount
+            UInt16(12), // subtableOffset [0]
+            UInt16(20), // subtableOffset [1]
+            UInt16(28), // subtableOffset [2]
+            // Extension subtable 0
+            UInt16(1), // substFormat
+            UInt16(4), // extensionLookupType
+            UInt32(24), // extensionOffset
+            // Extension subtable 1
+            UInt16(1), // substFormat
+            UInt16(7), // extensionLookupType, nested extensions are not allowed
+            UInt32(0), // extensionOffset
+            // Extension subtable 2
+            UInt16(1), // substFormat
+            UInt16(4), // extensionLookupType
+            UInt32(8), // extensionOffset
+            // Subtable
+            UInt16(1), // substFormat
+        ]);
+
+        let lookups = Lookups::parse(&data, 7).unwrap();
+        let lookup = lookups.get(LookupIndex(0)).unwrap();
+        assert_eq!(lookup.lookup_type(), 7);
+        assert_eq!(lookup.subtables().count(), 3);
+
+        let subtables: std::vec::Vec<_> = lookup.resolved_subtables().collect();
+        assert_eq!(subtables, &[(4, &data[40..]), (4, &data[40..])]);
+
+        // A regular lookup in a different table.
+        let lookups = Lookups::parse(&data, 9).unwrap();
+        let lookup = lookups.get(LookupIndex(0)).unwrap();
+        assert_eq!(lookup.resolved_subtables().count(), 3);
+        assert!(lookup.resolved_subtables().all(|(kind, _)| kind == 7));
+    }
+
+    #[test]
+    fn stylistic_set_name_id() {
+        let data = writer::convert(&[
+            UInt16(6), // featureParamsOffset
+            UInt16(1), // lookupIndexCount
+            UInt16(3), // lookupListIndices [0]
+            // FeatureParams
+            UInt16(0), // version
+            UInt16(256), // UINameID
+        ]);
+
+        let feature = Feature::parse(Tag::from_bytes(b"ss01"), &data).unwrap();
+        assert_eq!(feature.name_id(), Some(256));
+        assert_eq!(feature.lookups().collect::<std::vec::Vec<_>>(), &[LookupIndex(3)]);
+
+        let feature = Feature::parse(Tag::from_bytes(b"ss21"), &data).unwrap();
+        assert_eq!(feature.name_id(), None);
+
+        let feature = Feature::parse(Tag::from_bytes(b"liga"), &data).unwrap();
+        assert_eq!(feature.name_id(), None);
+    }
+
+    #[test]
+    fn feature_without_params() {
+        let data = writer::convert(&[
+            UInt16(0), // featureParamsOffset
+            UInt16(0), // lookupIndexCount
+        ]);
+
+        let feature = Feature::parse(Tag::from_bytes(b"ss01"), &data).unwrap();
+        assert_eq!(feature.name_id(), None);
+    }
+}
@@ -26,6 +26,8 @@ A high-level, safe, zero-allocation TrueType font parser.
 ## Error handling
 
 `ttf-parser` is designed to parse well-formed fonts, so it does not have an `Error` enum.
+Detailed errors are reported only by the opt-in validation methods,
+like `Font::validate()` and `Font::validate_cff_glyph()`.
 It doesn't mean that it will crash or panic on malformed fonts, only that the
 error handling will boil down to `Option::None`. So you will not get a detailed cause of an error.
 By doing so we can simplify an API quite a lot since otherwise, we will have to use
@@ -47,6 +49,7 @@ extern crate std;
 #[cfg(feature = "std")]
 use std::string::String;
 
+use core::convert::TryFrom;
 use core::fmt;
 use core::num::NonZeroU16;
 
@@ -60,6 +63,7 @@ macro_rules! try_opt_or {
 }
 
 mod ggg;
+mod outline;
 mod parser;
 mod raw;
 mod tables;
@@ -69,13 +73,26 @@ mod var_store;
 mod writer;
 
 use tables::*;
-use parser::{Stream, FromData, Offset, NumFrom, TryNumFrom, i16_bound, f32_bound};
+use parser::{Stream, FromData, Offset, LazyArray16, NumFrom, TryNumFrom, i16_bound, f32_bound, f32_round};
 use head::IndexToLocationFormat;
+use outline::ContourCounter;
+pub use cff::{CFFError, CffBlueZones, CffSettings};
+pub use cmap::{Codepoints, GlyphIndexCursor, GlyphVariationResult};
+pub use colr::ColorLayers;
+pub use cvar::CvtDeltas;
 pub use fvar::{VariationAxes, VariationAxis};
-pub use gdef::GlyphClass;
+pub use gdef::{GlyphClass, LigatureCarets};
+pub use glyf::{GlyphPoint, GlyphPoints};
+pub use gpos::{PositioningTable, ValueRecord};
+pub use gsub::SubstitutionTable;
+pub use head::MacStyle;
+pub use hmtx::GlyphAdvances;
+pub use vorg::GlyphYOrigins;
 pub use ggg::*;
 pub use name::*;
 pub use os2::*;
+pub use outline::*;
+pub use sbix::{RasterStrike, RasterStrikes};
 
 
 /// A type-safe wrapper for glyph ID.
@@ -263,6 +280,30 @@ impl core::fmt::Display for Tag {
     }
 }
 
+impl core::str::FromStr for Tag {
+    type Err = ();
+
+    /// Creates a `Tag` from a string.
+    ///
+    /// Strings shorter than 4 bytes are padded with spaces.
+    ///
+    /// Returns an error when a string is empty, longer than 4 bytes
+    /// or contains non-printable ASCII characters.
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.as_bytes();
+        if bytes.is_empty() || bytes.len() > 4 {
+            return Err(());
+        }
+
+        if !bytes.iter().all(|b| (0x20..=0x7E).contains(b)) {
+            return Err(());
+        }
+
+        Ok(Tag::from_bytes_lossy(bytes))
+    }
+}
+
 impl FromData for Tag {
     #[inline]
     fn parse(data: &[u8]) -> Option<Self> {
@@ -286,6 +327,46 @@ pub struct LineMetrics {
 }
 
 
+/// Font's line layout metrics.
+///
+/// Returned by `Font::horizontal_line_metrics()` and `Font::vertical_line_metrics()`.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LayoutMetrics {
+    /// Ascender.
+    pub ascender: i16,
+
+    /// Descender. Usually negative.
+    pub descender: i16,
+
+    /// Line gap.
+    pub line_gap: i16,
+}
+
+
+/// A mismatch between the number of glyphs in `maxp` and the one implied by another table.
+///
+/// Returned by `Font::validate()`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GlyphCountMismatch {
+    /// A table tag.
+    pub table: Tag,
+
+    /// The number of glyphs in `maxp`.
+    pub expected: u16,
+
+    /// The number of glyphs implied by the table size.
+    pub actual: u32,
+}
+
+impl fmt::Display for GlyphCountMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the '{}' table has data for {} glyphs, while 'maxp' declares {}",
+               self.table, self.actual, self.expected)
+    }
+}
+
+
 /// A rectangle.
 #[repr(C)]
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -312,6 +393,19 @@ impl Rect {
 }
 
 
+/// A glyph outline metrics.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct OutlineMetrics {
+    /// A tight glyph bounding box.
+    pub bbox: Rect,
+
+    /// The number of contours.
+    ///
+    /// Each contour starts with a MoveTo segment.
+    pub contours: u16,
+}
+
+
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct BBox {
     x_min: f32,
@@ -347,8 +441,17 @@ impl BBox {
         self.y_max = self.y_max.max(y);
     }
 
+    /// Converts a bounding box into a `Rect`.
+    ///
+    /// Returns `None` when no points were added or when coordinates
+    /// do not fit into `i16`.
     #[inline]
     fn to_rect(&self) -> Option<Rect> {
+        // Sentinel values should never be casted.
+        if self.is_default() {
+            return None;
+        }
+
         Some(Rect {
             x_min: i16::try_num_from(self.x_min)?,
             y_min: i16::try_num_from(self.y_min)?,
@@ -378,17 +481,27 @@ pub trait OutlineBuilder {
     /// Appends a ClosePath segment.
     ///
     /// End of a contour.
+    ///
+    /// All contours are closed. `glyf` contours are closed by definition,
+    /// and `CFF`/`CFF2` charstrings do not have a closepath operator at all,
+    /// so a contour is always closed by the next moveto or by endchar.
     fn close(&mut self);
 }
 
 
-struct DummyOutline;
-impl OutlineBuilder for DummyOutline {
-    fn move_to(&mut self, _: f32, _: f32) {}
-    fn line_to(&mut self, _: f32, _: f32) {}
-    fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {}
-    fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {}
-    fn close(&mut self) {}
+/// A trait for receiving stem hints.
+///
+/// Only the `CFF` table provides hints this way.
+pub trait HintBuilder {
+    /// Appends a horizontal stem.
+    ///
+    /// Stem edges are `y` and `y + dy`.
+    fn horizontal_stem(&mut self, y: f32, dy: f32);
+
+    /// Appends a vertical stem.
+    ///
+    /// Stem edges are `x` and `x + dx`.
+    fn vertical_stem(&mut self, x: f32, dx: f32);
 }
 
 
@@ -445,13 +558,18 @@ pub struct GlyphImage<'a> {
 pub enum TableName {
     AxisVariations = 0,
     CharacterToGlyphIndexMapping,
+    Color,
     ColorBitmapData,
     ColorBitmapLocation,
+    ColorPalette,
     CompactFontFormat,
     CompactFontFormat2,
+    ControlValueVariations,
     FontVariations,
     GlyphData,
     GlyphDefinition,
+    GlyphPositioning,
+    GlyphSubstitution,
     GlyphVariations,
     Header,
     HorizontalHeader,
@@ -504,9 +622,14 @@ pub struct Font<'a> {
     cff_: Option<cff::Metadata<'a>>,
     cff2: Option<cff2::Metadata<'a>>,
     cmap: Option<cmap::Table<'a>>,
+    colr: Option<colr::Table<'a>>,
+    cpal: Option<cpal::Table<'a>>,
+    cvar: Option<cvar::Table<'a>>,
     fvar: Option<fvar::Table<'a>>,
     gdef: Option<gdef::Table<'a>>,
     glyf: Option<&'a [u8]>,
+    gpos: Option<PositioningTable<'a>>,
+    gsub: Option<SubstitutionTable<'a>>,
     gvar: Option<gvar::Table<'a>>,
     head: &'a [u8],
     hhea: &'a [u8],
@@ -514,6 +637,7 @@ pub struct Font<'a> {
     hvar: Option<hvar::Table<'a>>,
     kern: Option<&'a [u8]>,
     loca: Option<loca::Table<'a>>,
+    maxp: &'a [u8],
     mvar: Option<mvar::Table<'a>>,
     name: Option<name::Names<'a>>,
     os_2: Option<os2::Table<'a>>,
@@ -524,8 +648,10 @@ pub struct Font<'a> {
     svg_: Option<&'a [u8]>,
     vorg: Option<vorg::Table<'a>>,
     vvar: Option<hvar::Table<'a>>,
+    tables: LazyArray16<'a, raw::TableRecord>,
     number_of_glyphs: NonZeroU16,
     coordinates: VarCoords,
+    cff_settings: CffSettings,
 }
 
 impl<'a> Font<'a> {
@@ -583,9 +709,14 @@ impl<'a> Font<'a> {
             cff_: None,
             cff2: None,
             cmap: None,
+            colr: None,
+            cpal: None,
+            cvar: None,
             fvar: None,
             gdef: None,
             glyf: None,
+            gpos: None,
+            gsub: None,
             gvar: None,
             head: &[],
             hhea: &[],
@@ -593,6 +724,7 @@ impl<'a> Font<'a> {
             hvar: None,
             kern: None,
             loca: None,
+            maxp: &[],
             mvar: None,
             name: None,
             os_2: None,
@@ -603,19 +735,26 @@ impl<'a> Font<'a> {
             svg_: None,
             vorg: None,
             vvar: None,
+            tables,
             number_of_glyphs: NonZeroU16::new(1).unwrap(), // dummy
             coordinates: VarCoords::default(),
+            cff_settings: CffSettings::default(),
         };
 
         let mut number_of_glyphs = None;
         let mut hmtx = None;
         let mut vmtx = None;
         let mut loca = None;
+        let mut cvt = None;
+        let mut cvar = None;
 
         for table in tables {
             let offset = table.offset().to_usize();
             let length = usize::num_from(table.length());
-            let range = offset..(offset + length);
+            let range = match table_range(offset, length) {
+                Some(range) => range,
+                None => continue,
+            };
 
             match &table.table_tag().to_bytes() {
                 b"CBDT" => font.cbdt = data.get(range),
@@ -623,13 +762,19 @@ impl<'a> Font<'a> {
                 b"CFF " => font.cff_ = data.get(range).and_then(|data| cff::parse_metadata(data)),
                 b"CFF2" => font.cff2 = data.get(range).and_then(|data| cff2::parse_metadata(data)),
                 b"GDEF" => font.gdef = data.get(range).and_then(|data| gdef::Table::parse(data)),
+                b"GPOS" => font.gpos = data.get(range).and_then(|data| PositioningTable::parse(data)),
+                b"GSUB" => font.gsub = data.get(range).and_then(|data| SubstitutionTable::parse(data)),
                 b"HVAR" => font.hvar = data.get(range).and_then(|data| hvar::Table::parse(data)),
                 b"MVAR" => font.mvar = data.get(range).and_then(|data| mvar::Table::parse(data)),
                 b"OS/2" => font.os_2 = data.get(range).and_then(|data| os2::Table::parse(data)),
+                b"COLR" => font.colr = data.get(range).and_then(|data| colr::Table::parse(data)),
+                b"CPAL" => font.cpal = data.get(range).and_then(|data| cpal::Table::parse(data)),
                 b"SVG " => font.svg_ = data.get(range),
                 b"VORG" => font.vorg = data.get(range).and_then(|data| vorg::Table::parse(data)),
                 b"VVAR" => font.vvar = data.get(range).and_then(|data| hvar::Table::parse(data)),
                 b"avar" => font.avar = data.get(range).and_then(|data| avar::Table::parse(data)),
+                b"cvar" => cvar = data.get(range),
+                b"cvt " => cvt = data.get(range),
                 b"cmap" => font.cmap = data.get(range).and_then(|data| cmap::Table::parse(data)),
                 b"fvar" => font.fvar = data.get(range).and_then(|data| fvar::Table::parse(data)),
                 b"glyf" => font.glyf = data.get(range),
@@ -639,7 +784,10 @@ impl<'a> Font<'a> {
                 b"hmtx" => hmtx = data.get(range),
                 b"kern" => font.kern = data.get(range),
                 b"loca" => loca = data.get(range),
-                b"maxp" => number_of_glyphs = data.get(range).and_then(|data| maxp::parse(data)),
+                b"maxp" => {
+                    font.maxp = data.get(range).unwrap_or_default();
+                    number_of_glyphs = maxp::parse(font.maxp);
+                }
                 b"name" => font.name = data.get(range).and_then(|data| name::parse(data)),
                 b"post" => font.post = data.get(range).and_then(|data| post::Table::parse(data)),
                 b"sbix" => font.sbix = data.get(range),
@@ -677,9 +825,31 @@ impl<'a> Font<'a> {
             }
         }
 
+        if let Some(data) = cvar {
+            // 'The number of control values is determined by the length of the table.'
+            let cvt_len = cvt.map(|data| data.len() / 2).unwrap_or(0);
+            let cvt_len = u16::try_from(cvt_len).unwrap_or(core::u16::MAX);
+            font.cvar = cvar::Table::parse(data, cvt_len);
+        }
+
         Some(font)
     }
 
+    /// Creates a `Font` object from a font collection.
+    ///
+    /// Unlike `from_data()`, returns `None` when `data` is not a TrueType font collection.
+    /// Also returns `None` when `index` is out of range, which can be checked
+    /// beforehand using `fonts_in_collection()`.
+    #[inline]
+    pub fn from_collection(data: &'a [u8], index: u32) -> Option<Self> {
+        let number_of_fonts = fonts_in_collection(data)?;
+        if index < number_of_fonts {
+            Self::from_data(data, index)
+        } else {
+            None
+        }
+    }
+
     /// Checks that font has a specified table.
     ///
     /// Will return `true` only for tables that were successfully parsed.
@@ -691,13 +861,18 @@ impl<'a> Font<'a> {
             TableName::MaximumProfile               => true,
             TableName::AxisVariations               => self.avar.is_some(),
             TableName::CharacterToGlyphIndexMapping => self.cmap.is_some(),
+            TableName::Color                        => self.colr.is_some(),
             TableName::ColorBitmapData              => self.cbdt.is_some(),
             TableName::ColorBitmapLocation          => self.cblc.is_some(),
+            TableName::ColorPalette                 => self.cpal.is_some(),
             TableName::CompactFontFormat            => self.cff_.is_some(),
             TableName::CompactFontFormat2           => self.cff2.is_some(),
+            TableName::ControlValueVariations       => self.cvar.is_some(),
             TableName::FontVariations               => self.fvar.is_some(),
             TableName::GlyphData                    => self.glyf.is_some(),
             TableName::GlyphDefinition              => self.gdef.is_some(),
+            TableName::GlyphPositioning             => self.gpos.is_some(),
+            TableName::GlyphSubstitution            => self.gsub.is_some(),
             TableName::GlyphVariations              => self.gvar.is_some(),
             TableName::HorizontalMetrics            => self.hmtx.is_some(),
             TableName::HorizontalMetricsVariations  => self.hvar.is_some(),
@@ -716,6 +891,59 @@ impl<'a> Font<'a> {
         }
     }
 
+    /// Returns an iterator over the font's table directory.
+    ///
+    /// Yields tags and lengths of all tables stored in the font,
+    /// including unknown and malformed ones.
+    #[inline]
+    pub fn tables(&self) -> impl Iterator<Item = (Tag, u32)> + 'a {
+        self.tables.into_iter().map(|record| (record.table_tag(), record.length()))
+    }
+
+    /// Checks that the `loca` and `hmtx` table sizes match the number of glyphs in `maxp`.
+    ///
+    /// `Font::from_data()` doesn't do this to keep parsing cheap,
+    /// so a font with extra data in those tables is still accepted,
+    /// while a truncated table is skipped.
+    pub fn validate(&self) -> Result<(), GlyphCountMismatch> {
+        let expected = self.number_of_glyphs.get();
+        for (tag, length) in self.tables() {
+            let actual = match &tag.to_bytes() {
+                b"loca" => {
+                    let entry_size = match head::index_to_loc_format(self.head) {
+                        Some(IndexToLocationFormat::Short) => 2,
+                        Some(IndexToLocationFormat::Long) => 4,
+                        None => continue,
+                    };
+
+                    // 'loca' has an extra offset for the end of the last glyph.
+                    (length / entry_size).saturating_sub(1)
+                }
+                b"hmtx" => {
+                    let number_of_h_metrics = match hhea::number_of_h_metrics(self.hhea) {
+                        Some(n) => u32::from(n.get()),
+                        None => continue,
+                    };
+
+                    // Long metrics are followed by left side bearings of the remaining glyphs.
+                    let metrics_len = number_of_h_metrics * 4;
+                    if length >= metrics_len {
+                        number_of_h_metrics + (length - metrics_len) / 2
+                    } else {
+                        length / 4
+                    }
+                }
+                _ => continue,
+            };
+
+            if actual != u32::from(expected) {
+                return Err(GlyphCountMismatch { table: tag, expected, actual });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Returns an iterator over [Name Records].
     ///
     /// An iterator can be empty.
@@ -726,6 +954,26 @@ impl<'a> Font<'a> {
         self.name.unwrap_or_default()
     }
 
+    /// Returns the best name record with the specified
+    /// [Name ID](https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-ids).
+    ///
+    /// A shorthand for `names().get(name_id)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ttf_parser::{Font, name_id};
+    ///
+    /// let data = std::fs::read("fonts/SourceSansPro-Regular-Tiny.ttf").unwrap();
+    /// let font = Font::from_data(&data, 0).unwrap();
+    /// let name = font.name(name_id::FULL_NAME).unwrap();
+    /// assert_eq!(name.name_utf8(), Some("Source Sans Pro".to_string()));
+    /// ```
+    #[inline]
+    pub fn name(&self, name_id: u16) -> Option<Name<'a>> {
+        self.name.and_then(|names| names.get(name_id))
+    }
+
     /// Returns font's family name.
     ///
     /// *Typographic Family* is preferred over *Family*.
@@ -736,21 +984,10 @@ impl<'a> Font<'a> {
     #[cfg(feature = "std")]
     #[inline]
     pub fn family_name(&self) -> Option<String> {
-        let mut idx = None;
-        let mut iter = self.names();
-        for (i, name) in iter.enumerate() {
-            if name.name_id() == name_id::TYPOGRAPHIC_FAMILY && name.is_unicode() {
-                // Break the loop as soon as we reached 'Typographic Family'.
-                idx = Some(i);
-                break;
-            } else if name.name_id() == name_id::FAMILY && name.is_unicode() {
-                idx = Some(i);
-                // Do not break the loop since 'Typographic Family' can be set later
-                // and it has a higher priority.
-            }
-        }
-
-        iter.nth(idx?).and_then(|name| name.name_from_utf16_be())
+        // 'Typographic Family' has a higher priority.
+        self.name(name_id::TYPOGRAPHIC_FAMILY)
+            .or_else(|| self.name(name_id::FAMILY))
+            .and_then(|name| name.name_utf8())
     }
 
     /// Returns font's PostScript name.
@@ -761,9 +998,7 @@ impl<'a> Font<'a> {
     #[cfg(feature = "std")]
     #[inline]
     pub fn post_script_name(&self) -> Option<String> {
-        self.names()
-            .find(|name| name.name_id() == name_id::POST_SCRIPT_NAME && name.is_unicode())
-            .and_then(|name| name.name_from_utf16_be())
+        self.name(name_id::POST_SCRIPT_NAME).and_then(|name| name.name_utf8())
     }
 
     /// Checks that font is marked as *Regular*.
@@ -798,6 +1033,24 @@ impl<'a> Font<'a> {
         try_opt_or!(self.os_2, false).is_oblique()
     }
 
+    /// Checks that font prefers typographic metrics over `hhea` ones.
+    ///
+    /// Ascender, descender and line gap will be taken from the OS/2 table in this case.
+    ///
+    /// Returns `false` when OS/2 table is not present or when its version is < 4.
+    #[inline]
+    pub fn use_typo_metrics(&self) -> bool {
+        try_opt_or!(self.os_2, false).is_use_typo_metrics()
+    }
+
+    /// Checks that font is marked as *Monospaced*.
+    ///
+    /// Returns `false` when `post` table is not present.
+    #[inline]
+    pub fn is_monospaced(&self) -> bool {
+        try_opt_or!(self.post, false).is_monospaced()
+    }
+
     /// Checks that font is variable.
     ///
     /// Simply checks the presence of a `fvar` table.
@@ -817,14 +1070,30 @@ impl<'a> Font<'a> {
 
     /// Returns font's width.
     ///
-    /// Returns `Width::Normal` when OS/2 table is not present or when value is invalid.
+    /// Returns `Width::Normal` when OS/2 table is not present.
     #[inline]
     pub fn width(&self) -> Width {
         try_opt_or!(self.os_2, Width::default()).width()
     }
 
+    /// Returns Unicode ranges declared by the font.
+    ///
+    /// Returns an empty set when OS/2 table is not present.
     #[inline]
-    fn use_typo_metrics(&self) -> Option<os2::Table> {
+    pub fn unicode_ranges(&self) -> UnicodeRanges {
+        try_opt_or!(self.os_2, UnicodeRanges::default()).unicode_ranges()
+    }
+
+    /// Returns code pages declared by the font.
+    ///
+    /// Returns an empty set when OS/2 table is not present or when its version is < 1.
+    #[inline]
+    pub fn code_pages(&self) -> CodePages {
+        try_opt_or!(self.os_2, CodePages::default()).code_pages()
+    }
+
+    #[inline]
+    fn typo_metrics_table(&self) -> Option<os2::Table> {
         self.os_2.filter(|table| table.is_use_typo_metrics())
     }
 
@@ -833,7 +1102,7 @@ impl<'a> Font<'a> {
     /// This method is affected by variation axes.
     #[inline]
     pub fn ascender(&self) -> i16 {
-        if let Some(os_2) = self.use_typo_metrics() {
+        if let Some(os_2) = self.typo_metrics_table() {
             let v = os_2.typo_ascender();
             self.apply_metrics_variation(Tag::from_bytes(b"hasc"), v)
         } else {
@@ -846,7 +1115,7 @@ impl<'a> Font<'a> {
     /// This method is affected by variation axes.
     #[inline]
     pub fn descender(&self) -> i16 {
-        if let Some(os_2) = self.use_typo_metrics() {
+        if let Some(os_2) = self.typo_metrics_table() {
             let v = os_2.typo_descender();
             self.apply_metrics_variation(Tag::from_bytes(b"hdsc"), v)
         } else {
@@ -867,7 +1136,7 @@ impl<'a> Font<'a> {
     /// This method is affected by variation axes.
     #[inline]
     pub fn line_gap(&self) -> i16 {
-        if let Some(os_2) = self.use_typo_metrics() {
+        if let Some(os_2) = self.typo_metrics_table() {
             let v = os_2.typo_line_gap();
             self.apply_metrics_variation(Tag::from_bytes(b"hlgp"), v)
         } else {
@@ -875,6 +1144,20 @@ impl<'a> Font<'a> {
         }
     }
 
+    /// Returns horizontal ascender, descender and line gap.
+    ///
+    /// A shorthand for `ascender()`, `descender()` and `line_gap()`.
+    ///
+    /// This method is affected by variation axes.
+    #[inline]
+    pub fn horizontal_line_metrics(&self) -> LayoutMetrics {
+        LayoutMetrics {
+            ascender: self.ascender(),
+            descender: self.descender(),
+            line_gap: self.line_gap(),
+        }
+    }
+
     // TODO: does this affected by USE_TYPO_METRICS?
 
     /// Returns a vertical font ascender.
@@ -912,6 +1195,79 @@ impl<'a> Font<'a> {
             .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"vlgp"), v))
     }
 
+    /// Returns vertical ascender, descender and line gap.
+    ///
+    /// A shorthand for `vertical_ascender()`, `vertical_descender()`
+    /// and `vertical_line_gap()`.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when `vhea` table is not present.
+    #[inline]
+    pub fn vertical_line_metrics(&self) -> Option<LayoutMetrics> {
+        Some(LayoutMetrics {
+            ascender: self.vertical_ascender()?,
+            descender: self.vertical_descender()?,
+            line_gap: self.vertical_line_gap()?,
+        })
+    }
+
+    /// Returns font's style bits from the `head` table.
+    ///
+    /// Unlike `is_bold()` and `is_italic()`, doesn't depend on the `OS/2` table.
+    #[inline]
+    pub fn mac_style(&self) -> MacStyle {
+        head::mac_style(self.head).unwrap_or_default()
+    }
+
+    /// Returns raw `head` table flags.
+    #[inline]
+    pub fn head_flags(&self) -> u16 {
+        head::flags(self.head).unwrap_or(0)
+    }
+
+    /// Returns font's creation time from the `head` table.
+    ///
+    /// The value is in seconds since 12:00 midnight, January 1, 1904, UTC.
+    #[inline]
+    pub fn created(&self) -> i64 {
+        head::created(self.head).unwrap_or(0)
+    }
+
+    /// Returns font's modification time from the `head` table.
+    ///
+    /// The value is in seconds since 12:00 midnight, January 1, 1904, UTC.
+    #[inline]
+    pub fn modified(&self) -> i64 {
+        head::modified(self.head).unwrap_or(0)
+    }
+
+    /// Returns font's creation time as a `SystemTime`.
+    ///
+    /// Returns `None` when the time cannot be represented by the `SystemTime`.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn created_time(&self) -> Option<std::time::SystemTime> {
+        head::to_system_time(self.created())
+    }
+
+    /// Returns font's modification time as a `SystemTime`.
+    ///
+    /// Returns `None` when the time cannot be represented by the `SystemTime`.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn modified_time(&self) -> Option<std::time::SystemTime> {
+        head::to_system_time(self.modified())
+    }
+
+    /// Returns font's glyph data format from the `head` table.
+    ///
+    /// Should be 0 for the current format.
+    #[inline]
+    pub fn glyph_data_format(&self) -> i16 {
+        head::glyph_data_format(self.head).unwrap_or(0)
+    }
+
     /// Returns font's units per EM.
     ///
     /// Returns `None` when value is not in a 16..=16384 range.
@@ -931,6 +1287,27 @@ impl<'a> Font<'a> {
             .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"xhgt"), v))
     }
 
+    /// Returns font's capital height.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when OS/2 table is not present or when its version is < 2.
+    #[inline]
+    pub fn capital_height(&self) -> Option<i16> {
+        self.os_2.and_then(|os_2| os_2.cap_height())
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"cpht"), v))
+    }
+
+    /// Returns font's italic angle in counter-clockwise degrees from the vertical.
+    ///
+    /// Zero for upright text, negative for text that leans to the right.
+    ///
+    /// Returns `None` when `post` table is not present.
+    #[inline]
+    pub fn italic_angle(&self) -> Option<f32> {
+        self.post.map(|post| post.italic_angle())
+    }
+
     /// Returns font's underline metrics.
     ///
     /// This method is affected by variation axes.
@@ -997,7 +1374,9 @@ impl<'a> Font<'a> {
 
     /// Returns a total number of glyphs in the font.
     ///
-    /// Never zero.
+    /// Never zero. Valid glyph IDs are in a `0..number_of_glyphs` range.
+    ///
+    /// Taken from the `maxp` table, which is required.
     ///
     /// The value was already parsed, so this function doesn't involve any parsing.
     #[inline]
@@ -1005,83 +1384,263 @@ impl<'a> Font<'a> {
         self.number_of_glyphs.get()
     }
 
+    /// Checks that the font has the specified glyph.
+    ///
+    /// Checks the `CFF`/`CFF2` CharStrings INDEX when present
+    /// and the number of glyphs otherwise. Glyphs are not outlined.
+    ///
+    /// Note that an existing glyph can still be empty, like a space.
+    #[inline]
+    pub fn has_glyph(&self, glyph_id: GlyphId) -> bool {
+        if let Some(ref metadata) = self.cff_ {
+            return metadata.contains_glyph(glyph_id);
+        }
+
+        if let Some(ref metadata) = self.cff2 {
+            return metadata.contains_glyph(glyph_id);
+        }
+
+        glyph_id.0 < self.number_of_glyphs.get()
+    }
+
+    /// Returns the maximum number of points in a simple glyph.
+    ///
+    /// Returns `None` when the `maxp` table version is not 1.0,
+    /// which is the case for `CFF` fonts.
+    #[inline]
+    pub fn max_points(&self) -> Option<u16> {
+        maxp::max_points(self.maxp)
+    }
+
+    /// Returns the maximum number of components referenced at the top level
+    /// of a composite glyph.
+    ///
+    /// Returns `None` when the `maxp` table version is not 1.0.
+    #[inline]
+    pub fn max_component_elements(&self) -> Option<u16> {
+        maxp::max_component_elements(self.maxp)
+    }
+
+    /// Returns the maximum levels of composite glyphs recursion.
+    ///
+    /// Returns `None` when the `maxp` table version is not 1.0.
+    #[inline]
+    pub fn max_component_depth(&self) -> Option<u16> {
+        maxp::max_component_depth(self.maxp)
+    }
+
     /// Resolves a Glyph ID for a code point.
     ///
     /// Returns `None` instead of `0` when glyph is not found.
     ///
     /// All subtable formats except Mixed Coverage (8) are supported.
+    ///
+    /// Only a single subtable is used. It's selected during `Font` creation in the following order:
+    /// Windows Unicode full repertoire, Unicode full repertoire, Windows Unicode BMP (format 4),
+    /// any other Unicode subtable, Windows Symbol and, finally, Mac OS Roman (format 0 and 6).
+    ///
+    /// For Windows Symbol subtables, code points in the 0..255 range that are not mapped
+    /// directly will be looked up in the U+F000..U+F0FF range as well.
     #[inline]
     pub fn glyph_index(&self, c: char) -> Option<GlyphId> {
         cmap::glyph_index(self.cmap.as_ref()?, c)
     }
 
+    /// Returns a cursor for resolving many glyph indices in a row.
+    ///
+    /// Produces the same results as `glyph_index()`, but is faster for text
+    /// where consecutive characters share a `cmap` format 4 segment, like ASCII.
+    #[inline]
+    pub fn glyph_index_cursor(&self) -> GlyphIndexCursor<'a> {
+        self.cmap.as_ref().map(GlyphIndexCursor::new).unwrap_or_default()
+    }
+
+    /// Returns the platform ID, the encoding ID and the format
+    /// of the `cmap` subtable used by `glyph_index()`.
+    ///
+    /// Returns `None` when font doesn't have a supported `cmap` subtable.
+    #[inline]
+    pub fn cmap_subtable_info(&self) -> Option<(PlatformId, u16, u16)> {
+        cmap::subtable_info(self.cmap.as_ref()?)
+    }
+
+    /// Returns an iterator over code points mapped by the `cmap` subtable
+    /// used by `glyph_index()`.
+    ///
+    /// Code points are yielded in ascending order.
+    /// Only subtable formats 4, 12 and 13 are supported.
+    /// For other formats an empty iterator will be returned.
+    #[inline]
+    pub fn cmap_codepoints(&self) -> Codepoints<'a> {
+        self.cmap.as_ref().map(cmap::codepoints).unwrap_or_default()
+    }
+
+    /// Returns the first code point mapped to a glyph.
+    ///
+    /// Uses the same `cmap` subtable as `glyph_index()`
+    /// and has the same limitations as `cmap_codepoints()`.
+    ///
+    /// Since `cmap` is not indexed by glyph, this method has an O(n) complexity.
+    /// To map many glyphs, build a reverse map using `cmap_codepoints()` instead.
+    #[inline]
+    pub fn codepoint_for_glyph(&self, glyph_id: GlyphId) -> Option<u32> {
+        cmap::glyph_codepoint(self.cmap.as_ref()?, glyph_id)
+    }
+
     /// Resolves a variation of a Glyph ID from two code points.
     ///
     /// Implemented according to
     /// [Unicode Variation Sequences](
     /// https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-14-unicode-variation-sequences).
     ///
-    /// Returns `None` instead of `0` when glyph is not found.
+    /// Returns `GlyphVariationResult::UseDefault` when the sequence should be resolved
+    /// via `glyph_index()` and `GlyphVariationResult::NotFound` when there is
+    /// no such sequence or the font doesn't have a format 14 `cmap` subtable.
     #[inline]
-    pub fn glyph_variation_index(&self, c: char, variation: char) -> Option<GlyphId> {
-        cmap::glyph_variation_index(self.cmap.as_ref()?, c, variation)
+    pub fn glyph_variation_index(&self, c: char, variation: char) -> GlyphVariationResult {
+        match self.cmap {
+            Some(ref cmap) => cmap::glyph_variation_index(cmap, c, variation),
+            None => GlyphVariationResult::NotFound,
+        }
     }
 
     /// Returns glyph's horizontal advance.
     ///
     /// This method is affected by variation axes.
+    /// Only `HVAR` deltas are applied, so when a variable font doesn't have
+    /// this table, the default instance value is returned.
     #[inline]
     pub fn glyph_hor_advance(&self, glyph_id: GlyphId) -> Option<u16> {
         let mut advance = self.hmtx?.advance(glyph_id)? as f32;
 
         if self.is_variable() {
-            // We can't use `round()` in `no_std`, so this is the next best thing.
-            advance += hvar::glyph_advance_offset(self.hvar?, glyph_id, self.coords())? + 0.5;
+            if let Some(table) = self.hvar {
+                advance += hvar::glyph_advance_offset(table, glyph_id, self.coords())?;
+            }
         }
 
-        u16::try_num_from(advance)
+        u16::try_num_from(f32_round(advance))
     }
 
     /// Returns glyph's vertical advance.
     ///
+    /// When `vmtx` table is not present, `ascender - descender` from `vhea`
+    /// is used for all glyphs.
+    ///
     /// This method is affected by variation axes.
+    /// Only `VVAR` deltas are applied, so when a variable font doesn't have
+    /// this table, the default instance value is returned.
     #[inline]
     pub fn glyph_ver_advance(&self, glyph_id: GlyphId) -> Option<u16> {
-        let mut advance = self.vmtx?.advance(glyph_id)? as f32;
+        let vmtx = match self.vmtx {
+            Some(vmtx) => vmtx,
+            None => {
+                if glyph_id.0 >= self.number_of_glyphs.get() {
+                    return None;
+                }
+
+                let vhea = self.vhea?;
+                let advance = i32::from(vhea::ascender(vhea)) - i32::from(vhea::descender(vhea));
+                return u16::try_from(advance).ok();
+            }
+        };
+
+        let mut advance = vmtx.advance(glyph_id)? as f32;
 
         if self.is_variable() {
-            advance += hvar::glyph_advance_offset(self.vvar?, glyph_id, self.coords())? + 0.5;
+            if let Some(table) = self.vvar {
+                advance += hvar::glyph_advance_offset(table, glyph_id, self.coords())?;
+            }
         }
 
-        u16::try_num_from(advance)
+        u16::try_num_from(f32_round(advance))
+    }
+
+    /// Returns an iterator over horizontal advances of all glyphs.
+    ///
+    /// Much faster than calling `glyph_hor_advance()` for each glyph.
+    ///
+    /// This method is **not** affected by variation axes.
+    ///
+    /// An iterator can be empty.
+    #[inline]
+    pub fn glyph_hor_advances(&self) -> GlyphAdvances<'a> {
+        self.hmtx.map(|hmtx| hmtx.advances(self.number_of_glyphs)).unwrap_or_default()
+    }
+
+    /// Returns an iterator over vertical advances of all glyphs.
+    ///
+    /// Much faster than calling `glyph_ver_advance()` for each glyph.
+    ///
+    /// This method is **not** affected by variation axes.
+    ///
+    /// An iterator can be empty.
+    #[inline]
+    pub fn glyph_ver_advances(&self) -> GlyphAdvances<'a> {
+        self.vmtx.map(|vmtx| vmtx.advances(self.number_of_glyphs)).unwrap_or_default()
     }
 
     /// Returns glyph's horizontal side bearing.
     ///
     /// This method is affected by variation axes.
+    /// Only `HVAR` deltas are applied, so when a variable font doesn't have
+    /// this table or its side bearing mapping, the default instance value is returned.
     #[inline]
     pub fn glyph_hor_side_bearing(&self, glyph_id: GlyphId) -> Option<i16> {
         let mut bearing = self.hmtx?.side_bearing(glyph_id)? as f32;
 
         if self.is_variable() {
-            bearing += hvar::glyph_side_bearing_offset(self.hvar?, glyph_id, self.coords())? + 0.5;
+            if let Some(table) = self.hvar {
+                bearing += hvar::glyph_side_bearing_offset(table, glyph_id, self.coords())?;
+            }
         }
 
-        i16::try_num_from(bearing)
+        i16::try_num_from(f32_round(bearing))
+    }
+
+    /// Returns glyph's horizontal advance and side bearing.
+    ///
+    /// Same as `glyph_hor_advance()` and `glyph_hor_side_bearing()`,
+    /// but resolves both values using a single `hmtx` lookup.
+    /// Returns `None` when any of them is not available.
+    ///
+    /// This method is affected by variation axes.
+    /// Only `HVAR` deltas are applied, so when a variable font doesn't have
+    /// this table, the default instance value is returned.
+    #[inline]
+    pub fn glyph_hor_metrics(&self, glyph_id: GlyphId) -> Option<(u16, i16)> {
+        let (advance, bearing) = self.hmtx?.metrics(glyph_id)?;
+        let mut advance = advance as f32;
+        let mut bearing = bearing as f32;
+
+        if self.is_variable() {
+            if let Some(table) = self.hvar {
+                advance += hvar::glyph_advance_offset(table, glyph_id, self.coords())?;
+                // A missing `lsbMapping` means that side bearings have no deltas.
+                bearing += hvar::glyph_side_bearing_offset(table, glyph_id, self.coords())
+                    .unwrap_or(0.0);
+            }
+        }
+
+        Some((u16::try_num_from(f32_round(advance))?, i16::try_num_from(f32_round(bearing))?))
     }
 
     /// Returns glyph's vertical side bearing.
     ///
     /// This method is affected by variation axes.
+    /// Only `VVAR` deltas are applied, so when a variable font doesn't have
+    /// this table or its side bearing mapping, the default instance value is returned.
     #[inline]
     pub fn glyph_ver_side_bearing(&self, glyph_id: GlyphId) -> Option<i16> {
         let mut bearing = self.vmtx?.side_bearing(glyph_id)? as f32;
 
         if self.is_variable() {
-            bearing += hvar::glyph_side_bearing_offset(self.vvar?, glyph_id, self.coords())? + 0.5;
+            if let Some(table) = self.vvar {
+                bearing += hvar::glyph_side_bearing_offset(table, glyph_id, self.coords())?;
+            }
         }
 
-        i16::try_num_from(bearing)
+        i16::try_num_from(f32_round(bearing))
     }
 
     /// Returns glyph's vertical origin according to
@@ -1090,6 +1649,15 @@ impl<'a> Font<'a> {
         self.vorg.map(|vorg| vorg.glyph_y_origin(glyph_id))
     }
 
+    /// Returns an iterator over vertical origins of glyphs in the specified range.
+    ///
+    /// Much faster than calling `glyph_y_origin()` for each glyph.
+    ///
+    /// Returns `None` when font doesn't have a `VORG` table.
+    pub fn glyph_y_origins(&self, range: core::ops::Range<GlyphId>) -> Option<GlyphYOrigins<'a>> {
+        self.vorg.map(|vorg| vorg.glyph_y_origins(range))
+    }
+
     /// Returns glyph's name.
     ///
     /// Uses the `post` table as a source.
@@ -1100,6 +1668,21 @@ impl<'a> Font<'a> {
         self.post.and_then(|post| post.glyph_name(glyph_id))
     }
 
+    /// Returns a glyph ID by its name.
+    ///
+    /// Uses the `post` table as a source. Falls back to the `CFF` charset
+    /// when `post` has no glyph names or no such name.
+    /// CID-keyed `CFF` fonts and the predefined Expert and Expert Subset charsets
+    /// are not supported.
+    ///
+    /// This is an O(n) operation, so callers that need many lookups
+    /// should cache the results.
+    #[inline]
+    pub fn glyph_index_by_name(&self, name: &str) -> Option<GlyphId> {
+        self.post.and_then(|post| post.glyph_index_by_name(name))
+            .or_else(|| self.cff_.as_ref()?.glyph_index_by_name(name))
+    }
+
     /// Checks that font has
     /// [Glyph Class Definition Table](
     /// https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#glyph-class-definition-table).
@@ -1126,6 +1709,17 @@ impl<'a> Font<'a> {
         try_opt_or!(self.gdef, Class(0)).glyph_mark_attachment_class(glyph_id)
     }
 
+    /// Returns ligature caret positions according to
+    /// [Ligature Caret List Table](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#ligature-caret-list-table).
+    ///
+    /// Returns an empty iterator when *Ligature Caret List Table* is not set
+    /// or glyph is not a ligature.
+    #[inline]
+    pub fn glyph_ligature_carets(&self, glyph_id: GlyphId) -> LigatureCarets<'a> {
+        self.gdef.map(|gdef| gdef.ligature_carets(glyph_id)).unwrap_or_default()
+    }
+
     /// Checks that glyph is a mark according to
     /// [Mark Glyph Sets Table](
     /// https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#mark-glyph-sets-table).
@@ -1139,13 +1733,42 @@ impl<'a> Font<'a> {
         try_opt_or!(self.gdef, false).is_mark_glyph(glyph_id, set_index)
     }
 
-    /// Returns a glyphs pair kerning.
+    /// Returns a glyphs pair kerning from the `kern` table.
     ///
-    /// Only a horizontal kerning is supported.
+    /// Both OpenType and Apple tables are supported, but only format 0 subtables
+    /// with a horizontal kerning are used. Values from all matching subtables are accumulated.
     pub fn glyphs_kerning(&self, glyph_id1: GlyphId, glyph_id2: GlyphId) -> Option<i16> {
         kern::glyphs_kerning(self.kern?, glyph_id1, glyph_id2)
     }
 
+    /// Returns a glyphs pair kerning from the `GPOS` table.
+    ///
+    /// Walks all pair adjustment lookups (type 2) in order and returns
+    /// the horizontal advance adjustment of the `left` glyph
+    /// from the first subtable that covers the pair.
+    /// Both glyph pairs (format 1) and class pairs (format 2) are supported.
+    ///
+    /// Returns `None` when `GPOS` table is not present or the pair is not kerned.
+    pub fn gpos_kerning(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        self.gpos?.glyphs_kerning(left, right)
+    }
+
+    /// Returns a `GPOS` table.
+    ///
+    /// Use the `GlyphPosSubTable` trait to access lookups.
+    #[inline]
+    pub fn positioning_table(&self) -> Option<PositioningTable<'a>> {
+        self.gpos
+    }
+
+    /// Returns a `GSUB` table.
+    ///
+    /// Use the `GlyphPosSubTable` trait to access lookups.
+    #[inline]
+    pub fn substitution_table(&self) -> Option<SubstitutionTable<'a>> {
+        self.gsub
+    }
+
     /// Outlines a glyph and returns its tight bounding box.
     ///
     /// **Warning**: since `ttf-parser` is a pull parser,
@@ -1154,10 +1777,13 @@ impl<'a> Font<'a> {
     /// `OutlineBuilder`'s output.
     ///
     /// `glyf`, `gvar`, `CFF` and `CFF2` tables are supported.
+    /// The outline table is picked automatically: `glyf` (with `loca`) first,
+    /// then `CFF` and then `CFF2`.
     ///
     /// This method is affected by variation axes.
     ///
     /// Returns `None` when glyph has no outline or on error.
+    /// Use `has_table()` to check that a font has any outlines at all.
     ///
     /// # Example
     ///
@@ -1202,16 +1828,17 @@ impl<'a> Font<'a> {
         glyph_id: GlyphId,
         builder: &mut dyn OutlineBuilder,
     ) -> Option<Rect> {
-        if let Some(ref gvar_table) = self.gvar {
-            return gvar::outline(self.loca?, self.glyf?, gvar_table, self.coords(), glyph_id, builder);
-        }
+        if let (Some(loca_table), Some(glyf_table)) = (self.loca, self.glyf) {
+            if let Some(ref gvar_table) = self.gvar {
+                return gvar::outline(loca_table, glyf_table, gvar_table, self.coords(),
+                                     glyph_id, builder);
+            }
 
-        if let Some(glyf_table) = self.glyf {
-            return glyf::outline(self.loca?, glyf_table, glyph_id, builder);
+            return glyf::outline(loca_table, glyf_table, glyph_id, builder);
         }
 
         if let Some(ref metadata) = self.cff_ {
-            return cff::outline(metadata, glyph_id, builder);
+            return cff::outline(metadata, self.cff_settings, glyph_id, builder, None);
         }
 
         if let Some(ref metadata) = self.cff2 {
@@ -1221,11 +1848,174 @@ impl<'a> Font<'a> {
         None
     }
 
+    /// Checks that a glyph has no outline, like a space.
+    ///
+    /// For `glyf` based fonts, only the `loca` table is checked, so no glyph data is parsed.
+    /// A composite glyph is never empty, even when all of its components are.
+    ///
+    /// For `CFF` and `CFF2` based fonts, a charstring is interpreted without building
+    /// an outline, and a glyph is empty when it has no contours.
+    /// Malformed charstrings are empty too, since they will not be outlined.
+    ///
+    /// Returns `false` for glyphs that are not present in a font and for fonts without outlines.
+    pub fn is_glyph_empty(&self, glyph_id: GlyphId) -> bool {
+        if let (Some(loca_table), Some(_)) = (self.loca, self.glyf) {
+            return loca_table.is_empty_glyph(glyph_id);
+        }
+
+        if (self.cff_.is_some() || self.cff2.is_some()) && self.has_glyph(glyph_id) {
+            let mut builder = DummyOutline;
+            let mut counter = ContourCounter::new(&mut builder);
+            let _ = self.outline_glyph(glyph_id, &mut counter);
+            return counter.contours() == 0;
+        }
+
+        false
+    }
+
+    /// Outlines a glyph in a Y-down coordinate system, scaled to the specified em height.
+    ///
+    /// Coordinates are scaled by `em_height / units_per_em` and the Y axis is inverted,
+    /// so the glyph's baseline is placed at `baseline`.
+    /// This is a shorthand for `outline_glyph()` with a `TransformBuilder`.
+    ///
+    /// Just like with `TransformBuilder`, the returned bounding box is not transformed
+    /// and is in font units.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ttf_parser::{Font, GlyphId, SvgPathBuilder};
+    ///
+    /// let data = std::fs::read("fonts/SourceSansPro-Regular-Tiny.ttf").unwrap();
+    /// let font = Font::from_data(&data, 0).unwrap();
+    /// let mut builder = SvgPathBuilder::new(2, false);
+    /// font.outline_glyph_flipped(GlyphId(13), 10.0, 8.0, &mut builder).unwrap();
+    /// assert_eq!(builder.finish(), "M 0.9 8 L 0.9 1.44 L 1.73 1.44 L 1.73 7.29 L 4.6 7.29 L 4.6 8 L 0.9 8 Z");
+    /// ```
+    #[inline]
+    pub fn outline_glyph_flipped(
+        &self,
+        glyph_id: GlyphId,
+        em_height: f32,
+        baseline: f32,
+        builder: &mut dyn OutlineBuilder,
+    ) -> Option<Rect> {
+        let scale = em_height / f32::from(self.units_per_em()?);
+        let mut builder = TransformBuilder::new([scale, 0.0, 0.0, -scale, 0.0, baseline], builder);
+        self.outline_glyph(glyph_id, &mut builder)
+    }
+
+    /// Outlines a glyph and returns its tight bounding box along with the number of contours.
+    ///
+    /// Works just like `outline_glyph()`, but also counts contours,
+    /// so blank glyphs can be detected without a custom `OutlineBuilder`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ttf_parser::{Font, GlyphId, DummyOutline};
+    ///
+    /// let data = std::fs::read("fonts/SourceSansPro-Regular-Tiny.ttf").unwrap();
+    /// let font = Font::from_data(&data, 0).unwrap();
+    /// let metrics = font.outline_glyph_metrics(GlyphId(13), &mut DummyOutline).unwrap();
+    /// assert_eq!(metrics.contours, 1);
+    /// ```
+    #[inline]
+    pub fn outline_glyph_metrics(
+        &self,
+        glyph_id: GlyphId,
+        builder: &mut dyn OutlineBuilder,
+    ) -> Option<OutlineMetrics> {
+        let mut counter = ContourCounter::new(builder);
+        let bbox = self.outline_glyph(glyph_id, &mut counter)?;
+        Some(OutlineMetrics { bbox, contours: counter.contours() })
+    }
+
+    /// Outlines a glyph and reports its stem hints.
+    ///
+    /// Works just like `outline_glyph()`, but also passes all the stem hints
+    /// to the `hints` builder as they are decoded.
+    ///
+    /// Only `CFF` hints are supported. For other tables `hints` will not be called.
+    #[inline]
+    pub fn outline_glyph_with_hints(
+        &self,
+        glyph_id: GlyphId,
+        builder: &mut dyn OutlineBuilder,
+        hints: &mut dyn HintBuilder,
+    ) -> Option<Rect> {
+        if self.gvar.is_none() && self.glyf.is_none() {
+            if let Some(ref metadata) = self.cff_ {
+                return cff::outline(metadata, self.cff_settings, glyph_id, builder, Some(hints));
+            }
+        }
+
+        self.outline_glyph(glyph_id, builder)
+    }
+
+    /// Sets settings that will be used during `CFF` outlining.
+    ///
+    /// Can be used to raise or lower the subroutines nesting limit.
+    #[inline]
+    pub fn set_cff_settings(&mut self, settings: CffSettings) {
+        self.cff_settings = settings;
+    }
+
+    /// Returns alignment zones and standard stem widths from the `CFF` Private DICT.
+    ///
+    /// Returns `None` when font doesn't have a `CFF` table.
+    #[inline]
+    pub fn cff_blue_zones(&self) -> Option<CffBlueZones> {
+        self.cff_.as_ref().map(|metadata| *metadata.blue_zones())
+    }
+
+    /// Resolves a Glyph ID for a character code using the `CFF` Encoding.
+    ///
+    /// Useful for PDF simple fonts, which are addressed by 8-bit codes
+    /// and may not have a `cmap` table.
+    ///
+    /// Supports formats 0 and 1, supplemental codes
+    /// and the predefined Standard and Expert Encodings.
+    /// Returns `None` for CID-keyed fonts.
+    #[inline]
+    pub fn cff_code_to_gid(&self, code: u8) -> Option<GlyphId> {
+        self.cff_.as_ref()?.code_to_gid(code)
+    }
+
+    /// Checks that a glyph's `CFF` charstring is structurally valid,
+    /// without producing an outline.
+    ///
+    /// Uses settings from `set_cff_settings()`.
+    ///
+    /// Only the `CFF` table is checked, `CFF2` charstrings are not validated.
+    ///
+    /// Returns `None` when font doesn't have a `CFF` table.
+    #[inline]
+    pub fn validate_cff_glyph(&self, glyph_id: GlyphId) -> Option<Result<(), CFFError>> {
+        let metadata = self.cff_.as_ref()?;
+        Some(metadata.validate_charstring(self.cff_settings, glyph_id))
+    }
+
+    /// Checks that all `CFF` charstrings are structurally valid.
+    ///
+    /// Stops on the first invalid glyph and returns it along with an error.
+    ///
+    /// Only the `CFF` table is checked, `CFF2` charstrings are not validated.
+    ///
+    /// Returns `None` when font doesn't have a `CFF` table.
+    #[inline]
+    pub fn validate_cff_glyphs(&self) -> Option<Result<(), (GlyphId, CFFError)>> {
+        let metadata = self.cff_.as_ref()?;
+        Some(metadata.validate_all(self.cff_settings))
+    }
+
     /// Returns a tight glyph bounding box.
     ///
     /// Unless the current font has a `glyf` table, this is just a shorthand for `outline_glyph()`
     /// since only the `glyf` table stores a bounding box. In case of CFF and variable fonts
-    /// we have to actually outline a glyph to find it's bounding box.
+    /// with non-default coordinates we have to actually outline a glyph
+    /// to find it's bounding box.
     ///
     /// When a glyph is defined by a raster or a vector image,
     /// that can be obtained via `glyph_image()`,
@@ -1234,21 +2024,39 @@ impl<'a> Font<'a> {
     /// This method is affected by variation axes.
     #[inline]
     pub fn glyph_bounding_box(&self, glyph_id: GlyphId) -> Option<Rect> {
-        if let Some(glyf_table) = self.glyf {
+        // A stored bounding box is valid only for default variation coordinates.
+        let is_default_instance = self.gvar.is_none() || self.coords().iter().all(|c| c.get() == 0);
+        if let (Some(glyf_table), true) = (self.glyf, is_default_instance) {
             return glyf::glyph_bbox(self.loca?, glyf_table, glyph_id);
         }
 
         self.outline_glyph(glyph_id, &mut DummyOutline)
     }
 
+    /// Returns an iterator over simple glyph points.
+    ///
+    /// Unlike `outline_glyph()`, returns raw `glyf` points along with contour ends,
+    /// which is useful for hinting and font editing.
+    ///
+    /// This method is **not** affected by variation axes.
+    ///
+    /// Returns `None` when a font doesn't have a `glyf` table,
+    /// for composite glyphs and for empty glyphs.
+    #[inline]
+    pub fn glyph_points(&self, glyph_id: GlyphId) -> Option<GlyphPoints<'a>> {
+        glyf::glyph_points(self.loca?, self.glyf?, glyph_id)
+    }
+
     /// Returns a reference to a glyph image.
     ///
     /// A font can define a glyph using a raster or a vector image instead of a simple outline.
     /// Which is primarily used for emojis. This method should be used to access those images.
     ///
-    /// `pixels_per_em` allows selecting a preferred image size. While the chosen size will
-    /// be closer to an upper one. So when font has 64px and 96px images and `pixels_per_em`
-    /// is set to 72, 96px image will be returned.
+    /// `pixels_per_em` allows selecting a preferred image size.
+    /// For `sbix`, the largest image that is not larger than `pixels_per_em` will be returned,
+    /// or the smallest one when all images are larger. So when font has 64px and 96px images
+    /// and `pixels_per_em` is set to 72, 64px image will be returned.
+    /// For `CBLC`+`CBDT`, the chosen size will be closer to an upper one instead.
     /// To get the largest image simply use `std::u16::MAX`.
     /// This property has no effect in case of SVG.
     ///
@@ -1279,12 +2087,94 @@ impl<'a> Font<'a> {
         None
     }
 
+    /// Returns an iterator over bitmap strikes from the
+    /// [Standard Bitmap Graphics Table](https://docs.microsoft.com/en-us/typography/opentype/spec/sbix).
+    ///
+    /// Unlike `glyph_image()`, this method allows selecting a strike manually.
+    ///
+    /// Returns an empty iterator when font doesn't have an `sbix` table.
+    #[inline]
+    pub fn raster_strikes(&self) -> RasterStrikes<'a> {
+        match self.sbix {
+            Some(data) => sbix::strikes(data, self.number_of_glyphs),
+            None => RasterStrikes::default(),
+        }
+    }
+
+    /// Returns color layers of a glyph from the
+    /// [Color Table](https://docs.microsoft.com/en-us/typography/opentype/spec/colr).
+    ///
+    /// Each layer is a glyph ID and a palette entry index, which can be resolved
+    /// via `palette_color()`. Layers should be rendered from bottom to top.
+    ///
+    /// Returns `None` when font doesn't have a `COLR` table or glyph is not a color glyph.
+    #[inline]
+    pub fn glyph_color_layers(&self, glyph_id: GlyphId) -> Option<ColorLayers<'a>> {
+        self.colr?.glyph_layers(glyph_id)
+    }
+
+    /// Returns a color from the
+    /// [Color Palette Table](https://docs.microsoft.com/en-us/typography/opentype/spec/cpal)
+    /// in RGBA order.
+    ///
+    /// Returns `None` when font doesn't have a `CPAL` table or indices are out of bounds.
+    /// The `0xFFFF` palette entry index, which indicates the text foreground color,
+    /// must be handled by the caller.
+    #[inline]
+    pub fn palette_color(&self, palette: u16, index: u16) -> Option<[u8; 4]> {
+        self.cpal?.color(palette, index)
+    }
+
     /// Returns an iterator over variation axes.
     #[inline]
     pub fn variation_axes(&self) -> VariationAxes {
         self.fvar.map(|fvar| fvar.axes()).unwrap_or_default()
     }
 
+    /// Returns an iterator over `cvt ` value deltas for the current variation instance
+    /// from the [CVT Variations Table](https://docs.microsoft.com/en-us/typography/opentype/spec/cvar).
+    ///
+    /// Indices that are not yielded have no delta.
+    ///
+    /// Returns an empty iterator when font doesn't have a `cvar` table.
+    #[inline]
+    pub fn cvt_deltas(&self) -> CvtDeltas<'_> {
+        match self.cvar {
+            Some(ref table) => table.cvt_deltas(self.coords()),
+            None => CvtDeltas::default(),
+        }
+    }
+
+    /// Converts user-space variation coordinates into normalized ones.
+    ///
+    /// `user_coords` must be in the same order as `Font::variation_axes`.
+    /// Missing coordinates are set to axis defaults. Values are clamped to the axis range,
+    /// normalized and then mapped via the `avar` table, when present.
+    ///
+    /// `normalized` will be filled with F2Dot14 values (-16384..16384)
+    /// and must have the same length as the number of axes.
+    ///
+    /// Returns `None` when font is not variable or `normalized` has a wrong length.
+    pub fn normalize_variation(&self, user_coords: &[f32], normalized: &mut [i16]) -> Option<()> {
+        let fvar = self.fvar?;
+        if normalized.len() > usize::from(MAX_VAR_COORDS) {
+            return None;
+        }
+
+        let mut coords = VarCoords { len: normalized.len() as u8, ..VarCoords::default() };
+        fvar.normalize(user_coords, coords.as_mut_slice())?;
+
+        if let Some(avar) = self.avar {
+            avar.map_coordinates(coords.as_mut_slice())?;
+        }
+
+        for (n, coord) in normalized.iter_mut().zip(coords.as_slice()) {
+            *n = coord.get();
+        }
+
+        Some(())
+    }
+
     /// Sets a variation axis coordinate.
     ///
     /// This is the only mutable method in the library.
@@ -1367,6 +2257,12 @@ pub fn fonts_in_collection(data: &[u8]) -> Option<u32> {
     s.read()
 }
 
+// Can overflow on 32-bit targets.
+#[inline]
+fn table_range(offset: usize, length: usize) -> Option<core::ops::Range<usize>> {
+    Some(offset..offset.checked_add(length)?)
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -1374,6 +2270,23 @@ mod tests {
     use crate::writer;
     use writer::TtfType::*;
 
+    #[test]
+    fn tag_from_str() {
+        assert_eq!("GPOS".parse(), Ok(Tag::from_bytes(b"GPOS")));
+        assert_eq!("cvt".parse(), Ok(Tag::from_bytes(b"cvt ")));
+        assert_eq!("".parse::<Tag>(), Err(()));
+        assert_eq!("latin".parse::<Tag>(), Err(()));
+        assert_eq!("a\tb".parse::<Tag>(), Err(()));
+        assert_eq!("ä".parse::<Tag>(), Err(()));
+    }
+
+    #[test]
+    fn tag_display() {
+        let tag: Tag = "cvt".parse().unwrap();
+        assert_eq!(format!("{}", tag), "cvt ");
+        assert_eq!(format!("{:?}", tag), "Tag(cvt )");
+    }
+
     #[test]
     fn empty_font() {
         assert!(Font::from_data(&[], 0).is_none());
@@ -1420,6 +2333,49 @@ mod tests {
         assert!(Font::from_data(&data, 0).is_none());
     }
 
+    #[test]
+    fn truncated_required_table() {
+        let data = writer::convert(&[
+            TrueTypeMagic,
+            UInt16(1), // numTables
+            UInt16(0), // searchRange
+            UInt16(0), // entrySelector
+            UInt16(0), // rangeShift
+            Raw(b"head"), // tableTag
+            UInt32(0), // checkSum
+            UInt32(28), // offset
+            UInt32(54), // length
+            UInt32(0x00010000), // head: version
+        ]);
+
+        assert!(Font::from_data(&data, 0).is_none());
+    }
+
+    #[test]
+    fn table_range_overflow() {
+        assert_eq!(table_range(10, 20), Some(10..30));
+        assert_eq!(table_range(core::usize::MAX, 0), Some(core::usize::MAX..core::usize::MAX));
+        assert_eq!(table_range(core::usize::MAX, 1), None);
+        assert_eq!(table_range(1, core::usize::MAX), None);
+    }
+
+    #[test]
+    fn table_out_of_bounds() {
+        let data = writer::convert(&[
+            TrueTypeMagic,
+            UInt16(1), // numTables
+            UInt16(0), // searchRange
+            UInt16(0), // entrySelector
+            UInt16(0), // rangeShift
+            Raw(b"head"), // tableTag
+            UInt32(0), // checkSum
+            UInt32(core::u32::MAX), // offset
+            UInt32(core::u32::MAX), // length
+        ]);
+
+        assert!(Font::from_data(&data, 0).is_none());
+    }
+
     #[test]
     fn open_type_magic() {
         let data = writer::convert(&[
@@ -1497,4 +2453,291 @@ mod tests {
         assert_eq!(fonts_in_collection(&data), Some(std::u32::MAX));
         assert!(Font::from_data(&data, std::u32::MAX - 1).is_none());
     }
+
+    // Builds a font with the required tables and `extra` ones appended.
+    fn font_data(extra: &[(&[u8; 4], &[u8])]) -> std::vec::Vec<u8> {
+        font_data_with_hhea(&[0u8; 36], extra)
+    }
+
+    fn font_data_with_hhea(hhea: &[u8], extra: &[(&[u8; 4], &[u8])]) -> std::vec::Vec<u8> {
+        let mut head = [0u8; 54];
+        head[0..4].copy_from_slice(&[0x00, 0x01, 0x00, 0x00]); // version
+        head[12..16].copy_from_slice(&0x5F0F3CF5u32.to_be_bytes()); // magicNumber
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes()); // unitsPerEm
+        let maxp = writer::convert(&[
+            UInt32(0x00005000), // version
+            UInt16(2), // numGlyphs
+        ]);
+
+        let mut tables: std::vec::Vec<(&[u8; 4], &[u8])> = std::vec::Vec::new();
+        tables.push((b"head", &head));
+        tables.push((b"hhea", hhea));
+        tables.push((b"maxp", &maxp));
+        tables.extend_from_slice(extra);
+
+        let mut data = writer::convert(&[
+            TrueTypeMagic,
+            UInt16(tables.len() as u16), // numTables
+            UInt16(0), // searchRange
+            UInt16(0), // entrySelector
+            UInt16(0), // rangeShift
+        ]);
+
+        let mut offset = 12 + tables.len() * 16;
+        for (tag, table) in &tables {
+            data.extend_from_slice(*tag);
+            data.extend_from_slice(&writer::convert(&[
+                UInt32(0), // checkSum
+                UInt32(offset as u32), // offset
+                UInt32(table.len() as u32), // length
+            ]));
+            offset += table.len();
+        }
+
+        for (_, table) in &tables {
+            data.extend_from_slice(table);
+        }
+
+        data
+    }
+
+    #[test]
+    fn validate() {
+        use std::string::ToString;
+
+        // Two glyphs require three short offsets.
+        let loca = writer::convert(&[UInt16(0), UInt16(0), UInt16(0)]);
+        let data = font_data(&[(b"loca", &loca)]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.validate(), Ok(()));
+
+        let loca = writer::convert(&[UInt16(0), UInt16(0), UInt16(0), UInt16(0)]);
+        let data = font_data(&[(b"loca", &loca)]);
+        let font = Font::from_data(&data, 0).unwrap();
+        let err = font.validate().unwrap_err();
+        assert_eq!(err, GlyphCountMismatch {
+            table: Tag::from_bytes(b"loca"),
+            expected: 2,
+            actual: 3,
+        });
+        assert_eq!(err.to_string(), "the 'loca' table has data for 3 glyphs, while 'maxp' declares 2");
+    }
+
+    #[test]
+    fn validate_hmtx() {
+        let mut hhea = [0u8; 36];
+        hhea[34..36].copy_from_slice(&1u16.to_be_bytes()); // numberOfHMetrics
+
+        // One long metric and one left side bearing.
+        let hmtx = writer::convert(&[UInt16(500), Int16(0), Int16(0)]);
+        let data = font_data_with_hhea(&hhea, &[(b"hmtx", &hmtx)]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.validate(), Ok(()));
+
+        // A missing left side bearing.
+        let hmtx = writer::convert(&[UInt16(500), Int16(0)]);
+        let data = font_data_with_hhea(&hhea, &[(b"hmtx", &hmtx)]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert!(!font.has_table(TableName::HorizontalMetrics));
+        assert_eq!(font.validate().unwrap_err().actual, 1);
+    }
+
+    #[test]
+    fn is_glyph_empty() {
+        let loca = writer::convert(&[UInt16(0), UInt16(0), UInt16(5)]);
+        let glyf = [0u8; 10];
+        let data = font_data(&[(b"glyf", &glyf), (b"loca", &loca)]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert!(font.is_glyph_empty(GlyphId(0)));
+        assert!(!font.is_glyph_empty(GlyphId(1)));
+        assert!(!font.is_glyph_empty(GlyphId(2)));
+
+        // No outline tables.
+        let data = font_data(&[]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert!(!font.is_glyph_empty(GlyphId(0)));
+    }
+
+    #[test]
+    fn hor_metrics() {
+        let mut hhea = [0u8; 36];
+        hhea[34..36].copy_from_slice(&1u16.to_be_bytes()); // numberOfHMetrics
+
+        let hmtx = writer::convert(&[UInt16(500), Int16(10), Int16(20)]);
+        let data = font_data_with_hhea(&hhea, &[(b"hmtx", &hmtx)]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.glyph_hor_metrics(GlyphId(0)), Some((500, 10)));
+        assert_eq!(font.glyph_hor_metrics(GlyphId(1)), Some((500, 20)));
+        assert_eq!(font.glyph_hor_metrics(GlyphId(2)), None);
+    }
+
+    #[test]
+    fn hor_metrics_variable() {
+        let mut hhea = [0u8; 36];
+        hhea[34..36].copy_from_slice(&1u16.to_be_bytes()); // numberOfHMetrics
+
+        let hmtx = writer::convert(&[UInt16(500), Int16(10), Int16(20)]);
+        let fvar = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(16), // axesArrayOffset
+            UInt16(2), // reserved
+            UInt16(1), // axisCount
+            UInt16(20), // axisSize
+            UInt16(0), // instanceCount
+            UInt16(8), // instanceSize
+            // VariationAxisRecord
+            Raw(b"wght"), // axisTag
+            Int32(0), // minValue
+            Int32(0), // defaultValue
+            Int32(0x00010000), // maxValue
+            UInt16(0), // flags
+            UInt16(256), // axisNameID
+        ]);
+        let hvar = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt32(20), // itemVariationStoreOffset
+            UInt32(0), // advanceWidthMappingOffset
+            UInt32(0), // lsbMappingOffset
+            UInt32(0), // rsbMappingOffset
+            // ItemVariationStore
+            UInt16(1), // format
+            UInt32(12), // variationRegionListOffset
+            UInt16(1), // itemVariationDataCount
+            UInt32(22), // itemVariationDataOffsets[0]
+            // VariationRegionList
+            UInt16(1), // axisCount
+            UInt16(1), // regionCount
+            Int16(0), Int16(16384), Int16(16384), // region 0
+            // ItemVariationData
+            UInt16(2), // itemCount
+            UInt16(1), // wordDeltaCount
+            UInt16(1), // regionIndexCount
+            UInt16(0), // regionIndexes
+            Int16(10), Int16(-20), // deltas
+        ]);
+
+        let data = font_data_with_hhea(&hhea, &[
+            (b"HVAR", &hvar),
+            (b"fvar", &fvar),
+            (b"hmtx", &hmtx),
+        ]);
+        let mut font = Font::from_data(&data, 0).unwrap();
+        assert!(font.set_variation(Tag::from_bytes(b"wght"), 0.5).is_some());
+        assert_eq!(font.glyph_hor_advance(GlyphId(0)), Some(505));
+        // Side bearings are not varied without an `lsbMapping`.
+        assert_eq!(font.glyph_hor_metrics(GlyphId(0)), Some((505, 10)));
+        assert_eq!(font.glyph_hor_metrics(GlyphId(1)), Some((490, 20)));
+    }
+
+    #[test]
+    fn ver_advance_from_vhea() {
+        let mut vhea = [0u8; 36];
+        vhea[4..6].copy_from_slice(&880i16.to_be_bytes()); // ascender
+        vhea[6..8].copy_from_slice(&(-120i16).to_be_bytes()); // descender
+
+        let data = font_data(&[(b"vhea", &vhea)]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert!(!font.has_table(TableName::VerticalMetrics));
+        assert_eq!(font.glyph_ver_advance(GlyphId(0)), Some(1000));
+        assert_eq!(font.glyph_ver_advance(GlyphId(1)), Some(1000));
+        assert_eq!(font.glyph_ver_advance(GlyphId(2)), None);
+    }
+
+    #[test]
+    fn ver_advance_from_vmtx() {
+        let mut vhea = [0u8; 36];
+        vhea[4..6].copy_from_slice(&880i16.to_be_bytes()); // ascender
+        vhea[6..8].copy_from_slice(&(-120i16).to_be_bytes()); // descender
+        vhea[34..36].copy_from_slice(&1u16.to_be_bytes()); // numOfLongVerMetrics
+        let vmtx = writer::convert(&[
+            UInt16(900), // advanceHeight
+            Int16(10), // topSideBearing
+            Int16(20), // topSideBearing
+        ]);
+
+        let data = font_data(&[(b"vhea", &vhea), (b"vmtx", &vmtx)]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.glyph_ver_advance(GlyphId(0)), Some(900));
+        assert_eq!(font.glyph_ver_advance(GlyphId(1)), Some(900));
+    }
+
+    #[test]
+    fn ver_advance_without_vertical_tables() {
+        let data = font_data(&[]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.glyph_ver_advance(GlyphId(0)), None);
+    }
+
+    #[test]
+    fn outline_without_outline_tables() {
+        let data = font_data(&[]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.outline_glyph(GlyphId(0), &mut DummyOutline), None);
+    }
+
+    #[test]
+    fn has_glyph() {
+        let data = font_data(&[]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert!(font.has_glyph(GlyphId(0)));
+        assert!(font.has_glyph(GlyphId(1)));
+        assert!(!font.has_glyph(GlyphId(2)));
+    }
+
+    #[test]
+    fn tables() {
+        let data = font_data(&[(b"ZZZZ", &[1, 2, 3])]);
+        let font = Font::from_data(&data, 0).unwrap();
+        let tables: std::vec::Vec<_> = font.tables().collect();
+        assert_eq!(tables, &[
+            (Tag::from_bytes(b"head"), 54),
+            (Tag::from_bytes(b"hhea"), 36),
+            (Tag::from_bytes(b"maxp"), 6),
+            (Tag::from_bytes(b"ZZZZ"), 3),
+        ]);
+    }
+
+    #[test]
+    fn font_collection() {
+        let font = font_data(&[]);
+        let mut data = writer::convert(&[
+            FontCollectionMagic,
+            UInt16(1), // majorVersion
+            UInt16(0), // minorVersion
+            UInt32(2), // numFonts
+            UInt32(20), // tableDirectoryOffsets [0]
+            UInt32(20), // tableDirectoryOffsets [1]
+        ]);
+
+        // Table offsets are from the beginning of the collection.
+        let mut shifted = font.clone();
+        for i in 0..3 {
+            let pos = 12 + i * 16 + 8;
+            let offset = u32::from_be_bytes([font[pos], font[pos + 1], font[pos + 2], font[pos + 3]]);
+            shifted[pos..pos + 4].copy_from_slice(&(offset + 20).to_be_bytes());
+        }
+        data.extend_from_slice(&shifted);
+
+        assert!(Font::from_collection(&data, 0).is_some());
+        assert!(Font::from_collection(&data, 1).is_some());
+        assert!(Font::from_collection(&data, 2).is_none());
+
+        // Not a collection.
+        assert!(Font::from_data(&font, 0).is_some());
+        assert!(Font::from_collection(&font, 0).is_none());
+    }
+
+    #[test]
+    fn bbox_to_rect() {
+        assert_eq!(BBox::new().to_rect(), None);
+
+        let mut bbox = BBox::new();
+        bbox.extend_by(10.0, -20.0);
+        bbox.extend_by(-5.0, 15.0);
+        assert_eq!(bbox.to_rect(), Some(Rect { x_min: -5, y_min: -20, x_max: 10, y_max: 15 }));
+
+        let mut bbox = BBox::new();
+        bbox.extend_by(40000.0, 0.0);
+        assert_eq!(bbox.to_rect(), None);
+    }
 }
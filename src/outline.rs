@@ -0,0 +1,1002 @@
+//! Reusable `OutlineBuilder` adapters.
+
+#[cfg(feature = "std")]
+use std::string::String;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::OutlineBuilder;
+
+
+/// An `OutlineBuilder` that ignores all segments.
+///
+/// Useful when only a glyph bounding box returned by `Font::outline_glyph()` is needed.
+///
+/// # Example
+///
+/// ```
+/// use ttf_parser::{Font, GlyphId, DummyOutline};
+///
+/// let data = std::fs::read("fonts/SourceSansPro-Regular-Tiny.ttf").unwrap();
+/// let font = Font::from_data(&data, 0).unwrap();
+/// let bbox = font.outline_glyph(GlyphId(13), &mut DummyOutline).unwrap();
+/// assert!(bbox.width() > 0);
+/// ```
+#[derive(Clone, Copy, Default, Debug)]
+pub struct DummyOutline;
+
+impl OutlineBuilder for DummyOutline {
+    #[inline] fn move_to(&mut self, _: f32, _: f32) {}
+    #[inline] fn line_to(&mut self, _: f32, _: f32) {}
+    #[inline] fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {}
+    #[inline] fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {}
+    #[inline] fn close(&mut self) {}
+}
+
+
+// Counts contours while passing all segments to the inner builder.
+pub(crate) struct ContourCounter<'a> {
+    builder: &'a mut dyn OutlineBuilder,
+    contours: u16,
+}
+
+impl<'a> ContourCounter<'a> {
+    #[inline]
+    pub fn new(builder: &'a mut dyn OutlineBuilder) -> Self {
+        ContourCounter { builder, contours: 0 }
+    }
+
+    #[inline]
+    pub fn contours(&self) -> u16 {
+        self.contours
+    }
+}
+
+impl OutlineBuilder for ContourCounter<'_> {
+    #[inline]
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.contours = self.contours.saturating_add(1);
+        self.builder.move_to(x, y);
+    }
+
+    #[inline]
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.builder.line_to(x, y);
+    }
+
+    #[inline]
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    #[inline]
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.builder.curve_to(x1, y1, x2, y2, x, y);
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+
+/// An `OutlineBuilder` that applies an affine transform to all coordinates
+/// before passing them to the inner builder.
+///
+/// The matrix is stored as `[a, b, c, d, e, f]`, so a point is transformed as:
+///
+/// ```text
+/// x' = a * x + c * y + e
+/// y' = b * x + d * y + f
+/// ```
+///
+/// Note that a bounding box returned by `Font::outline_glyph()` will not be transformed.
+///
+/// # Example
+///
+/// ```
+/// use ttf_parser::{Font, GlyphId, OutlineBuilder, TransformBuilder};
+///
+/// # struct Builder;
+/// # impl OutlineBuilder for Builder {
+/// #     fn move_to(&mut self, _: f32, _: f32) {}
+/// #     fn line_to(&mut self, _: f32, _: f32) {}
+/// #     fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {}
+/// #     fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {}
+/// #     fn close(&mut self) {}
+/// # }
+/// let data = std::fs::read("fonts/SourceSansPro-Regular-Tiny.ttf").unwrap();
+/// let font = Font::from_data(&data, 0).unwrap();
+///
+/// // Convert font units to pixels.
+/// let scale = 16.0 / f32::from(font.units_per_em().unwrap());
+/// let mut builder = Builder;
+/// let mut builder = TransformBuilder::new([scale, 0.0, 0.0, scale, 0.0, 0.0], &mut builder);
+/// font.outline_glyph(GlyphId(13), &mut builder).unwrap();
+/// ```
+pub struct TransformBuilder<'a> {
+    builder: &'a mut dyn OutlineBuilder,
+    matrix: [f32; 6],
+}
+
+impl<'a> TransformBuilder<'a> {
+    /// Creates a new builder.
+    #[inline]
+    pub fn new(matrix: [f32; 6], builder: &'a mut dyn OutlineBuilder) -> Self {
+        TransformBuilder { builder, matrix }
+    }
+
+    /// Returns the transform matrix.
+    #[inline]
+    pub fn matrix(&self) -> [f32; 6] {
+        self.matrix
+    }
+
+    #[inline]
+    fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let [a, b, c, d, e, f] = self.matrix;
+        (a * x + c * y + e, b * x + d * y + f)
+    }
+}
+
+impl OutlineBuilder for TransformBuilder<'_> {
+    #[inline]
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.apply(x, y);
+        self.builder.move_to(x, y);
+    }
+
+    #[inline]
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.apply(x, y);
+        self.builder.line_to(x, y);
+    }
+
+    #[inline]
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.apply(x1, y1);
+        let (x, y) = self.apply(x, y);
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    #[inline]
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.apply(x1, y1);
+        let (x2, y2) = self.apply(x2, y2);
+        let (x, y) = self.apply(x, y);
+        self.builder.curve_to(x1, y1, x2, y2, x, y);
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+impl core::fmt::Debug for TransformBuilder<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "TransformBuilder({:?})", self.matrix)
+    }
+}
+
+
+/// A trait for outlines that consist only of lines and quadratic curves.
+///
+/// Used by `QuadBuilder`.
+pub trait QuadOutlineBuilder {
+    /// Appends a MoveTo segment.
+    fn move_to(&mut self, x: f32, y: f32);
+
+    /// Appends a LineTo segment.
+    fn line_to(&mut self, x: f32, y: f32);
+
+    /// Appends a QuadTo segment.
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32);
+
+    /// Appends a ClosePath segment.
+    fn close(&mut self);
+}
+
+
+// Prevents too many segments on huge curves or a tiny tolerance.
+const MAX_QUADS_PER_CUBIC: u16 = 64;
+
+/// An `OutlineBuilder` that converts cubic curves into quadratic ones.
+///
+/// Each cubic curve is split into as many quadratic curves as required
+/// to keep the approximation error within the specified tolerance,
+/// but no more than 64.
+///
+/// Useful for `CFF` fonts, since they use only cubic curves.
+pub struct QuadBuilder<'a> {
+    builder: &'a mut dyn QuadOutlineBuilder,
+    tolerance: f32,
+    start: Point,
+    last: Point,
+}
+
+impl<'a> QuadBuilder<'a> {
+    /// Creates a new builder.
+    ///
+    /// `tolerance` is the maximum allowed distance between the original cubic curve
+    /// and its approximation, in font units.
+    #[inline]
+    pub fn new(tolerance: f32, builder: &'a mut dyn QuadOutlineBuilder) -> Self {
+        QuadBuilder {
+            builder,
+            tolerance,
+            start: Point::default(),
+            last: Point::default(),
+        }
+    }
+
+    // The error of approximating a cubic with a single quadratic curve
+    // is bounded by `sqrt(3) / 36 * |p3 - 3 * p2 + 3 * p1 - p0|`.
+    // Splitting a cubic into `n` parts reduces this error by `n^3`.
+    fn quads_count(&self, p0: Point, p1: Point, p2: Point, p3: Point) -> u16 {
+        let dx = p3.x - 3.0 * p2.x + 3.0 * p1.x - p0.x;
+        let dy = p3.y - 3.0 * p2.y + 3.0 * p1.y - p0.y;
+
+        // Compare squared values, since `sqrt` is not available in `no_std`.
+        let err_sq = (dx * dx + dy * dy) * (3.0 / 1296.0);
+        let tolerance_sq = self.tolerance * self.tolerance;
+
+        let mut n = 1;
+        while n < MAX_QUADS_PER_CUBIC {
+            // Computed in `f32`, since `n^3` doesn't fit into `u16` for n > 40.
+            let nf = f32::from(n);
+            let n3 = nf * nf * nf;
+            if err_sq <= tolerance_sq * n3 * n3 {
+                break;
+            }
+
+            n += 1;
+        }
+
+        n
+    }
+}
+
+impl OutlineBuilder for QuadBuilder<'_> {
+    #[inline]
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = Point { x, y };
+        self.last = self.start;
+        self.builder.move_to(x, y);
+    }
+
+    #[inline]
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.last = Point { x, y };
+        self.builder.line_to(x, y);
+    }
+
+    #[inline]
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.last = Point { x, y };
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p0 = self.last;
+        let p1 = Point { x: x1, y: y1 };
+        let p2 = Point { x: x2, y: y2 };
+        let p3 = Point { x, y };
+
+        let n = self.quads_count(p0, p1, p2, p3);
+        for i in 0..n {
+            let t0 = f32::from(i) / f32::from(n);
+            let t1 = f32::from(i + 1) / f32::from(n);
+            let (q0, q1, q2, q3) = cubic_segment(p0, p1, p2, p3, t0, t1);
+
+            // The best single quadratic control point for a cubic segment.
+            let cx = (3.0 * (q1.x + q2.x) - q0.x - q3.x) / 4.0;
+            let cy = (3.0 * (q1.y + q2.y) - q0.y - q3.y) / 4.0;
+
+            // Use exact end points to prevent gaps.
+            let end = if i + 1 == n { p3 } else { q3 };
+            self.builder.quad_to(cx, cy, end.x, end.y);
+        }
+
+        self.last = p3;
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        // The current point returns to the start of the contour.
+        self.last = self.start;
+        self.builder.close();
+    }
+}
+
+impl core::fmt::Debug for QuadBuilder<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "QuadBuilder({})", self.tolerance)
+    }
+}
+
+
+// Limits the recursion depth, so up to 2^10 lines per curve.
+const MAX_FLATTEN_DEPTH: u8 = 10;
+
+// Prevents subdividing up to the depth limit on every curve.
+const MIN_FLATTEN_TOLERANCE: f32 = 0.01;
+
+/// An `OutlineBuilder` that converts curves into lines.
+///
+/// Each curve is adaptively subdivided until it's flat enough,
+/// so the inner builder will receive only `move_to`, `line_to` and `close` calls.
+pub struct FlattenBuilder<'a> {
+    builder: &'a mut dyn OutlineBuilder,
+    tolerance: f32,
+    start: Point,
+    last: Point,
+}
+
+impl<'a> FlattenBuilder<'a> {
+    /// Creates a new builder.
+    ///
+    /// `tolerance` is the maximum allowed distance between a curve
+    /// and its approximation, in font units.
+    /// Values below 0.01, including NaN, are treated as 0.01.
+    #[inline]
+    pub fn new(tolerance: f32, builder: &'a mut dyn OutlineBuilder) -> Self {
+        // Written this way to handle NaN.
+        let tolerance = if tolerance > MIN_FLATTEN_TOLERANCE {
+            tolerance
+        } else {
+            MIN_FLATTEN_TOLERANCE
+        };
+
+        FlattenBuilder {
+            builder,
+            tolerance,
+            start: Point::default(),
+            last: Point::default(),
+        }
+    }
+
+    // Based on the flatness test by Roger Willcocks.
+    fn is_flat(&self, p0: Point, p1: Point, p2: Point, p3: Point) -> bool {
+        let ux = 3.0 * p1.x - 2.0 * p0.x - p3.x;
+        let uy = 3.0 * p1.y - 2.0 * p0.y - p3.y;
+        let vx = 3.0 * p2.x - 2.0 * p3.x - p0.x;
+        let vy = 3.0 * p2.y - 2.0 * p3.y - p0.y;
+
+        let dx = if ux * ux > vx * vx { ux * ux } else { vx * vx };
+        let dy = if uy * uy > vy * vy { uy * uy } else { vy * vy };
+        dx + dy <= 16.0 * self.tolerance * self.tolerance
+    }
+
+    fn flatten(&mut self, p0: Point, p1: Point, p2: Point, p3: Point, depth: u8) {
+        if depth == MAX_FLATTEN_DEPTH || self.is_flat(p0, p1, p2, p3) {
+            self.builder.line_to(p3.x, p3.y);
+            return;
+        }
+
+        // Split in half using de Casteljau's algorithm.
+        let p01 = p0.lerp(p1, 0.5);
+        let p12 = p1.lerp(p2, 0.5);
+        let p23 = p2.lerp(p3, 0.5);
+        let p012 = p01.lerp(p12, 0.5);
+        let p123 = p12.lerp(p23, 0.5);
+        let mid = p012.lerp(p123, 0.5);
+
+        self.flatten(p0, p01, p012, mid, depth + 1);
+        self.flatten(mid, p123, p23, p3, depth + 1);
+    }
+}
+
+impl OutlineBuilder for FlattenBuilder<'_> {
+    #[inline]
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = Point { x, y };
+        self.last = self.start;
+        self.builder.move_to(x, y);
+    }
+
+    #[inline]
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.last = Point { x, y };
+        self.builder.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        // Elevate to a cubic curve.
+        let p0 = self.last;
+        let p3 = Point { x, y };
+        let c = Point { x: x1, y: y1 };
+        let p1 = p0.lerp(c, 2.0 / 3.0);
+        let p2 = p3.lerp(c, 2.0 / 3.0);
+        self.flatten(p0, p1, p2, p3, 0);
+        self.last = p3;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p3 = Point { x, y };
+        self.flatten(self.last, Point { x: x1, y: y1 }, Point { x: x2, y: y2 }, p3, 0);
+        self.last = p3;
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        // The current point returns to the start of the contour.
+        self.last = self.start;
+        self.builder.close();
+    }
+}
+
+impl core::fmt::Debug for FlattenBuilder<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "FlattenBuilder({})", self.tolerance)
+    }
+}
+
+/// An `OutlineBuilder` that writes segments as an SVG path data string.
+///
+/// Numbers are rounded to the specified amount of fractional digits
+/// and written without trailing zeros.
+///
+/// Since fonts use a Y-up coordinate system and SVG uses a Y-down one,
+/// the Y axis can be flipped. In this case, a glyph should be translated by its ascender
+/// or a bounding box height afterwards.
+///
+/// # Example
+///
+/// ```
+/// use ttf_parser::{Font, GlyphId, SvgPathBuilder};
+///
+/// let data = std::fs::read("fonts/SourceSansPro-Regular-Tiny.ttf").unwrap();
+/// let font = Font::from_data(&data, 0).unwrap();
+/// let mut builder = SvgPathBuilder::new(0, false);
+/// font.outline_glyph(GlyphId(13), &mut builder).unwrap();
+/// assert_eq!(builder.finish(), "M 90 0 L 90 656 L 173 656 L 173 71 L 460 71 L 460 0 L 90 0 Z");
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Default, Debug)]
+pub struct SvgPathBuilder {
+    path: String,
+    precision: u8,
+    flip_y: bool,
+}
+
+#[cfg(feature = "std")]
+impl SvgPathBuilder {
+    /// Creates a new builder.
+    ///
+    /// `precision` is the maximum number of fractional digits.
+    #[inline]
+    pub fn new(precision: u8, flip_y: bool) -> Self {
+        SvgPathBuilder {
+            path: String::new(),
+            precision,
+            flip_y,
+        }
+    }
+
+    /// Returns the accumulated path data.
+    #[inline]
+    pub fn finish(mut self) -> String {
+        if self.path.ends_with(' ') {
+            self.path.pop();
+        }
+
+        self.path
+    }
+
+    fn push_segment(&mut self, cmd: char, points: &[(f32, f32)]) {
+        self.path.push(cmd);
+        for &(x, y) in points {
+            let y = if self.flip_y { -y } else { y };
+            self.push_number(x);
+            self.push_number(y);
+        }
+
+        self.path.push(' ');
+    }
+
+    fn push_number(&mut self, n: f32) {
+        use core::fmt::Write;
+
+        self.path.push(' ');
+        let start = self.path.len();
+        write!(&mut self.path, "{:.*}", usize::from(self.precision), n).unwrap();
+
+        if self.path[start..].contains('.') {
+            let len = self.path.trim_end_matches('0').trim_end_matches('.').len();
+            self.path.truncate(len);
+        }
+
+        // Rounding can produce a negative zero.
+        if &self.path[start..] == "-0" {
+            self.path.truncate(start);
+            self.path.push('0');
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl OutlineBuilder for SvgPathBuilder {
+    #[inline]
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.push_segment('M', &[(x, y)]);
+    }
+
+    #[inline]
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push_segment('L', &[(x, y)]);
+    }
+
+    #[inline]
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.push_segment('Q', &[(x1, y1), (x, y)]);
+    }
+
+    #[inline]
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.push_segment('C', &[(x1, y1), (x2, y2), (x, y)]);
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        self.path.push_str("Z ");
+    }
+}
+
+
+/// A path segment recorded by `OutlineCommands`.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub enum PathCommand {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    CurveTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+/// An `OutlineBuilder` that records segments as a list of `PathCommand`.
+///
+/// # Example
+///
+/// ```
+/// use ttf_parser::{Font, GlyphId, OutlineCommands, PathCommand};
+///
+/// let data = std::fs::read("fonts/SourceSansPro-Regular-Tiny.ttf").unwrap();
+/// let font = Font::from_data(&data, 0).unwrap();
+/// let mut builder = OutlineCommands::new();
+/// font.outline_glyph(GlyphId(13), &mut builder).unwrap();
+/// let commands = builder.finish();
+/// assert_eq!(commands.first(), Some(&PathCommand::MoveTo(90.0, 0.0)));
+/// assert_eq!(commands.last(), Some(&PathCommand::Close));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Default, Debug)]
+pub struct OutlineCommands {
+    commands: Vec<PathCommand>,
+}
+
+#[cfg(feature = "std")]
+impl OutlineCommands {
+    /// Creates a new builder.
+    #[inline]
+    pub fn new() -> Self {
+        OutlineCommands::default()
+    }
+
+    /// Returns the recorded commands.
+    #[inline]
+    pub fn finish(self) -> Vec<PathCommand> {
+        self.commands
+    }
+}
+
+#[cfg(feature = "std")]
+impl OutlineBuilder for OutlineCommands {
+    #[inline]
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.commands.push(PathCommand::MoveTo(x, y));
+    }
+
+    #[inline]
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.commands.push(PathCommand::LineTo(x, y));
+    }
+
+    #[inline]
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.commands.push(PathCommand::QuadTo(x1, y1, x, y));
+    }
+
+    #[inline]
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.commands.push(PathCommand::CurveTo(x1, y1, x2, y2, x, y));
+    }
+
+    #[inline]
+    fn close(&mut self) {
+        self.commands.push(PathCommand::Close);
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+impl Point {
+    #[inline]
+    fn lerp(self, other: Point, t: f32) -> Point {
+        Point {
+            x: self.x + t * (other.x - self.x),
+            y: self.y + t * (other.y - self.y),
+        }
+    }
+}
+
+#[inline]
+fn cubic_at(p0: Point, p1: Point, p2: Point, p3: Point, t: f32) -> Point {
+    let a = p0.lerp(p1, t);
+    let b = p1.lerp(p2, t);
+    let c = p2.lerp(p3, t);
+    a.lerp(b, t).lerp(b.lerp(c, t), t)
+}
+
+// Returns control points of a cubic curve part in the `t0..t1` range.
+fn cubic_segment(
+    p0: Point, p1: Point, p2: Point, p3: Point,
+    t0: f32, t1: f32,
+) -> (Point, Point, Point, Point) {
+    let q0 = cubic_at(p0, p1, p2, p3, t0);
+    let q3 = cubic_at(p0, p1, p2, p3, t1);
+
+    // Derivatives at segment ends, scaled to the segment length.
+    let dt = (t1 - t0) / 3.0;
+    let d0 = cubic_derivative(p0, p1, p2, p3, t0);
+    let d1 = cubic_derivative(p0, p1, p2, p3, t1);
+
+    let q1 = Point { x: q0.x + d0.x * dt, y: q0.y + d0.y * dt };
+    let q2 = Point { x: q3.x - d1.x * dt, y: q3.y - d1.y * dt };
+    (q0, q1, q2, q3)
+}
+
+#[inline]
+fn cubic_derivative(p0: Point, p1: Point, p2: Point, p3: Point, t: f32) -> Point {
+    let mt = 1.0 - t;
+    let a = 3.0 * mt * mt;
+    let b = 6.0 * mt * t;
+    let c = 3.0 * t * t;
+    Point {
+        x: a * (p1.x - p0.x) + b * (p2.x - p1.x) + c * (p3.x - p2.x),
+        y: a * (p1.y - p0.y) + b * (p2.y - p1.y) + c * (p3.y - p2.y),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::String;
+    use std::vec::Vec;
+    use std::fmt::Write;
+
+    struct Builder(String);
+    impl OutlineBuilder for Builder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "M {} {} ", x, y).unwrap();
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "L {} {} ", x, y).unwrap();
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            write!(&mut self.0, "Q {} {} {} {} ", x1, y1, x, y).unwrap();
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            write!(&mut self.0, "C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y).unwrap();
+        }
+
+        fn close(&mut self) {
+            write!(&mut self.0, "Z ").unwrap();
+        }
+    }
+
+    #[test]
+    fn contour_counter() {
+        let mut inner = Builder(String::new());
+        let mut builder = ContourCounter::new(&mut inner);
+        builder.move_to(1.0, 2.0);
+        builder.line_to(3.0, 4.0);
+        builder.close();
+        builder.move_to(5.0, 6.0);
+        builder.quad_to(7.0, 8.0, 9.0, 10.0);
+        builder.close();
+
+        assert_eq!(builder.contours(), 2);
+        assert_eq!(inner.0, "M 1 2 L 3 4 Z M 5 6 Q 7 8 9 10 Z ");
+    }
+
+    #[test]
+    fn svg_path() {
+        let mut builder = SvgPathBuilder::new(2, false);
+        builder.move_to(1.0, 2.5);
+        builder.line_to(3.126, -4.0);
+        builder.quad_to(5.0, 6.0, 7.0, 8.0);
+        builder.curve_to(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+        builder.close();
+
+        assert_eq!(builder.finish(), "M 1 2.5 L 3.13 -4 Q 5 6 7 8 C 1 2 3 4 5 6 Z");
+    }
+
+    #[test]
+    fn svg_path_flip_y() {
+        let mut builder = SvgPathBuilder::new(0, true);
+        builder.move_to(10.4, 20.0);
+        builder.line_to(-0.2, -30.0);
+        builder.line_to(0.0, 0.0);
+        builder.close();
+
+        assert_eq!(builder.finish(), "M 10 -20 L 0 30 L 0 0 Z");
+    }
+
+    #[test]
+    fn svg_path_empty() {
+        assert_eq!(SvgPathBuilder::default().finish(), "");
+    }
+
+    #[test]
+    fn outline_commands() {
+        let mut builder = OutlineCommands::new();
+        builder.move_to(1.0, 2.0);
+        builder.line_to(3.0, 4.0);
+        builder.quad_to(5.0, 6.0, 7.0, 8.0);
+        builder.curve_to(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+        builder.close();
+
+        assert_eq!(builder.finish(), vec![
+            PathCommand::MoveTo(1.0, 2.0),
+            PathCommand::LineTo(3.0, 4.0),
+            PathCommand::QuadTo(5.0, 6.0, 7.0, 8.0),
+            PathCommand::CurveTo(1.0, 2.0, 3.0, 4.0, 5.0, 6.0),
+            PathCommand::Close,
+        ]);
+    }
+
+    #[test]
+    fn transform_scale_and_translate() {
+        let mut inner = Builder(String::new());
+        let mut builder = TransformBuilder::new([2.0, 0.0, 0.0, 0.5, 10.0, 20.0], &mut inner);
+        builder.move_to(1.0, 2.0);
+        builder.line_to(3.0, 4.0);
+        builder.quad_to(5.0, 6.0, 7.0, 8.0);
+        builder.curve_to(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+        builder.close();
+
+        assert_eq!(inner.0, "M 12 21 L 16 22 Q 20 23 24 24 C 12 21 16 22 20 23 Z ");
+    }
+
+    #[test]
+    fn transform_rotate() {
+        let mut inner = Builder(String::new());
+        // 90 degrees counter-clockwise.
+        let mut builder = TransformBuilder::new([0.0, 1.0, -1.0, 0.0, 0.0, 0.0], &mut inner);
+        builder.move_to(1.0, 0.0);
+        builder.line_to(0.0, 1.0);
+
+        assert_eq!(inner.0, "M 0 1 L -1 0 ");
+    }
+
+    struct Quads {
+        start: Point,
+        quads: Vec<(Point, Point)>,
+    }
+
+    impl QuadOutlineBuilder for Quads {
+        fn move_to(&mut self, x: f32, y: f32) {
+            self.start = Point { x, y };
+        }
+
+        fn line_to(&mut self, _: f32, _: f32) {}
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            self.quads.push((Point { x: x1, y: y1 }, Point { x, y }));
+        }
+
+        fn close(&mut self) {}
+    }
+
+    fn check_cubic_to_quad(tolerance: f32, p: [Point; 4]) -> usize {
+        let mut quads = Quads { start: Point::default(), quads: Vec::new() };
+        let mut builder = QuadBuilder::new(tolerance, &mut quads);
+        builder.move_to(p[0].x, p[0].y);
+        builder.curve_to(p[1].x, p[1].y, p[2].x, p[2].y, p[3].x, p[3].y);
+
+        let n = quads.quads.len();
+        let mut prev = quads.start;
+        for (i, (ctrl, end)) in quads.quads.iter().enumerate() {
+            for j in 0..=10 {
+                let s = j as f32 / 10.0;
+                let q = prev.lerp(*ctrl, s).lerp(ctrl.lerp(*end, s), s);
+                let t = (i as f32 + s) / n as f32;
+                let c = cubic_at(p[0], p[1], p[2], p[3], t);
+                let dist = ((q.x - c.x).powi(2) + (q.y - c.y).powi(2)).sqrt();
+                assert!(dist <= tolerance + 0.001, "{} > {}", dist, tolerance);
+            }
+
+            prev = *end;
+        }
+
+        assert_eq!(prev.x, p[3].x);
+        assert_eq!(prev.y, p[3].y);
+        n
+    }
+
+    #[test]
+    fn cubic_to_quad() {
+        let curve = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 0.0, y: 500.0 },
+            Point { x: 1000.0, y: -500.0 },
+            Point { x: 1000.0, y: 0.0 },
+        ];
+
+        let coarse = check_cubic_to_quad(10.0, curve);
+        let fine = check_cubic_to_quad(0.1, curve);
+        assert!(coarse > 1);
+        assert!(fine > coarse);
+    }
+
+    #[test]
+    fn cubic_to_quad_exact() {
+        // A quadratic curve written as a cubic one.
+        let curve = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 200.0, y: 200.0 },
+            Point { x: 400.0, y: 200.0 },
+            Point { x: 600.0, y: 0.0 },
+        ];
+
+        assert_eq!(check_cubic_to_quad(0.01, curve), 1);
+    }
+
+    #[test]
+    fn cubic_to_quad_zero_tolerance() {
+        let curve = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 0.0, y: 500.0 },
+            Point { x: 1000.0, y: -500.0 },
+            Point { x: 1000.0, y: 0.0 },
+        ];
+
+        for tolerance in &[0.0, core::f32::NAN] {
+            let mut quads = Quads { start: Point::default(), quads: Vec::new() };
+            let mut builder = QuadBuilder::new(*tolerance, &mut quads);
+            builder.move_to(0.0, 0.0);
+            builder.curve_to(0.0, 500.0, 1000.0, -500.0, 1000.0, 0.0);
+            assert_eq!(quads.quads.len(), usize::from(MAX_QUADS_PER_CUBIC));
+
+            let (_, end) = quads.quads.last().unwrap();
+            assert_eq!((end.x, end.y), (curve[3].x, curve[3].y));
+        }
+    }
+
+    #[test]
+    fn cubic_to_quad_after_close() {
+        let mut quads = Quads { start: Point::default(), quads: Vec::new() };
+        let mut builder = QuadBuilder::new(0.5, &mut quads);
+        builder.move_to(0.0, 0.0);
+        builder.line_to(100.0, 0.0);
+        builder.close();
+        // Starts at (0, 0), so this is a straight line.
+        builder.curve_to(10.0, 10.0, 20.0, 20.0, 30.0, 30.0);
+
+        assert_eq!(quads.quads.len(), 1);
+        let (ctrl, end) = quads.quads[0];
+        assert_eq!((ctrl.x, ctrl.y), (15.0, 15.0));
+        assert_eq!((end.x, end.y), (30.0, 30.0));
+    }
+
+    struct Lines {
+        points: Vec<Point>,
+        closed: bool,
+    }
+
+    impl OutlineBuilder for Lines {
+        fn move_to(&mut self, x: f32, y: f32) {
+            self.points.push(Point { x, y });
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            self.points.push(Point { x, y });
+        }
+
+        fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {
+            panic!("unexpected quad_to");
+        }
+
+        fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {
+            panic!("unexpected curve_to");
+        }
+
+        fn close(&mut self) {
+            self.closed = true;
+        }
+    }
+
+    #[test]
+    fn flatten_curve() {
+        let p = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 0.0, y: 500.0 },
+            Point { x: 1000.0, y: -500.0 },
+            Point { x: 1000.0, y: 0.0 },
+        ];
+
+        let mut lines = Lines { points: Vec::new(), closed: false };
+        let mut builder = FlattenBuilder::new(0.5, &mut lines);
+        builder.move_to(p[0].x, p[0].y);
+        builder.curve_to(p[1].x, p[1].y, p[2].x, p[2].y, p[3].x, p[3].y);
+        builder.close();
+
+        assert!(lines.closed);
+        assert!(lines.points.len() > 2);
+
+        let last = lines.points.last().unwrap();
+        assert_eq!((last.x, last.y), (1000.0, 0.0));
+
+        // Every point must lie on the curve.
+        for point in &lines.points {
+            let closest = (0..=1000)
+                .map(|i| cubic_at(p[0], p[1], p[2], p[3], i as f32 / 1000.0))
+                .map(|c| ((point.x - c.x).powi(2) + (point.y - c.y).powi(2)).sqrt())
+                .fold(core::f32::MAX, f32::min);
+            assert!(closest < 1.0);
+        }
+    }
+
+    #[test]
+    fn flatten_line() {
+        let mut lines = Lines { points: Vec::new(), closed: false };
+        let mut builder = FlattenBuilder::new(0.5, &mut lines);
+        builder.move_to(0.0, 0.0);
+        builder.curve_to(10.0, 10.0, 20.0, 20.0, 30.0, 30.0);
+        builder.quad_to(40.0, 40.0, 50.0, 50.0);
+
+        assert_eq!(lines.points.len(), 3);
+    }
+
+    #[test]
+    fn flatten_zero_tolerance() {
+        for &tolerance in &[0.0, -1.0, core::f32::NAN] {
+            let mut lines = Lines { points: Vec::new(), closed: false };
+            let mut builder = FlattenBuilder::new(tolerance, &mut lines);
+            builder.move_to(0.0, 0.0);
+            builder.curve_to(0.0, 1000.0, 1000.0, 1000.0, 1000.0, 0.0);
+
+            // Must not subdivide up to the depth limit.
+            assert!(lines.points.len() > 2);
+            assert!(lines.points.len() < 1 + (1 << MAX_FLATTEN_DEPTH));
+
+            let last = lines.points.last().unwrap();
+            assert_eq!((last.x, last.y), (1000.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn flatten_after_close() {
+        let mut lines = Lines { points: Vec::new(), closed: false };
+        let mut builder = FlattenBuilder::new(0.5, &mut lines);
+        builder.move_to(0.0, 0.0);
+        builder.line_to(100.0, 0.0);
+        builder.close();
+        // Starts at (0, 0), so this is a straight line.
+        builder.quad_to(5.0, 5.0, 10.0, 10.0);
+
+        assert!(lines.closed);
+        assert_eq!(lines.points.len(), 3);
+    }
+}
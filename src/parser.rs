@@ -60,6 +60,13 @@ impl FromData for i32 {
     }
 }
 
+impl FromData for i64 {
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        data.try_into().ok().map(i64::from_be_bytes)
+    }
+}
+
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/otff#data-types
 #[derive(Clone, Copy, Debug)]
@@ -100,13 +107,21 @@ impl FromData for F2DOT14 {
 #[derive(Clone, Copy, Debug)]
 pub struct Fixed(pub f32);
 
+impl Fixed {
+    /// Creates a number from raw 16.16 bits.
+    #[inline]
+    pub fn from_bits(n: i32) -> Self {
+        // Casting `i32` to `f32` is lossy for large values, but never fails.
+        Fixed(n as f32 / 65536.0)
+    }
+}
+
 impl FromData for Fixed {
     const SIZE: usize = 4;
 
     #[inline]
     fn parse(data: &[u8]) -> Option<Self> {
-        // TODO: is it safe to cast?
-        i32::parse(data).map(|n| Fixed(n as f32 / 65536.0))
+        i32::parse(data).map(Fixed::from_bits)
     }
 }
 
@@ -251,7 +266,22 @@ impl<'a, T: FromData> LazyArray16<'a, T> {
         self.binary_search_by(|p| p.cmp(key))
     }
 
+    /// Performs a binary search by a key extracted with specified closure.
+    ///
+    /// The array must be sorted by this key in ascending order.
+    /// Otherwise, the result is unspecified.
+    #[inline]
+    pub fn binary_search_by_key<K, F>(&self, key: &K, mut f: F) -> Option<(u16, T)>
+        where F: FnMut(&T) -> K, K: Ord
+    {
+        self.binary_search_by(|p| f(p).cmp(key))
+    }
+
     /// Performs a binary search using specified closure.
+    ///
+    /// The closure must return an ordering of an array item relative to the target,
+    /// and the array must be sorted in ascending order according to it.
+    /// Otherwise, the result is unspecified.
     #[inline]
     pub fn binary_search_by<F>(&self, mut f: F) -> Option<(u16, T)>
         where F: FnMut(&T) -> core::cmp::Ordering
@@ -392,7 +422,22 @@ impl<'a, T: FromData> LazyArray32<'a, T> {
         self.binary_search_by(|p| p.cmp(key))
     }
 
+    /// Performs a binary search by a key extracted with specified closure.
+    ///
+    /// The array must be sorted by this key in ascending order.
+    /// Otherwise, the result is unspecified.
+    #[inline]
+    pub fn binary_search_by_key<K, F>(&self, key: &K, mut f: F) -> Option<(u32, T)>
+        where F: FnMut(&T) -> K, K: Ord
+    {
+        self.binary_search_by(|p| f(p).cmp(key))
+    }
+
     /// Performs a binary search using specified closure.
+    ///
+    /// The closure must return an ordering of an array item relative to the target,
+    /// and the array must be sorted in ascending order according to it.
+    /// Otherwise, the result is unspecified.
     #[inline]
     pub fn binary_search_by<F>(&self, mut f: F) -> Option<(u32, T)>
         where F: FnMut(&T) -> core::cmp::Ordering
@@ -539,6 +584,18 @@ impl<'a> Stream<'a> {
         self.read_bytes(T::SIZE).and_then(T::parse)
     }
 
+    /// Reads a 2.14 fixed number.
+    #[inline]
+    pub fn read_f2dot14(&mut self) -> Option<f32> {
+        self.read::<F2DOT14>().map(|n| n.to_f32())
+    }
+
+    /// Reads a 16.16 fixed number.
+    #[inline]
+    pub fn read_fixed(&mut self) -> Option<f32> {
+        self.read::<Fixed>().map(|n| n.0)
+    }
+
     #[inline]
     pub fn read_at<T: FromData>(data: &[u8], offset: usize) -> Option<T> {
         data.get(offset..offset + T::SIZE).and_then(T::parse)
@@ -648,3 +705,76 @@ pub fn f32_bound(min: f32, val: f32, max: f32) -> f32 {
 
     val
 }
+
+/// Rounds half away from zero, since `f32::round` is not available in `no_std`.
+#[inline]
+pub fn f32_round(val: f32) -> f32 {
+    // Values outside this range have no fractional part.
+    if !(val > -8388608.0 && val < 8388608.0) {
+        return val;
+    }
+
+    let trunc = val as i32 as f32;
+    let fract = val - trunc;
+    if fract >= 0.5 {
+        trunc + 1.0
+    } else if fract <= -0.5 {
+        trunc - 1.0
+    } else {
+        trunc
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_numbers() {
+        let data = [
+            0x40, 0x00, // 1.0
+            0xE0, 0x00, // -0.5
+            0x00, 0x01, 0x80, 0x00, // 1.5
+            0xFF, 0xFF, 0x80, 0x00, // -0.5
+            0x00, 0x00,
+        ];
+
+        let mut s = Stream::new(&data);
+        assert_eq!(s.read_f2dot14(), Some(1.0));
+        assert_eq!(s.read_f2dot14(), Some(-0.5));
+        assert_eq!(s.read_fixed(), Some(1.5));
+        assert_eq!(s.read_fixed(), Some(-0.5));
+        assert_eq!(s.read_fixed(), None);
+    }
+
+    #[test]
+    fn round() {
+        assert_eq!(f32_round(0.0), 0.0);
+        assert_eq!(f32_round(1.4), 1.0);
+        assert_eq!(f32_round(1.5), 2.0);
+        assert_eq!(f32_round(-1.4), -1.0);
+        assert_eq!(f32_round(-1.5), -2.0);
+        assert_eq!(f32_round(-10.7), -11.0);
+        assert_eq!(f32_round(1e10), 1e10);
+    }
+
+    #[test]
+    fn binary_search_by_key() {
+        let data = [0, 1, 0, 3, 0, 5, 0, 7];
+        let array = LazyArray16::<u16>::new(&data);
+        assert_eq!(array.binary_search_by_key(&5, |v| *v), Some((2, 5)));
+        assert_eq!(array.binary_search_by_key(&1, |v| *v), Some((0, 1)));
+        assert_eq!(array.binary_search_by_key(&7, |v| *v), Some((3, 7)));
+        assert_eq!(array.binary_search_by_key(&4, |v| *v), None);
+        assert_eq!(array.binary_search_by_key(&20, |v| v * 2), None);
+        assert_eq!(array.binary_search_by_key(&14, |v| v * 2), Some((3, 7)));
+
+        let array = LazyArray32::<u16>::new(&data);
+        assert_eq!(array.binary_search_by_key(&3, |v| *v), Some((1, 3)));
+        assert_eq!(array.binary_search_by_key(&0, |v| *v), None);
+
+        let array = LazyArray16::<u16>::new(&[]);
+        assert_eq!(array.binary_search_by_key(&0, |v| *v), None);
+    }
+}
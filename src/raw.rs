@@ -65,8 +65,14 @@ impl FromData for TableRecord {
 
 pub mod head {
     pub const TABLE_SIZE: usize = 54;
+    pub const MAGIC_NUMBER_OFFSET: usize = 12;
+    pub const FLAGS_OFFSET: usize = 16;
     pub const UNITS_PER_EM_OFFSET: usize = 18;
+    pub const CREATED_OFFSET: usize = 20;
+    pub const MODIFIED_OFFSET: usize = 28;
+    pub const MAC_STYLE_OFFSET: usize = 44;
     pub const INDEX_TO_LOC_FORMAT_OFFSET: usize = 50;
+    pub const GLYPH_DATA_FORMAT_OFFSET: usize = 52;
 }
 
 pub mod hhea {
@@ -125,8 +131,10 @@ pub mod vhea {
 
 pub mod post {
     pub const TABLE_SIZE: usize = 32;
+    pub const ITALIC_ANGLE_OFFSET: usize = 4;
     pub const UNDERLINE_POSITION_OFFSET: usize = 8;
     pub const UNDERLINE_THICKNESS_OFFSET: usize = 10;
+    pub const IS_FIXED_PITCH_OFFSET: usize = 12;
 }
 
 pub mod cmap {
@@ -394,11 +402,14 @@ pub mod os_2 {
     pub const Y_SUPERSCRIPT_X_SIZE_OFFSET: usize = 18;
     pub const Y_STRIKEOUT_SIZE_OFFSET: usize = 26;
     pub const Y_STRIKEOUT_POSITION_OFFSET: usize = 28;
+    pub const UL_UNICODE_RANGE1_OFFSET: usize = 42;
     pub const FS_SELECTION_OFFSET: usize = 62;
     pub const S_TYPO_ASCENDER_OFFSET: usize = 68;
     pub const S_TYPO_DESCENDER_OFFSET: usize = 70;
     pub const S_TYPO_LINE_GAP_OFFSET: usize = 72;
+    pub const UL_CODE_PAGE_RANGE1_OFFSET: usize = 78;
     pub const SX_HEIGHT_OFFSET: usize = 86;
+    pub const S_CAP_HEIGHT_OFFSET: usize = 88;
 }
 
 pub mod name {
@@ -457,44 +468,44 @@ pub mod name {
             Self::new(data)
         }
     }
-}
-
-pub mod kern {
-    use crate::parser::FromData;
 
     #[derive(Clone, Copy)]
-    pub struct Coverage {
-        data: [u8; 2],
+    pub struct LangTagRecord {
+        data: [u8; 4],
     }
 
-    impl Coverage {
-        pub const SIZE: usize = 2;
+    impl LangTagRecord {
+        pub const SIZE: usize = 4;
 
         #[inline(always)]
         pub fn new(input: &[u8]) -> Option<Self> {
             use core::convert::TryInto;
-            input.try_into().ok().map(|data| Coverage { data })
+            input.try_into().ok().map(|data| LangTagRecord { data })
         }
 
         #[inline(always)]
-        pub fn coverage(&self) -> u8 {
-            self.data[0]
+        pub fn length(&self) -> u16 {
+            u16::from_be_bytes([self.data[0], self.data[1]])
         }
 
         #[inline(always)]
-        pub fn format(&self) -> u8 {
-            self.data[1]
+        pub fn offset(&self) -> u16 {
+            u16::from_be_bytes([self.data[2], self.data[3]])
         }
     }
 
-    impl FromData for Coverage {
-        const SIZE: usize = Coverage::SIZE;
+    impl FromData for LangTagRecord {
+        const SIZE: usize = LangTagRecord::SIZE;
 
         #[inline]
         fn parse(data: &[u8]) -> Option<Self> {
             Self::new(data)
         }
     }
+}
+
+pub mod kern {
+    use crate::parser::FromData;
 
     #[derive(Clone, Copy)]
     pub struct KerningRecord {
@@ -532,8 +543,8 @@ pub mod kern {
 }
 
 pub mod gdef {
-    use crate::parser::FromData;
-    use crate::GlyphId;
+    use crate::parser::{FromData, Offset16};
+    use crate::{GlyphId, Tag};
     use core::ops::RangeInclusive;
 
     #[derive(Clone, Copy)]
@@ -605,6 +616,45 @@ pub mod gdef {
             Self::new(data)
         }
     }
+
+    #[derive(Clone, Copy)]
+    pub struct TagRecord {
+        data: [u8; 6],
+    }
+
+    impl TagRecord {
+        pub const SIZE: usize = 6;
+
+        #[inline(always)]
+        pub fn new(input: &[u8]) -> Option<Self> {
+            use core::convert::TryInto;
+            input.try_into().ok().map(|data| TagRecord { data })
+        }
+
+        #[inline(always)]
+        pub fn tag(&self) -> Tag {
+            Tag(u32::from_be_bytes([
+                self.data[0],
+                self.data[1],
+                self.data[2],
+                self.data[3],
+            ]))
+        }
+
+        #[inline(always)]
+        pub fn offset(&self) -> Offset16 {
+            Offset16(u16::from_be_bytes([self.data[4], self.data[5]]))
+        }
+    }
+
+    impl FromData for TagRecord {
+        const SIZE: usize = TagRecord::SIZE;
+
+        #[inline]
+        fn parse(data: &[u8]) -> Option<Self> {
+            Self::new(data)
+        }
+    }
 }
 
 pub mod avar {
@@ -681,20 +731,35 @@ pub mod fvar {
 
         #[inline(always)]
         pub fn min_value(&self) -> f32 {
-            i32::from_be_bytes([self.data[4], self.data[5], self.data[6], self.data[7]]) as f32
-                / 65536.0
+            crate::parser::Fixed::from_bits(i32::from_be_bytes([
+                self.data[4],
+                self.data[5],
+                self.data[6],
+                self.data[7],
+            ]))
+            .0
         }
 
         #[inline(always)]
         pub fn def_value(&self) -> f32 {
-            i32::from_be_bytes([self.data[8], self.data[9], self.data[10], self.data[11]]) as f32
-                / 65536.0
+            crate::parser::Fixed::from_bits(i32::from_be_bytes([
+                self.data[8],
+                self.data[9],
+                self.data[10],
+                self.data[11],
+            ]))
+            .0
         }
 
         #[inline(always)]
         pub fn max_value(&self) -> f32 {
-            i32::from_be_bytes([self.data[12], self.data[13], self.data[14], self.data[15]]) as f32
-                / 65536.0
+            crate::parser::Fixed::from_bits(i32::from_be_bytes([
+                self.data[12],
+                self.data[13],
+                self.data[14],
+                self.data[15],
+            ]))
+            .0
         }
 
         #[inline(always)]
@@ -908,3 +973,129 @@ pub mod svg {
         }
     }
 }
+
+pub mod colr {
+    use crate::parser::FromData;
+    use crate::GlyphId;
+
+    #[derive(Clone, Copy)]
+    pub struct BaseGlyphRecord {
+        data: [u8; 6],
+    }
+
+    impl BaseGlyphRecord {
+        pub const SIZE: usize = 6;
+
+        #[inline(always)]
+        pub fn new(input: &[u8]) -> Option<Self> {
+            use core::convert::TryInto;
+            input.try_into().ok().map(|data| BaseGlyphRecord { data })
+        }
+
+        #[inline(always)]
+        pub fn glyph_id(&self) -> GlyphId {
+            GlyphId(u16::from_be_bytes([self.data[0], self.data[1]]))
+        }
+
+        #[inline(always)]
+        pub fn first_layer_index(&self) -> u16 {
+            u16::from_be_bytes([self.data[2], self.data[3]])
+        }
+
+        #[inline(always)]
+        pub fn num_layers(&self) -> u16 {
+            u16::from_be_bytes([self.data[4], self.data[5]])
+        }
+    }
+
+    impl FromData for BaseGlyphRecord {
+        const SIZE: usize = BaseGlyphRecord::SIZE;
+
+        #[inline]
+        fn parse(data: &[u8]) -> Option<Self> {
+            Self::new(data)
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct LayerRecord {
+        data: [u8; 4],
+    }
+
+    impl LayerRecord {
+        pub const SIZE: usize = 4;
+
+        #[inline(always)]
+        pub fn new(input: &[u8]) -> Option<Self> {
+            use core::convert::TryInto;
+            input.try_into().ok().map(|data| LayerRecord { data })
+        }
+
+        #[inline(always)]
+        pub fn glyph_id(&self) -> GlyphId {
+            GlyphId(u16::from_be_bytes([self.data[0], self.data[1]]))
+        }
+
+        #[inline(always)]
+        pub fn palette_index(&self) -> u16 {
+            u16::from_be_bytes([self.data[2], self.data[3]])
+        }
+    }
+
+    impl FromData for LayerRecord {
+        const SIZE: usize = LayerRecord::SIZE;
+
+        #[inline]
+        fn parse(data: &[u8]) -> Option<Self> {
+            Self::new(data)
+        }
+    }
+}
+
+pub mod cpal {
+    use crate::parser::FromData;
+
+    #[derive(Clone, Copy)]
+    pub struct ColorRecord {
+        data: [u8; 4],
+    }
+
+    impl ColorRecord {
+        pub const SIZE: usize = 4;
+
+        #[inline(always)]
+        pub fn new(input: &[u8]) -> Option<Self> {
+            use core::convert::TryInto;
+            input.try_into().ok().map(|data| ColorRecord { data })
+        }
+
+        #[inline(always)]
+        pub fn blue(&self) -> u8 {
+            self.data[0]
+        }
+
+        #[inline(always)]
+        pub fn green(&self) -> u8 {
+            self.data[1]
+        }
+
+        #[inline(always)]
+        pub fn red(&self) -> u8 {
+            self.data[2]
+        }
+
+        #[inline(always)]
+        pub fn alpha(&self) -> u8 {
+            self.data[3]
+        }
+    }
+
+    impl FromData for ColorRecord {
+        const SIZE: usize = ColorRecord::SIZE;
+
+        #[inline]
+        fn parse(data: &[u8]) -> Option<Self> {
+            Self::new(data)
+        }
+    }
+}
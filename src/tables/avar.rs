@@ -60,11 +60,10 @@ impl<'a> Table<'a> {
 fn map_value(map: &LazyArray16<raw::AxisValueMapRecord>, value: i16) -> Option<i16> {
     // This code is based on harfbuzz implementation.
 
-    if map.len() == 0 {
+    // 'The segment map must contain at least three entries
+    // (-1.0 to -1.0, 0 to 0 and 1.0 to 1.0)'. Otherwise the map is ignored.
+    if map.len() < 3 {
         return Some(value);
-    } else if map.len() == 1 {
-        let record = map.get(0)?;
-        return Some(value - record.from_coordinate() + record.to_coordinate());
     }
 
     let record_0 = map.get(0)?;
@@ -105,3 +104,56 @@ fn map_value(map: &LazyArray16<raw::AxisValueMapRecord>, value: i16) -> Option<i
     let value = prev_to + k / denom;
     i16::try_from(value).ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn table(maps: &[&[(i16, i16)]]) -> std::vec::Vec<u8> {
+        let mut data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(0), // reserved
+            UInt16(maps.len() as u16), // axisCount
+        ]);
+
+        for map in maps {
+            data.extend_from_slice(&writer::convert(&[UInt16(map.len() as u16)]));
+            for (from, to) in map.iter() {
+                data.extend_from_slice(&writer::convert(&[Int16(*from), Int16(*to)]));
+            }
+        }
+
+        data
+    }
+
+    #[test]
+    fn map_coordinates() {
+        let data = table(&[
+            &[(-16384, -16384), (0, 0), (8192, 4096), (16384, 16384)],
+            &[(-16384, -16384), (16384, 16384)], // too short
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+
+        let mut coords = [NormalizedCoord(4096), NormalizedCoord(4096)];
+        table.map_coordinates(&mut coords).unwrap();
+        assert_eq!(coords, [NormalizedCoord(2048), NormalizedCoord(4096)]);
+
+        let mut coords = [NormalizedCoord(12288), NormalizedCoord(-100)];
+        table.map_coordinates(&mut coords).unwrap();
+        assert_eq!(coords, [NormalizedCoord(10240), NormalizedCoord(-100)]);
+
+        let mut coords = [NormalizedCoord(-8192), NormalizedCoord(0)];
+        table.map_coordinates(&mut coords).unwrap();
+        assert_eq!(coords, [NormalizedCoord(-8192), NormalizedCoord(0)]);
+    }
+
+    #[test]
+    fn axis_count_mismatch() {
+        let data = table(&[&[(-16384, -16384), (0, 0), (16384, 16384)]]);
+        let table = Table::parse(&data).unwrap();
+        assert!(table.map_coordinates(&mut []).is_none());
+    }
+}
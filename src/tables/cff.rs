@@ -6,18 +6,21 @@
 use core::convert::TryFrom;
 use core::ops::Range;
 
-use crate::{GlyphId, OutlineBuilder, Rect, BBox};
-use crate::parser::{Stream, U24, Fixed, FromData, NumFrom, TryNumFrom};
+use crate::{GlyphId, OutlineBuilder, HintBuilder, Rect, BBox, DummyOutline};
+use crate::parser::{Stream, U24, FromData, NumFrom, TryNumFrom, LazyArray16};
 
 // Limits according to the Adobe Technical Note #5176, chapter 4 DICT Data.
 const MAX_OPERANDS_LEN: u8 = 48;
 
 // Limits according to the Adobe Technical Note #5177 Appendix B.
-const STACK_LIMIT: u8 = 10;
+const DEFAULT_STACK_LIMIT: u8 = 10;
 const MAX_ARGUMENTS_STACK_LEN: usize = 48;
 
 const END_OF_FLOAT_FLAG: u8 = 0xf;
 
+// Only Type 2 charstrings are supported.
+const DEFAULT_CHAR_STRING_TYPE: i32 = 2;
+
 const TWO_BYTE_OPERATOR_MARK: u8 = 12;
 
 /// Enumerates some operators defined in the Adobe Technical Note #5177.
@@ -56,35 +59,221 @@ mod operator {
 /// Enumerates some operators defined in the Adobe Technical Note #5176,
 /// Table 9 Top DICT Operator Entries
 mod top_dict_operator {
+    pub const CHARSET_OFFSET: u16               = 15;
+    pub const ENCODING_OFFSET: u16              = 16;
+    pub const CHAR_STRING_TYPE: u16             = 1206;
     pub const CHAR_STRINGS_OFFSET: u16          = 17;
     pub const PRIVATE_DICT_SIZE_AND_OFFSET: u16 = 18;
+    pub const ROS: u16                          = 1230;
+}
+
+/// Enumerates predefined charsets defined in the Adobe Technical Note #5176,
+/// Table 22 Charset ID
+mod charset_id {
+    pub const ISO_ADOBE: usize      = 0;
+    pub const EXPERT: usize         = 1;
+    pub const EXPERT_SUBSET: usize  = 2;
+}
+
+/// Enumerates predefined encodings defined in the Adobe Technical Note #5176,
+/// Table 16 Encoding ID
+mod encoding_id {
+    pub const STANDARD: usize   = 0;
+    pub const EXPERT: usize     = 1;
 }
 
 /// Enumerates some operators defined in the Adobe Technical Note #5176,
 /// Table 23 Private DICT Operators
 mod private_dict_operator {
+    pub const BLUE_VALUES: u16              = 6;
+    pub const OTHER_BLUES: u16              = 7;
+    pub const FAMILY_BLUES: u16             = 8;
+    pub const FAMILY_OTHER_BLUES: u16       = 9;
+    pub const STD_HW: u16                   = 10;
+    pub const STD_VW: u16                   = 11;
+    pub const BLUE_SCALE: u16               = 1209;
+    pub const BLUE_SHIFT: u16               = 1210;
+    pub const BLUE_FUZZ: u16                = 1211;
     pub const LOCAL_SUBROUTINES_OFFSET: u16 = 19;
 }
 
 
 /// A list of errors that can occur during a CFF table parsing.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum CFFError {
+    /// Charstring or subroutine data ended unexpectedly.
     ReadOutOfBounds,
+    /// An outline has no area.
+    ///
+    /// Not returned by validation, since empty glyphs are valid.
     ZeroBBox,
+    /// An unknown operator was found.
     InvalidOperator,
+    /// A known operator that is not supported by this charstring format was found.
     UnsupportedOperator,
+    /// A charstring doesn't end with the `endchar` operator.
     MissingEndChar,
+    /// A charstring has data after the `endchar` operator.
     DataAfterEndChar,
+    /// Subroutines are nested deeper than `CffSettings::max_subroutine_depth`.
     NestingLimitReached,
+    /// An arguments stack has more values than the format allows.
     ArgumentsStackLimitReached,
+    /// An operator has an unexpected number of arguments.
     InvalidArgumentsStackLength,
+    /// An outline bounding box cannot be represented by `Rect`.
     BboxOverflow,
+    /// A path operator was used before the first `moveto`.
     MissingMoveTo,
+    /// A subroutine index is out of bounds.
     InvalidSubroutineIndex,
+    /// A `vsindex` operator references a missing ItemVariationData.
+    ///
+    /// `CFF2` only.
     InvalidItemVariationDataIndex,
+    /// A `blend` operator has an unexpected number of operands.
+    ///
+    /// `CFF2` only.
     InvalidNumberOfBlendOperands,
+    /// A `blend` operator references more than 64 regions.
+    ///
+    /// `CFF2` only.
     BlendRegionsLimitReached,
+    /// A font uses a CharstringType other than 2.
+    UnsupportedCharstringType,
+}
+
+
+/// CFF outlining settings.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CffSettings {
+    /// The maximum subroutines nesting depth.
+    ///
+    /// Charstrings that go deeper will be treated as malformed.
+    ///
+    /// Default: 10, as per the Adobe Technical Note #5177.
+    pub max_subroutine_depth: u8,
+}
+
+impl Default for CffSettings {
+    #[inline]
+    fn default() -> Self {
+        CffSettings {
+            max_subroutine_depth: DEFAULT_STACK_LIMIT,
+        }
+    }
+}
+
+
+/// Alignment zones and standard stem widths from the CFF Private DICT.
+///
+/// All zones are stored as absolute `bottom, top` pairs.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct CffBlueZones {
+    blue_values: BlueArray,
+    other_blues: BlueArray,
+    family_blues: BlueArray,
+    family_other_blues: BlueArray,
+    blue_scale: Option<f32>,
+    blue_shift: Option<f32>,
+    blue_fuzz: Option<f32>,
+    std_hw: Option<f32>,
+    std_vw: Option<f32>,
+}
+
+impl CffBlueZones {
+    /// Returns the `BlueValues` zones.
+    #[inline]
+    pub fn blue_values(&self) -> &[f32] {
+        self.blue_values.as_slice()
+    }
+
+    /// Returns the `OtherBlues` zones.
+    #[inline]
+    pub fn other_blues(&self) -> &[f32] {
+        self.other_blues.as_slice()
+    }
+
+    /// Returns the `FamilyBlues` zones.
+    #[inline]
+    pub fn family_blues(&self) -> &[f32] {
+        self.family_blues.as_slice()
+    }
+
+    /// Returns the `FamilyOtherBlues` zones.
+    #[inline]
+    pub fn family_other_blues(&self) -> &[f32] {
+        self.family_other_blues.as_slice()
+    }
+
+    /// Returns the `BlueScale` value.
+    #[inline]
+    pub fn blue_scale(&self) -> Option<f32> {
+        self.blue_scale
+    }
+
+    /// Returns the `BlueShift` value.
+    #[inline]
+    pub fn blue_shift(&self) -> Option<f32> {
+        self.blue_shift
+    }
+
+    /// Returns the `BlueFuzz` value.
+    #[inline]
+    pub fn blue_fuzz(&self) -> Option<f32> {
+        self.blue_fuzz
+    }
+
+    /// Returns the `StdHW` value.
+    #[inline]
+    pub fn std_hw(&self) -> Option<f32> {
+        self.std_hw
+    }
+
+    /// Returns the `StdVW` value.
+    #[inline]
+    pub fn std_vw(&self) -> Option<f32> {
+        self.std_vw
+    }
+}
+
+
+// BlueValues can have up to 7 pairs, while other arrays up to 5.
+const MAX_BLUE_VALUES_LEN: usize = 14;
+
+#[derive(Clone, Copy, Default, Debug)]
+struct BlueArray {
+    data: [f32; MAX_BLUE_VALUES_LEN],
+    len: u8,
+}
+
+impl BlueArray {
+    fn parse(dict_parser: &mut DictionaryParser) -> Self {
+        // Zones can be set using real numbers, which `parse_operands()` doesn't preserve.
+        let mut deltas = [0.0; MAX_BLUE_VALUES_LEN];
+        let len = dict_parser.parse_real_operands(&mut deltas);
+        Self::from_deltas(&deltas[..len])
+    }
+
+    // Operands are delta-encoded, so we have to resolve them first.
+    fn from_deltas(operands: &[f32]) -> Self {
+        let mut array = BlueArray::default();
+        let mut value = 0.0;
+        for (i, delta) in operands.iter().take(MAX_BLUE_VALUES_LEN).enumerate() {
+            value += *delta;
+            array.data[i] = value;
+            array.len += 1;
+        }
+
+        // Zones are pairs, so an odd value is meaningless.
+        array.len &= !1;
+        array
+    }
+
+    #[inline]
+    fn as_slice(&self) -> &[f32] {
+        &self.data[..usize::from(self.len)]
+    }
 }
 
 
@@ -93,6 +282,76 @@ pub struct Metadata<'a> {
     global_subrs: DataIndex<'a>,
     local_subrs: DataIndex<'a>,
     char_strings: DataIndex<'a>,
+    char_string_type: i32,
+    blue_zones: CffBlueZones,
+    strings: DataIndex<'a>,
+    // Not set for CID-keyed fonts, since they do not have glyph names.
+    charset: Option<Charset<'a>>,
+    // Not set for CID-keyed fonts.
+    encoding: Option<Encoding<'a>>,
+}
+
+impl<'a> Metadata<'a> {
+    /// Returns alignment zones from the Private DICT.
+    #[inline]
+    pub fn blue_zones(&self) -> &CffBlueZones {
+        &self.blue_zones
+    }
+
+    /// Checks that the CharStrings INDEX has the specified glyph.
+    #[inline]
+    pub fn contains_glyph(&self, glyph_id: GlyphId) -> bool {
+        glyph_id.0 < self.char_strings.len()
+    }
+
+    /// Returns a glyph ID by its name using the charset.
+    ///
+    /// Returns `None` for CID-keyed fonts.
+    pub fn glyph_index_by_name(&self, name: &str) -> Option<GlyphId> {
+        let charset = self.charset?;
+        let sid = match STANDARD_NAMES.iter().position(|n| *n == name) {
+            Some(index) => index,
+            None => {
+                let index = self.strings.into_iter().position(|n| n == name.as_bytes())?;
+                STANDARD_NAMES.len() + index
+            }
+        };
+
+        charset.sid_to_gid(u16::try_from(sid).ok()?, self.char_strings.len())
+    }
+
+    /// Returns a glyph ID by a character code using the encoding.
+    ///
+    /// Returns `None` for CID-keyed fonts.
+    pub fn code_to_gid(&self, code: u8) -> Option<GlyphId> {
+        let encoding = self.encoding?;
+        encoding.code_to_gid(self.charset.as_ref(), code, self.char_strings.len())
+    }
+
+    /// Checks that a glyph charstring is structurally valid.
+    ///
+    /// Interprets the charstring without producing an outline
+    /// and stops on the first error.
+    /// Glyphs without an outline, like a space, are valid.
+    pub fn validate_charstring(&self, settings: CffSettings, glyph_id: GlyphId) -> Result<(), CFFError> {
+        let data = self.char_strings.get(glyph_id.0).ok_or(CFFError::ReadOutOfBounds)?;
+        match parse_char_string_with_hints(data, self, settings, &mut DummyOutline, None) {
+            Ok(_) | Err(CFFError::ZeroBBox) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Checks that all glyph charstrings are structurally valid.
+    ///
+    /// Returns the first invalid glyph and its error.
+    pub fn validate_all(&self, settings: CffSettings) -> Result<(), (GlyphId, CFFError)> {
+        for id in 0..self.char_strings.len() {
+            let glyph_id = GlyphId(id);
+            self.validate_charstring(settings, glyph_id).map_err(|e| (glyph_id, e))?;
+        }
+
+        Ok(())
+    }
 }
 
 pub(crate) fn parse_metadata(data: &[u8]) -> Option<Metadata> {
@@ -116,25 +375,30 @@ pub(crate) fn parse_metadata(data: &[u8]) -> Option<Metadata> {
     // Skip Name INDEX.
     skip_index(&mut s)?;
 
-    let (char_strings_offset, private_dict_range) = parse_top_dict(&mut s)?;
+    let top_dict = parse_top_dict(&mut s)?;
+    let char_strings_offset = top_dict.char_strings_offset;
+    let private_dict_range = top_dict.private_dict_range;
 
     // Must be set, otherwise there are nothing to parse.
     if char_strings_offset == 0 {
         return None;
     }
 
-    let subroutines_offset = if let Some(range) = private_dict_range.clone() {
+    let private_dict = if let Some(range) = private_dict_range.clone() {
         parse_private_dict(data.get(range)?)
     } else {
-        None
+        PrivateDict::default()
     };
+    let subroutines_offset = private_dict.local_subroutines_offset;
 
-    // Skip String INDEX.
-    skip_index(&mut s)?;
+    let strings = parse_index(&mut s)?;
 
     // Parse Global Subroutines INDEX.
     let mut metadata = Metadata::default();
+    metadata.strings = strings;
     metadata.global_subrs = parse_index(&mut s)?;
+    metadata.char_string_type = top_dict.char_string_type;
+    metadata.blue_zones = private_dict.blue_zones;
 
     match (private_dict_range, subroutines_offset) {
         (Some(private_dict_range), Some(subroutines_offset)) => {
@@ -155,22 +419,46 @@ pub(crate) fn parse_metadata(data: &[u8]) -> Option<Metadata> {
         parse_index(&mut s)?
     };
 
+    // A malformed charset should not affect outlining.
+    if !top_dict.has_ros {
+        let number_of_glyphs = metadata.char_strings.len();
+        metadata.charset = top_dict.charset_offset
+            .and_then(|offset| Charset::parse(data, offset, number_of_glyphs));
+        metadata.encoding = top_dict.encoding_offset
+            .and_then(|offset| Encoding::parse(data, offset));
+    }
+
     Some(metadata)
 }
 
 
 pub fn outline(
     metadata: &Metadata,
+    settings: CffSettings,
     glyph_id: GlyphId,
     builder: &mut dyn OutlineBuilder,
+    hints: Option<&mut dyn HintBuilder>,
 ) -> Option<Rect> {
     let data = metadata.char_strings.get(glyph_id.0)?;
-    parse_char_string(data, metadata, builder).ok()
+    parse_char_string_with_hints(data, metadata, settings, builder, hints).ok()
+}
+
+struct TopDict {
+    char_strings_offset: usize,
+    private_dict_range: Option<Range<usize>>,
+    char_string_type: i32,
+    charset_offset: Option<usize>,
+    encoding_offset: Option<usize>,
+    has_ros: bool,
 }
 
-fn parse_top_dict(s: &mut Stream) -> Option<(usize, Option<Range<usize>>)> {
+fn parse_top_dict(s: &mut Stream) -> Option<TopDict> {
     let mut char_strings_offset = 0;
     let mut private_dict_range = None;
+    let mut char_string_type = DEFAULT_CHAR_STRING_TYPE;
+    let mut charset_offset = Some(charset_id::ISO_ADOBE);
+    let mut encoding_offset = Some(encoding_id::STANDARD);
+    let mut has_ros = false;
 
     let index = parse_index(s)?;
 
@@ -199,38 +487,752 @@ fn parse_top_dict(s: &mut Stream) -> Option<(usize, Option<Range<usize>>)> {
                     private_dict_range = Some(start..end);
                 }
             }
-            _ => {}
-        }
+            top_dict_operator::CHAR_STRING_TYPE => {
+                dict_parser.parse_operands()?;
+                let operands = dict_parser.operands();
+
+                if operands.len() == 1 {
+                    char_string_type = operands[0];
+                }
+            }
+            top_dict_operator::CHARSET_OFFSET => {
+                dict_parser.parse_operands()?;
+                let operands = dict_parser.operands();
+
+                if operands.len() == 1 {
+                    // An invalid offset should not affect outlining.
+                    charset_offset = usize::try_from(operands[0]).ok();
+                }
+            }
+            top_dict_operator::ENCODING_OFFSET => {
+                dict_parser.parse_operands()?;
+                let operands = dict_parser.operands();
 
-        if char_strings_offset != 0 && private_dict_range.is_some() {
-            break;
+                if operands.len() == 1 {
+                    // An invalid offset should not affect outlining.
+                    encoding_offset = usize::try_from(operands[0]).ok();
+                }
+            }
+            top_dict_operator::ROS => {
+                has_ros = true;
+            }
+            _ => {}
         }
     }
 
-    Some((char_strings_offset, private_dict_range))
+    Some(TopDict {
+        char_strings_offset,
+        private_dict_range,
+        char_string_type,
+        charset_offset,
+        encoding_offset,
+        has_ros,
+    })
 }
 
-fn parse_private_dict(data: &[u8]) -> Option<usize> {
-    let mut subroutines_offset = None;
+#[derive(Clone, Copy, Default, Debug)]
+struct PrivateDict {
+    local_subroutines_offset: Option<usize>,
+    blue_zones: CffBlueZones,
+}
+
+fn parse_private_dict(data: &[u8]) -> PrivateDict {
+    let mut dict = PrivateDict::default();
     let mut dict_parser = DictionaryParser::new(data);
     while let Some(operator) = dict_parser.parse_next() {
-        if operator.get() == private_dict_operator::LOCAL_SUBROUTINES_OFFSET {
-            dict_parser.parse_operands()?;
-            let operands = dict_parser.operands();
+        let zones = &mut dict.blue_zones;
+        match operator.get() {
+            private_dict_operator::LOCAL_SUBROUTINES_OFFSET => {
+                dict.local_subroutines_offset = dict_parser.parse_operands()
+                    .and_then(|_| match dict_parser.operands() {
+                        [n] => usize::try_from(*n).ok(),
+                        _ => None,
+                    });
+            }
+            private_dict_operator::BLUE_VALUES => {
+                zones.blue_values = BlueArray::parse(&mut dict_parser);
+            }
+            private_dict_operator::OTHER_BLUES => {
+                zones.other_blues = BlueArray::parse(&mut dict_parser);
+            }
+            private_dict_operator::FAMILY_BLUES => {
+                zones.family_blues = BlueArray::parse(&mut dict_parser);
+            }
+            private_dict_operator::FAMILY_OTHER_BLUES => {
+                zones.family_other_blues = BlueArray::parse(&mut dict_parser);
+            }
+            private_dict_operator::STD_HW => zones.std_hw = dict_parser.parse_real_operand(),
+            private_dict_operator::STD_VW => zones.std_vw = dict_parser.parse_real_operand(),
+            private_dict_operator::BLUE_SCALE => zones.blue_scale = dict_parser.parse_real_operand(),
+            private_dict_operator::BLUE_SHIFT => zones.blue_shift = dict_parser.parse_real_operand(),
+            private_dict_operator::BLUE_FUZZ => zones.blue_fuzz = dict_parser.parse_real_operand(),
+            _ => {}
+        }
+    }
+
+    dict
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Charset<'a> {
+    IsoAdobe,
+    Expert,
+    ExpertSubset,
+    Format0(LazyArray16<'a, u16>),
+    Format1(&'a [u8]),
+    Format2(&'a [u8]),
+}
+
+impl<'a> Charset<'a> {
+    fn parse(data: &'a [u8], offset: usize, number_of_glyphs: u16) -> Option<Self> {
+        match offset {
+            charset_id::ISO_ADOBE => return Some(Charset::IsoAdobe),
+            charset_id::EXPERT => return Some(Charset::Expert),
+            charset_id::EXPERT_SUBSET => return Some(Charset::ExpertSubset),
+            _ => {}
+        }
+
+        let mut s = Stream::new_at(data, offset)?;
+        let format: u8 = s.read()?;
+        // `.notdef` is always the first glyph and is not stored in a charset.
+        let count = number_of_glyphs.checked_sub(1)?;
+        match format {
+            0 => Some(Charset::Format0(s.read_array16(count)?)),
+            1 => Some(Charset::Format1(s.tail()?)),
+            2 => Some(Charset::Format2(s.tail()?)),
+            _ => None,
+        }
+    }
+
+    fn sid_to_gid(&self, sid: u16, number_of_glyphs: u16) -> Option<GlyphId> {
+        if sid == 0 {
+            return Some(GlyphId(0));
+        }
+
+        match self {
+            Charset::IsoAdobe => {
+                // ISOAdobe charset maps SIDs 1..=228 to the same glyph IDs.
+                if sid <= 228 && sid < number_of_glyphs {
+                    Some(GlyphId(sid))
+                } else {
+                    None
+                }
+            }
+            // Expert charsets use SIDs that are not mapped one-to-one to glyph IDs
+            // and are not supported.
+            Charset::Expert | Charset::ExpertSubset => None,
+            Charset::Format0(array) => {
+                let index = array.into_iter().position(|n| n == sid)?;
+                u16::try_from(index + 1).ok().map(GlyphId)
+            }
+            Charset::Format1(data) => {
+                Self::range_sid_to_gid::<u8>(data, sid, number_of_glyphs)
+            }
+            Charset::Format2(data) => {
+                Self::range_sid_to_gid::<u16>(data, sid, number_of_glyphs)
+            }
+        }
+    }
 
-            if operands.len() == 1 {
-                subroutines_offset = usize::try_from(operands[0]).ok();
+    fn range_sid_to_gid<T>(data: &[u8], sid: u16, number_of_glyphs: u16) -> Option<GlyphId>
+        where T: FromData, u16: From<T>
+    {
+        let mut s = Stream::new(data);
+        let mut glyph_id = 1u16;
+        while glyph_id < number_of_glyphs {
+            let first: u16 = s.read()?;
+            let left = u16::from(s.read::<T>()?);
+            if sid >= first && sid - first <= left {
+                return glyph_id.checked_add(sid - first).map(GlyphId);
             }
 
-            break;
+            glyph_id = glyph_id.checked_add(left)?.checked_add(1)?;
         }
+
+        None
+    }
+}
+
+
+#[derive(Clone, Copy, Debug)]
+struct EncodingRange {
+    first: u8,
+    left: u8,
+}
+
+impl FromData for EncodingRange {
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(EncodingRange { first: s.read()?, left: s.read()? })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct EncodingSupplement {
+    code: u8,
+    sid: u16,
+}
+
+impl FromData for EncodingSupplement {
+    const SIZE: usize = 3;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(EncodingSupplement { code: s.read()?, sid: s.read()? })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum EncodingKind<'a> {
+    Standard,
+    Expert,
+    Format0(LazyArray16<'a, u8>),
+    Format1(LazyArray16<'a, EncodingRange>),
+}
+
+/// A CFF encoding that maps character codes to glyph IDs.
+#[derive(Clone, Copy, Debug)]
+struct Encoding<'a> {
+    kind: EncodingKind<'a>,
+    supplements: LazyArray16<'a, EncodingSupplement>,
+}
+
+impl<'a> Encoding<'a> {
+    fn parse(data: &'a [u8], offset: usize) -> Option<Self> {
+        let kind = match offset {
+            encoding_id::STANDARD => EncodingKind::Standard,
+            encoding_id::EXPERT => EncodingKind::Expert,
+            _ => {
+                let mut s = Stream::new_at(data, offset)?;
+                let format: u8 = s.read()?;
+                let kind = match format & 0x7F {
+                    0 => {
+                        let count: u8 = s.read()?;
+                        EncodingKind::Format0(s.read_array16(u16::from(count))?)
+                    }
+                    1 => {
+                        let count: u8 = s.read()?;
+                        EncodingKind::Format1(s.read_array16(u16::from(count))?)
+                    }
+                    _ => return None,
+                };
+
+                // The high bit indicates that supplemental codes follow the encoding data.
+                let supplements = if format & 0x80 != 0 {
+                    let count: u8 = s.read()?;
+                    s.read_array16(u16::from(count))?
+                } else {
+                    LazyArray16::default()
+                };
+
+                return Some(Encoding { kind, supplements });
+            }
+        };
+
+        Some(Encoding { kind, supplements: LazyArray16::default() })
+    }
+
+    // Only predefined encodings and supplements require a charset,
+    // since custom encodings map codes to glyph IDs directly.
+    fn code_to_gid(
+        &self,
+        charset: Option<&Charset>,
+        code: u8,
+        number_of_glyphs: u16,
+    ) -> Option<GlyphId> {
+        let glyph_id = match self.kind {
+            EncodingKind::Standard => {
+                let sid = u16::from(STANDARD_ENCODING[usize::from(code)]);
+                if sid != 0 {
+                    charset?.sid_to_gid(sid, number_of_glyphs)
+                } else {
+                    None
+                }
+            }
+            EncodingKind::Expert => {
+                let sid = EXPERT_ENCODING[usize::from(code)];
+                if sid != 0 {
+                    charset?.sid_to_gid(sid, number_of_glyphs)
+                } else {
+                    None
+                }
+            }
+            EncodingKind::Format0(codes) => {
+                // Codes are stored for glyphs starting from 1, since `.notdef` is not encoded.
+                codes.into_iter().position(|c| c == code)
+                    .and_then(|index| u16::try_from(index + 1).ok())
+                    .map(GlyphId)
+            }
+            EncodingKind::Format1(ranges) => Self::range_code_to_gid(ranges, code),
+        };
+
+        glyph_id
+            .filter(|id| id.0 < number_of_glyphs)
+            .or_else(|| {
+                let supplement = self.supplements.into_iter().find(|s| s.code == code)?;
+                charset?.sid_to_gid(supplement.sid, number_of_glyphs)
+            })
     }
 
-    subroutines_offset
+    fn range_code_to_gid(ranges: LazyArray16<EncodingRange>, code: u8) -> Option<GlyphId> {
+        // Ranges are stored for glyphs starting from 1, since `.notdef` is not encoded.
+        let mut glyph_id = 1u16;
+        for range in ranges {
+            if code >= range.first && code - range.first <= range.left {
+                return glyph_id.checked_add(u16::from(code - range.first)).map(GlyphId);
+            }
+
+            glyph_id = glyph_id.checked_add(u16::from(range.left) + 1)?;
+        }
+
+        None
+    }
 }
 
-struct CharStringParserContext<'a> {
+// The CFF Standard Strings, indexed by SID.
+// Adobe Technical Note #5176, Appendix A
+const STANDARD_NAMES: &[&str] = &[
+    ".notdef",
+    "space",
+    "exclam",
+    "quotedbl",
+    "numbersign",
+    "dollar",
+    "percent",
+    "ampersand",
+    "quoteright",
+    "parenleft",
+    "parenright",
+    "asterisk",
+    "plus",
+    "comma",
+    "hyphen",
+    "period",
+    "slash",
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "colon",
+    "semicolon",
+    "less",
+    "equal",
+    "greater",
+    "question",
+    "at",
+    "A",
+    "B",
+    "C",
+    "D",
+    "E",
+    "F",
+    "G",
+    "H",
+    "I",
+    "J",
+    "K",
+    "L",
+    "M",
+    "N",
+    "O",
+    "P",
+    "Q",
+    "R",
+    "S",
+    "T",
+    "U",
+    "V",
+    "W",
+    "X",
+    "Y",
+    "Z",
+    "bracketleft",
+    "backslash",
+    "bracketright",
+    "asciicircum",
+    "underscore",
+    "quoteleft",
+    "a",
+    "b",
+    "c",
+    "d",
+    "e",
+    "f",
+    "g",
+    "h",
+    "i",
+    "j",
+    "k",
+    "l",
+    "m",
+    "n",
+    "o",
+    "p",
+    "q",
+    "r",
+    "s",
+    "t",
+    "u",
+    "v",
+    "w",
+    "x",
+    "y",
+    "z",
+    "braceleft",
+    "bar",
+    "braceright",
+    "asciitilde",
+    "exclamdown",
+    "cent",
+    "sterling",
+    "fraction",
+    "yen",
+    "florin",
+    "section",
+    "currency",
+    "quotesingle",
+    "quotedblleft",
+    "guillemotleft",
+    "guilsinglleft",
+    "guilsinglright",
+    "fi",
+    "fl",
+    "endash",
+    "dagger",
+    "daggerdbl",
+    "periodcentered",
+    "paragraph",
+    "bullet",
+    "quotesinglbase",
+    "quotedblbase",
+    "quotedblright",
+    "guillemotright",
+    "ellipsis",
+    "perthousand",
+    "questiondown",
+    "grave",
+    "acute",
+    "circumflex",
+    "tilde",
+    "macron",
+    "breve",
+    "dotaccent",
+    "dieresis",
+    "ring",
+    "cedilla",
+    "hungarumlaut",
+    "ogonek",
+    "caron",
+    "emdash",
+    "AE",
+    "ordfeminine",
+    "Lslash",
+    "Oslash",
+    "OE",
+    "ordmasculine",
+    "ae",
+    "dotlessi",
+    "lslash",
+    "oslash",
+    "oe",
+    "germandbls",
+    "onesuperior",
+    "logicalnot",
+    "mu",
+    "trademark",
+    "Eth",
+    "onehalf",
+    "plusminus",
+    "Thorn",
+    "onequarter",
+    "divide",
+    "brokenbar",
+    "degree",
+    "thorn",
+    "threequarters",
+    "twosuperior",
+    "registered",
+    "minus",
+    "eth",
+    "multiply",
+    "threesuperior",
+    "copyright",
+    "Aacute",
+    "Acircumflex",
+    "Adieresis",
+    "Agrave",
+    "Aring",
+    "Atilde",
+    "Ccedilla",
+    "Eacute",
+    "Ecircumflex",
+    "Edieresis",
+    "Egrave",
+    "Iacute",
+    "Icircumflex",
+    "Idieresis",
+    "Igrave",
+    "Ntilde",
+    "Oacute",
+    "Ocircumflex",
+    "Odieresis",
+    "Ograve",
+    "Otilde",
+    "Scaron",
+    "Uacute",
+    "Ucircumflex",
+    "Udieresis",
+    "Ugrave",
+    "Yacute",
+    "Ydieresis",
+    "Zcaron",
+    "aacute",
+    "acircumflex",
+    "adieresis",
+    "agrave",
+    "aring",
+    "atilde",
+    "ccedilla",
+    "eacute",
+    "ecircumflex",
+    "edieresis",
+    "egrave",
+    "iacute",
+    "icircumflex",
+    "idieresis",
+    "igrave",
+    "ntilde",
+    "oacute",
+    "ocircumflex",
+    "odieresis",
+    "ograve",
+    "otilde",
+    "scaron",
+    "uacute",
+    "ucircumflex",
+    "udieresis",
+    "ugrave",
+    "yacute",
+    "ydieresis",
+    "zcaron",
+    "exclamsmall",
+    "Hungarumlautsmall",
+    "dollaroldstyle",
+    "dollarsuperior",
+    "ampersandsmall",
+    "Acutesmall",
+    "parenleftsuperior",
+    "parenrightsuperior",
+    "twodotenleader",
+    "onedotenleader",
+    "zerooldstyle",
+    "oneoldstyle",
+    "twooldstyle",
+    "threeoldstyle",
+    "fouroldstyle",
+    "fiveoldstyle",
+    "sixoldstyle",
+    "sevenoldstyle",
+    "eightoldstyle",
+    "nineoldstyle",
+    "commasuperior",
+    "threequartersemdash",
+    "periodsuperior",
+    "questionsmall",
+    "asuperior",
+    "bsuperior",
+    "centsuperior",
+    "dsuperior",
+    "esuperior",
+    "isuperior",
+    "lsuperior",
+    "msuperior",
+    "nsuperior",
+    "osuperior",
+    "rsuperior",
+    "ssuperior",
+    "tsuperior",
+    "ff",
+    "ffi",
+    "ffl",
+    "parenleftinferior",
+    "parenrightinferior",
+    "Circumflexsmall",
+    "hyphensuperior",
+    "Gravesmall",
+    "Asmall",
+    "Bsmall",
+    "Csmall",
+    "Dsmall",
+    "Esmall",
+    "Fsmall",
+    "Gsmall",
+    "Hsmall",
+    "Ismall",
+    "Jsmall",
+    "Ksmall",
+    "Lsmall",
+    "Msmall",
+    "Nsmall",
+    "Osmall",
+    "Psmall",
+    "Qsmall",
+    "Rsmall",
+    "Ssmall",
+    "Tsmall",
+    "Usmall",
+    "Vsmall",
+    "Wsmall",
+    "Xsmall",
+    "Ysmall",
+    "Zsmall",
+    "colonmonetary",
+    "onefitted",
+    "rupiah",
+    "Tildesmall",
+    "exclamdownsmall",
+    "centoldstyle",
+    "Lslashsmall",
+    "Scaronsmall",
+    "Zcaronsmall",
+    "Dieresissmall",
+    "Brevesmall",
+    "Caronsmall",
+    "Dotaccentsmall",
+    "Macronsmall",
+    "figuredash",
+    "hypheninferior",
+    "Ogoneksmall",
+    "Ringsmall",
+    "Cedillasmall",
+    "questiondownsmall",
+    "oneeighth",
+    "threeeighths",
+    "fiveeighths",
+    "seveneighths",
+    "onethird",
+    "twothirds",
+    "zerosuperior",
+    "foursuperior",
+    "fivesuperior",
+    "sixsuperior",
+    "sevensuperior",
+    "eightsuperior",
+    "ninesuperior",
+    "zeroinferior",
+    "oneinferior",
+    "twoinferior",
+    "threeinferior",
+    "fourinferior",
+    "fiveinferior",
+    "sixinferior",
+    "seveninferior",
+    "eightinferior",
+    "nineinferior",
+    "centinferior",
+    "dollarinferior",
+    "periodinferior",
+    "commainferior",
+    "Agravesmall",
+    "Aacutesmall",
+    "Acircumflexsmall",
+    "Atildesmall",
+    "Adieresissmall",
+    "Aringsmall",
+    "AEsmall",
+    "Ccedillasmall",
+    "Egravesmall",
+    "Eacutesmall",
+    "Ecircumflexsmall",
+    "Edieresissmall",
+    "Igravesmall",
+    "Iacutesmall",
+    "Icircumflexsmall",
+    "Idieresissmall",
+    "Ethsmall",
+    "Ntildesmall",
+    "Ogravesmall",
+    "Oacutesmall",
+    "Ocircumflexsmall",
+    "Otildesmall",
+    "Odieresissmall",
+    "OEsmall",
+    "Oslashsmall",
+    "Ugravesmall",
+    "Uacutesmall",
+    "Ucircumflexsmall",
+    "Udieresissmall",
+    "Yacutesmall",
+    "Thornsmall",
+    "Ydieresissmall",
+    "001.000",
+    "001.001",
+    "001.002",
+    "001.003",
+    "Black",
+    "Bold",
+    "Book",
+    "Light",
+    "Medium",
+    "Regular",
+    "Roman",
+    "Semibold",
+];
+
+// The CFF Standard Encoding, which maps codes to SIDs.
+// Adobe Technical Note #5176, Appendix B
+const STANDARD_ENCODING: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
+    33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48,
+    49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64,
+    65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80,
+    81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 96, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110,
+    0, 111, 112, 113, 114, 0, 115, 116, 117, 118, 119, 120, 121, 122, 0, 123,
+    0, 124, 125, 126, 127, 128, 129, 130, 131, 0, 132, 133, 0, 134, 135, 136,
+    137, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 138, 0, 139, 0, 0, 0, 0, 140, 141, 142, 143, 0, 0, 0, 0,
+    0, 144, 0, 0, 0, 145, 0, 0, 146, 147, 148, 149, 0, 0, 0, 0,
+];
+
+// The CFF Expert Encoding, which maps codes to SIDs.
+// Adobe Technical Note #5176, Appendix C
+const EXPERT_ENCODING: [u16; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 229, 230, 0, 231, 232, 233, 234, 235, 236, 237, 238, 13, 14, 15, 99,
+    239, 240, 241, 242, 243, 244, 245, 246, 247, 248, 27, 28, 249, 250, 251, 252,
+    0, 253, 254, 255, 256, 257, 0, 0, 0, 258, 0, 0, 259, 260, 261, 262,
+    0, 0, 263, 264, 265, 0, 266, 109, 110, 267, 268, 269, 0, 270, 271, 272,
+    273, 274, 275, 276, 277, 278, 279, 280, 281, 282, 283, 284, 285, 286, 287, 288,
+    289, 290, 291, 292, 293, 294, 295, 296, 297, 298, 299, 300, 301, 302, 303, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 304, 305, 306, 0, 0, 307, 308, 309, 310, 311, 0, 312, 0, 0, 313,
+    0, 0, 314, 315, 0, 0, 316, 317, 318, 0, 0, 0, 158, 155, 163, 319,
+    320, 321, 322, 323, 324, 325, 0, 0, 326, 150, 164, 169, 327, 328, 329, 330,
+    331, 332, 333, 334, 335, 336, 337, 338, 339, 340, 341, 342, 343, 344, 345, 346,
+    347, 348, 349, 350, 351, 352, 353, 354, 355, 356, 357, 358, 359, 360, 361, 362,
+    363, 364, 365, 366, 367, 368, 369, 370, 371, 372, 373, 374, 375, 376, 377, 378,
+];
+
+struct CharStringParserContext<'a, 'h> {
     metadata: &'a Metadata<'a>,
+    max_depth: u8,
+    hints: Option<&'h mut dyn HintBuilder>,
     is_first_move_to: bool,
     has_move_to: bool,
     width_parsed: bool,
@@ -238,13 +1240,33 @@ struct CharStringParserContext<'a> {
     has_endchar: bool,
 }
 
+#[cfg(test)]
 fn parse_char_string(
     data: &[u8],
     metadata: &Metadata,
+    settings: CffSettings,
+    builder: &mut dyn OutlineBuilder,
+) -> Result<Rect, CFFError> {
+    parse_char_string_with_hints(data, metadata, settings, builder, None)
+}
+
+fn parse_char_string_with_hints(
+    data: &[u8],
+    metadata: &Metadata,
+    settings: CffSettings,
     builder: &mut dyn OutlineBuilder,
+    hints: Option<&mut dyn HintBuilder>,
 ) -> Result<Rect, CFFError> {
+    // Type 1 charstrings use a different set of operators
+    // and will produce garbage when parsed as Type 2.
+    if metadata.char_string_type != DEFAULT_CHAR_STRING_TYPE {
+        return Err(CFFError::UnsupportedCharstringType);
+    }
+
     let mut ctx = CharStringParserContext {
         metadata,
+        max_depth: settings.max_subroutine_depth,
+        hints,
         is_first_move_to: true,
         has_move_to: false,
         width_parsed: false,
@@ -279,6 +1301,26 @@ fn parse_char_string(
 }
 
 
+// Stem edges are stored as deltas, with each stem being relative
+// to the end edge of the previous one.
+fn emit_stems(stack: &ArgumentsStack, start: usize, horizontal: bool, hints: &mut dyn HintBuilder) {
+    let mut pos = 0.0;
+    let mut i = start;
+    while i + 1 < stack.len() {
+        let start = pos + stack.at(i);
+        let delta = stack.at(i + 1);
+        if horizontal {
+            hints.horizontal_stem(start, delta);
+        } else {
+            hints.vertical_stem(start, delta);
+        }
+
+        pos = start + delta;
+        i += 2;
+    }
+}
+
+
 pub(crate) struct Builder<'a> {
     pub builder: &'a mut dyn OutlineBuilder,
     pub bbox: BBox,
@@ -347,7 +1389,12 @@ fn _parse_char_string(
 
                 ctx.stems_len += len as u32 >> 1;
 
-                // We are ignoring the hint operators.
+                if let Some(ref mut hints) = ctx.hints {
+                    let horizontal = op == operator::HORIZONTAL_STEM
+                        || op == operator::HORIZONTAL_STEM_HINT_MASK;
+                    emit_stems(stack, stack.len() - len, horizontal, &mut **hints);
+                }
+
                 stack.clear();
             }
             operator::VERTICAL_MOVE_TO => {
@@ -484,7 +1531,7 @@ fn _parse_char_string(
                     return Err(CFFError::InvalidArgumentsStackLength);
                 }
 
-                if depth == STACK_LIMIT {
+                if depth >= ctx.max_depth {
                     return Err(CFFError::NestingLimitReached);
                 }
 
@@ -653,9 +1700,6 @@ fn _parse_char_string(
             operator::HINT_MASK | operator::COUNTER_MASK => {
                 let mut len = stack.len();
 
-                // We are ignoring the hint operators.
-                stack.clear();
-
                 // If the stack length is uneven, than the first value is a `width`.
                 if len.is_odd() && !ctx.width_parsed {
                     len -= 1;
@@ -664,6 +1708,13 @@ fn _parse_char_string(
 
                 ctx.stems_len += len as u32 >> 1;
 
+                // Values left on the stack are implicit `vstem` operands.
+                if let Some(ref mut hints) = ctx.hints {
+                    emit_stems(stack, stack.len() - len, false, &mut **hints);
+                }
+
+                stack.clear();
+
                 s.advance(usize::num_from((ctx.stems_len + 7) >> 3));
             }
             operator::MOVE_TO => {
@@ -858,7 +1909,7 @@ fn _parse_char_string(
                     return Err(CFFError::InvalidArgumentsStackLength);
                 }
 
-                if depth == STACK_LIMIT {
+                if depth >= ctx.max_depth {
                     return Err(CFFError::NestingLimitReached);
                 }
 
@@ -983,8 +2034,8 @@ fn _parse_char_string(
                 stack.push(f32::from(n))?;
             }
             operator::FIXED_16_16 => {
-                let n = s.read::<Fixed>().ok_or(CFFError::ReadOutOfBounds)?;
-                stack.push(n.0)?;
+                let n = s.read_fixed().ok_or(CFFError::ReadOutOfBounds)?;
+                stack.push(n)?;
             }
         }
     }
@@ -1024,6 +2075,12 @@ fn parse_index<'a>(s: &mut Stream<'a>) -> Option<DataIndex<'a>> {
 
 #[inline]
 pub fn parse_index_impl<'a>(count: u32, s: &mut Stream<'a>) -> Option<DataIndex<'a>> {
+    // We are using `u16` for indices, therefore `count + 1` offsets must fit it.
+    // CFF2 allows larger indices, but we do not support them.
+    if count >= u32::from(core::u16::MAX) {
+        return None;
+    }
+
     let offset_size: OffsetSize = try_parse_offset_size(s)?;
     let offsets_len = (count + 1).checked_mul(offset_size.to_u32())?;
     let offsets = VarOffsets {
@@ -1105,8 +2162,9 @@ impl<'a> VarOffsets<'a> {
 
     #[inline]
     pub fn len(&self) -> u16 {
-        // TODO: check that len actually u16
-        self.data.len() as u16 / self.offset_size as u16
+        // The number of offsets is checked during parsing, so it always fits `u16`.
+        let len = self.data.len() / self.offset_size.to_usize();
+        u16::try_from(len).unwrap_or(0)
     }
 
     #[inline]
@@ -1157,18 +2215,24 @@ impl<'a> DataIndex<'a> {
         }
     }
 
+    /// Returns an item data.
+    ///
+    /// Valid indices are in a `0..len()` range. Returns `None` for any other index,
+    /// including any index of an empty INDEX.
     pub fn get(&self, index: u16) -> Option<&'a [u8]> {
-        // Check for overflow first.
-        if index == core::u16::MAX {
-            None
-        } else if index + 1 < self.offsets.len() {
-            let start = usize::try_from(self.offsets.get(index)?).ok()?;
-            let end = usize::try_from(self.offsets.get(index + 1)?).ok()?;
-            let data = self.data.get(start..end)?;
-            Some(data)
-        } else {
-            None
+        let next_index = index.checked_add(1)?;
+        if next_index >= self.offsets.len() {
+            return None;
         }
+
+        let start = usize::try_from(self.offsets.get(index)?).ok()?;
+        let end = usize::try_from(self.offsets.get(next_index)?).ok()?;
+        // Offsets must be monotonic. Otherwise an INDEX is malformed.
+        if start > end {
+            return None;
+        }
+
+        self.data.get(start..end)
     }
 }
 
@@ -1314,19 +2378,69 @@ impl<'a> DictionaryParser<'a> {
     fn operands(&self) -> &[i32] {
         &self.operands[..usize::from(self.operands_len)]
     }
-}
 
-// One-byte CFF DICT Operators according to the
-// Adobe Technical Note #5176, Appendix H CFF DICT Encoding.
-pub fn is_dict_one_byte_op(b: u8) -> bool {
-    match b {
-        0..=27 => true,
-        28..=30 => false, // numbers
-        31 => true, // Reserved
-        32..=254 => false, // numbers
-        255 => true, // Reserved
-    }
-}
+    /// Parses operands of the current operator as real numbers.
+    ///
+    /// Stops at the first malformed operand. Returns the number of parsed operands.
+    fn parse_real_operands(&self, values: &mut [f32]) -> usize {
+        let mut s = try_opt_or!(Stream::new_at(self.data, self.operands_offset), 0);
+        let mut len = 0;
+        while len < values.len() {
+            let b: u8 = try_opt_or!(s.read(), len);
+            if is_dict_one_byte_op(b) {
+                break;
+            }
+
+            let n = if b == 30 {
+                parse_float(&mut s)
+            } else {
+                parse_number(b, &mut s).map(|n| n as f32)
+            };
+
+            values[len] = try_opt_or!(n, len);
+            len += 1;
+        }
+
+        len
+    }
+
+    /// Parses a single operand of the current operator as a real number.
+    ///
+    /// Unlike `parse_operands()`, preserves the fractional part.
+    fn parse_real_operand(&self) -> Option<f32> {
+        let mut s = Stream::new_at(self.data, self.operands_offset)?;
+        let b: u8 = s.read()?;
+        if is_dict_one_byte_op(b) {
+            return None;
+        }
+
+        let n = if b == 30 {
+            parse_float(&mut s)?
+        } else {
+            parse_number(b, &mut s)? as f32
+        };
+
+        // Make sure that there is only one operand.
+        let b: u8 = s.read()?;
+        if is_dict_one_byte_op(b) {
+            Some(n)
+        } else {
+            None
+        }
+    }
+}
+
+// One-byte CFF DICT Operators according to the
+// Adobe Technical Note #5176, Appendix H CFF DICT Encoding.
+pub fn is_dict_one_byte_op(b: u8) -> bool {
+    match b {
+        0..=27 => true,
+        28..=30 => false, // numbers
+        31 => true, // Reserved
+        32..=254 => false, // numbers
+        255 => true, // Reserved
+    }
+}
 
 // Adobe Technical Note #5177, Table 3 Operand Encoding
 pub fn parse_number(b0: u8, s: &mut Stream) -> Option<i32> {
@@ -1371,6 +2485,75 @@ pub fn parse_number(b0: u8, s: &mut Stream) -> Option<i32> {
     }
 }
 
+// Adobe Technical Note #5176, Table 5 Nibble Definitions
+//
+// We are not using `str::parse`, because we don't want
+// the core::num::dec2flt dependency.
+fn parse_float(s: &mut Stream) -> Option<f32> {
+    // Digits after this will be ignored, since they do not fit into f32 anyway.
+    const MAX_MANTISSA: u32 = 100_000_000;
+
+    let mut mantissa = 0u32;
+    let mut scale = 0i32;
+    let mut exponent = 0i32;
+    let mut is_negative = false;
+    let mut is_negative_exponent = false;
+    let mut after_point = false;
+    let mut in_exponent = false;
+
+    'outer: while !s.at_end() {
+        let b: u8 = s.read()?;
+        for nibble in [b >> 4, b & 15].iter().cloned() {
+            match nibble {
+                0..=9 => {
+                    let digit = u32::from(nibble);
+                    if in_exponent {
+                        exponent = exponent.saturating_mul(10).saturating_add(digit as i32);
+                    } else if mantissa < MAX_MANTISSA {
+                        mantissa = mantissa * 10 + digit;
+                        if after_point {
+                            scale -= 1;
+                        }
+                    } else if !after_point {
+                        scale += 1;
+                    }
+                }
+                0xa => after_point = true,
+                0xb => in_exponent = true,
+                0xc => {
+                    in_exponent = true;
+                    is_negative_exponent = true;
+                }
+                0xd => return None, // Reserved.
+                0xe => is_negative = true,
+                _ => break 'outer, // END_OF_FLOAT_FLAG
+            }
+        }
+    }
+
+    if is_negative_exponent {
+        exponent = -exponent;
+    }
+
+    // Anything outside this range is either infinity or zero anyway.
+    let exponent = scale.saturating_add(exponent).max(-64).min(64);
+
+    let mut n = f64::from(mantissa);
+    for _ in 0..exponent.abs() {
+        if exponent > 0 {
+            n *= 10.0;
+        } else {
+            n /= 10.0;
+        }
+    }
+
+    if is_negative {
+        n = -n;
+    }
+
+    Some(n as f32)
+}
+
 // Just like `parse_number`, but doesn't actually parses the data.
 pub fn skip_number(b0: u8, s: &mut Stream) -> Option<()> {
     match b0 {
@@ -1426,6 +2609,8 @@ impl<'a> ArgumentsStack<'a> {
 
     #[inline]
     pub fn at(&self, index: usize) -> f32 {
+        // Values past `len` are leftovers from previous operators.
+        debug_assert!(index < self.len);
         self.data[index]
     }
 
@@ -1566,6 +2751,9 @@ mod tests {
                 CFFError::BlendRegionsLimitReached => {
                     write!(f, "only up to 64 blend regions are supported")
                 }
+                CFFError::UnsupportedCharstringType => {
+                    write!(f, "only Type 2 charstrings are supported")
+                }
             }
         }
     }
@@ -1752,12 +2940,62 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let rect = parse_char_string(char_str, &metadata, &mut builder).unwrap();
+        let rect = parse_char_string(char_str, &metadata, CffSettings::default(), &mut builder).unwrap();
 
         assert_eq!(builder.0, "M 10 0 Z ");
         assert_eq!(rect, Rect { x_min: 10, y_min: 0, x_max: 10, y_max: 0 });
     }
 
+    #[test]
+    fn type1_char_strings() {
+        let data = writer::convert(&[
+            // Header
+            UInt8(1), // major version
+            UInt8(0), // minor version
+            UInt8(4), // header size
+            UInt8(0), // absolute offset
+
+            // Name INDEX
+            UInt16(0), // count
+
+            // Top DICT
+            // INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(6), // index[1]
+            // Data
+            CFFInt(1),
+            UInt8(TWO_BYTE_OPERATOR_MARK),
+            UInt8(6), // CharstringType
+            CFFInt(20),
+            UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
+
+            // String INDEX
+            UInt16(0), // count
+
+            // Global Subroutines INDEX
+            UInt16(0), // count
+
+            // CharString INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(4), // index[1]
+            // Data
+            CFFInt(10),
+            UInt8(operator::HORIZONTAL_MOVE_TO),
+            UInt8(operator::ENDCHAR),
+        ]);
+
+        let metadata = parse_metadata(&data).unwrap();
+        let mut builder = Builder(String::new());
+        let char_str = metadata.char_strings.get(0).unwrap();
+        let res = parse_char_string(char_str, &metadata, CffSettings::default(), &mut builder);
+        assert_eq!(res.unwrap_err().to_string(), "only Type 2 charstrings are supported");
+        assert!(builder.0.is_empty());
+    }
+
     fn rect(x_min: i16, y_min: i16, x_max: i16, y_max: i16) -> Rect {
         Rect { x_min, y_min, x_max, y_max }
     }
@@ -1770,7 +3008,7 @@ mod tests {
                 let metadata = parse_metadata(&data).unwrap();
                 let mut builder = Builder(String::new());
                 let char_str = metadata.char_strings.get(0).unwrap();
-                let rect = parse_char_string(char_str, &metadata, &mut builder).unwrap();
+                let rect = parse_char_string(char_str, &metadata, CffSettings::default(), &mut builder).unwrap();
 
                 assert_eq!(builder.0, $path);
                 assert_eq!(rect, $rect_res);
@@ -1792,7 +3030,7 @@ mod tests {
                 let metadata = parse_metadata(&data).unwrap();
                 let mut builder = Builder(String::new());
                 let char_str = metadata.char_strings.get(0).unwrap();
-                let res = parse_char_string(char_str, &metadata, &mut builder);
+                let res = parse_char_string(char_str, &metadata, CffSettings::default(), &mut builder);
 
                 assert_eq!(res.unwrap_err().to_string(), $err);
             }
@@ -1956,13 +3194,396 @@ mod tests {
         rect(10, 20, 90, 190)
     );
 
+    test_cs!(hflex, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::HFLEX),
+        UInt8(operator::ENDCHAR),
+    ], "M 10 20 C 11 20 13 23 17 23 C 22 23 28 20 35 20 Z ",
+        rect(10, 20, 35, 23)
+    );
+
+    test_cs_err!(hflex_with_not_enough_coords, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9), CFFInt(10), CFFInt(11), CFFInt(12), CFFInt(13), CFFInt(14),
+        UInt8(operator::LINE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::HFLEX),
+        UInt8(operator::ENDCHAR),
+    ], "an invalid amount of items are in an arguments stack");
+
+    test_cs_err!(hflex_with_too_many_coords, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(1),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::HFLEX),
+        UInt8(operator::ENDCHAR),
+    ], "an invalid amount of items are in an arguments stack");
+
+    test_cs!(flex, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9), CFFInt(10), CFFInt(11), CFFInt(12), CFFInt(50),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::FLEX),
+        UInt8(operator::ENDCHAR),
+    ], "M 10 20 C 11 22 14 26 19 32 C 26 40 35 50 46 62 Z ",
+        rect(10, 20, 46, 62)
+    );
+
+    test_cs_err!(flex_with_not_enough_coords, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9), CFFInt(10), CFFInt(11), CFFInt(12), CFFInt(13), CFFInt(14),
+        UInt8(operator::LINE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9), CFFInt(10), CFFInt(11), CFFInt(12),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::FLEX),
+        UInt8(operator::ENDCHAR),
+    ], "an invalid amount of items are in an arguments stack");
+
+    test_cs_err!(flex_with_too_many_coords, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9), CFFInt(10), CFFInt(11), CFFInt(12), CFFInt(50), CFFInt(1),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::FLEX),
+        UInt8(operator::ENDCHAR),
+    ], "an invalid amount of items are in an arguments stack");
+
+    test_cs!(hflex1, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::HFLEX1),
+        UInt8(operator::ENDCHAR),
+    ], "M 10 20 C 11 22 14 26 19 26 C 25 26 32 34 41 20 Z ",
+        rect(10, 20, 41, 34)
+    );
+
+    test_cs_err!(hflex1_with_not_enough_coords, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9), CFFInt(10), CFFInt(11), CFFInt(12), CFFInt(13), CFFInt(14),
+        UInt8(operator::LINE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::HFLEX1),
+        UInt8(operator::ENDCHAR),
+    ], "an invalid amount of items are in an arguments stack");
+
+    test_cs_err!(hflex1_with_too_many_coords, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9), CFFInt(1),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::HFLEX1),
+        UInt8(operator::ENDCHAR),
+    ], "an invalid amount of items are in an arguments stack");
+
+    test_cs!(flex1, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9), CFFInt(10), CFFInt(11),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::FLEX1),
+        UInt8(operator::ENDCHAR),
+    ], "M 10 20 C 11 22 14 26 19 32 C 26 40 35 50 10 61 Z ",
+        rect(10, 20, 35, 61)
+    );
+
+    test_cs_err!(flex1_with_not_enough_coords, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9), CFFInt(10), CFFInt(11), CFFInt(12), CFFInt(13), CFFInt(14),
+        UInt8(operator::LINE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9), CFFInt(10),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::FLEX1),
+        UInt8(operator::ENDCHAR),
+    ], "an invalid amount of items are in an arguments stack");
+
+    test_cs_err!(flex1_with_too_many_coords, &[
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(1), CFFInt(2), CFFInt(3), CFFInt(4), CFFInt(5), CFFInt(6), CFFInt(7), CFFInt(8),
+        CFFInt(9), CFFInt(10), CFFInt(11), CFFInt(1),
+        UInt8(TWO_BYTE_OPERATOR_MARK), UInt8(operator::FLEX1),
+        UInt8(operator::ENDCHAR),
+    ], "an invalid amount of items are in an arguments stack");
+
+    #[test]
+    fn contains_glyph() {
+        let data = gen_cff(&[], &[], &[UInt8(operator::ENDCHAR)]);
+        let metadata = parse_metadata(&data).unwrap();
+        assert!(metadata.contains_glyph(GlyphId(0)));
+        assert!(!metadata.contains_glyph(GlyphId(1)));
+    }
+
+    fn gen_cff_with_charset(charset: &[writer::TtfType]) -> Vec<u8> {
+        gen_cff_with_top_dict_offset(top_dict_operator::CHARSET_OFFSET, None, charset)
+    }
+
+    // When `operand` is not set, the actual `table` offset will be written.
+    fn gen_cff_with_top_dict_offset(
+        operator: u16,
+        operand: Option<i32>,
+        table: &[writer::TtfType],
+    ) -> Vec<u8> {
+        let table_offset = 28;
+        let char_strings_offset = table_offset + writer::convert(table).len();
+
+        let mut w = writer::Writer::new();
+        // Header
+        w.write(UInt8(1)); // major version
+        w.write(UInt8(0)); // minor version
+        w.write(UInt8(4)); // header size
+        w.write(UInt8(0)); // absolute offset
+
+        // Name INDEX
+        w.write(UInt16(0)); // count
+
+        // Top DICT INDEX
+        w.write(UInt16(1)); // count
+        w.write(UInt8(1)); // offset size
+        w.write(UInt8(1)); // index[0]
+        w.write(UInt8(5)); // index[1]
+        w.write(CFFInt(operand.unwrap_or(table_offset as i32)));
+        w.write(UInt8(operator as u8));
+        w.write(CFFInt(char_strings_offset as i32));
+        w.write(UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8));
+
+        // String INDEX
+        w.write(UInt16(1)); // count
+        w.write(UInt8(1)); // offset size
+        w.write(UInt8(1)); // index[0]
+        w.write(UInt8(7)); // index[1]
+        for c in b"custom" {
+            w.write(UInt8(*c));
+        }
+
+        // Global Subroutines INDEX
+        w.write(UInt16(0)); // count
+
+        assert_eq!(w.offset(), table_offset);
+        for v in table {
+            w.write(*v);
+        }
+
+        // CharStrings INDEX
+        w.write(UInt16(3)); // count
+        w.write(UInt8(1)); // offset size
+        for i in 1..=4 {
+            w.write(UInt8(i));
+        }
+        for _ in 0..3 {
+            w.write(UInt8(operator::ENDCHAR));
+        }
+
+        w.data
+    }
+
+    #[test]
+    fn validate_charstring() {
+        let data = gen_cff(&[], &[], &[
+            CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+            CFFInt(1), UInt8(operator::LINE_TO),
+            UInt8(operator::ENDCHAR),
+        ]);
+        let metadata = parse_metadata(&data).unwrap();
+        let settings = CffSettings::default();
+        assert_eq!(
+            metadata.validate_charstring(settings, GlyphId(0)),
+            Err(CFFError::InvalidArgumentsStackLength),
+        );
+        assert_eq!(
+            metadata.validate_charstring(settings, GlyphId(1)),
+            Err(CFFError::ReadOutOfBounds),
+        );
+        assert_eq!(
+            metadata.validate_all(settings),
+            Err((GlyphId(0), CFFError::InvalidArgumentsStackLength)),
+        );
+    }
+
+    #[test]
+    fn validate_empty_charstring() {
+        let data = gen_cff(&[], &[], &[UInt8(operator::ENDCHAR)]);
+        let metadata = parse_metadata(&data).unwrap();
+        let settings = CffSettings::default();
+        assert_eq!(metadata.validate_charstring(settings, GlyphId(0)), Ok(()));
+        assert_eq!(metadata.validate_all(settings), Ok(()));
+    }
+
+    #[test]
+    fn code_to_gid_standard() {
+        let data = gen_cff_with_charset(&[
+            UInt8(0), // format
+            UInt16(34), // A
+            UInt16(391), // custom
+        ]);
+        let metadata = parse_metadata(&data).unwrap();
+        assert_eq!(metadata.code_to_gid(b'A'), Some(GlyphId(1)));
+        assert_eq!(metadata.code_to_gid(b'B'), None);
+        assert_eq!(metadata.code_to_gid(0), None);
+    }
+
+    #[test]
+    fn code_to_gid_standard_iso_adobe() {
+        let data = gen_cff(&[], &[], &[UInt8(operator::ENDCHAR)]);
+        let metadata = parse_metadata(&data).unwrap();
+        // ISOAdobe has `space` at 1, but the font has only one glyph.
+        assert_eq!(metadata.code_to_gid(b' '), None);
+    }
+
+    // Offsets 0..=2 are reserved for predefined charsets and encodings.
+    fn at_offset_4(values: &[writer::TtfType]) -> Vec<u8> {
+        let mut data = std::vec![0; 4];
+        data.extend_from_slice(&writer::convert(values));
+        data
+    }
+
+    #[test]
+    fn encoding_format_0() {
+        let charset = at_offset_4(&[
+            UInt8(0), // format
+            UInt16(34), // A
+            UInt16(35), // B
+        ]);
+        let charset = Charset::parse(&charset, 4, 3).unwrap();
+
+        let data = at_offset_4(&[
+            UInt8(0x80), // format 0 with supplements
+            UInt8(2), // nCodes
+            UInt8(b'x'), // code [0]
+            UInt8(b'y'), // code [1]
+            UInt8(1), // nSups
+            UInt8(b'z'), // code
+            UInt16(35), // glyph: B
+        ]);
+        let encoding = Encoding::parse(&data, 4).unwrap();
+        assert_eq!(encoding.code_to_gid(Some(&charset), b'x', 3), Some(GlyphId(1)));
+        assert_eq!(encoding.code_to_gid(Some(&charset), b'y', 3), Some(GlyphId(2)));
+        assert_eq!(encoding.code_to_gid(Some(&charset), b'z', 3), Some(GlyphId(2)));
+        assert_eq!(encoding.code_to_gid(Some(&charset), b'w', 3), None);
+
+        // Only supplements require a charset.
+        assert_eq!(encoding.code_to_gid(None, b'x', 3), Some(GlyphId(1)));
+        assert_eq!(encoding.code_to_gid(None, b'z', 3), None);
+    }
+
+    #[test]
+    fn encoding_format_1() {
+        let charset = Charset::IsoAdobe;
+        let data = at_offset_4(&[
+            UInt8(1), // format
+            UInt8(2), // nRanges
+            UInt8(b'a'), // first
+            UInt8(1), // nLeft
+            UInt8(b'x'), // first
+            UInt8(0), // nLeft
+        ]);
+        let encoding = Encoding::parse(&data, 4).unwrap();
+        assert_eq!(encoding.code_to_gid(Some(&charset), b'a', 4), Some(GlyphId(1)));
+        assert_eq!(encoding.code_to_gid(Some(&charset), b'b', 4), Some(GlyphId(2)));
+        assert_eq!(encoding.code_to_gid(Some(&charset), b'x', 4), Some(GlyphId(3)));
+        assert_eq!(encoding.code_to_gid(Some(&charset), b'c', 4), None);
+    }
+
+    #[test]
+    fn encoding_expert() {
+        let charset = at_offset_4(&[
+            UInt8(0), // format
+            UInt16(274), // Asmall
+            UInt16(13), // comma
+        ]);
+        let charset = Charset::parse(&charset, 4, 3).unwrap();
+
+        let encoding = Encoding::parse(&[], encoding_id::EXPERT).unwrap();
+        assert_eq!(encoding.code_to_gid(Some(&charset), b'a', 3), Some(GlyphId(1)));
+        assert_eq!(encoding.code_to_gid(Some(&charset), b',', 3), Some(GlyphId(2)));
+        // asuperior is not in the charset.
+        assert_eq!(encoding.code_to_gid(Some(&charset), b'A', 3), None);
+        assert_eq!(encoding.code_to_gid(Some(&charset), 0, 3), None);
+    }
+
+    #[test]
+    fn encoding_unknown_format() {
+        let data = at_offset_4(&[UInt8(2)]);
+        assert!(Encoding::parse(&data, 4).is_none());
+    }
+
+    #[test]
+    fn glyph_index_by_name_iso_adobe() {
+        let data = gen_cff(&[], &[], &[UInt8(operator::ENDCHAR)]);
+        let metadata = parse_metadata(&data).unwrap();
+        assert_eq!(metadata.glyph_index_by_name(".notdef"), Some(GlyphId(0)));
+        // ISOAdobe has `space` at 1, but the font has only one glyph.
+        assert_eq!(metadata.glyph_index_by_name("space"), None);
+    }
+
+    #[test]
+    fn glyph_index_by_name_format_0() {
+        let data = gen_cff_with_charset(&[
+            UInt8(0), // format
+            UInt16(34), // A
+            UInt16(391), // custom
+        ]);
+        let metadata = parse_metadata(&data).unwrap();
+        assert_eq!(metadata.glyph_index_by_name(".notdef"), Some(GlyphId(0)));
+        assert_eq!(metadata.glyph_index_by_name("A"), Some(GlyphId(1)));
+        assert_eq!(metadata.glyph_index_by_name("custom"), Some(GlyphId(2)));
+        assert_eq!(metadata.glyph_index_by_name("B"), None);
+        assert_eq!(metadata.glyph_index_by_name("unknown"), None);
+    }
+
+    #[test]
+    fn glyph_index_by_name_format_1() {
+        let data = gen_cff_with_charset(&[
+            UInt8(1), // format
+            UInt16(34), // first: A
+            UInt8(1), // left
+        ]);
+        let metadata = parse_metadata(&data).unwrap();
+        assert_eq!(metadata.glyph_index_by_name("A"), Some(GlyphId(1)));
+        assert_eq!(metadata.glyph_index_by_name("B"), Some(GlyphId(2)));
+        assert_eq!(metadata.glyph_index_by_name("C"), None);
+    }
+
+    #[test]
+    fn glyph_index_by_name_format_2() {
+        let data = gen_cff_with_charset(&[
+            UInt8(2), // format
+            UInt16(35), // first: B
+            UInt16(0), // left
+            UInt16(391), // first: custom
+            UInt16(0), // left
+        ]);
+        let metadata = parse_metadata(&data).unwrap();
+        assert_eq!(metadata.glyph_index_by_name("A"), None);
+        assert_eq!(metadata.glyph_index_by_name("B"), Some(GlyphId(1)));
+        assert_eq!(metadata.glyph_index_by_name("custom"), Some(GlyphId(2)));
+    }
+
+    #[test]
+    fn invalid_charset_offset() {
+        let data = gen_cff_with_top_dict_offset(top_dict_operator::CHARSET_OFFSET, Some(-1), &[]);
+        let metadata = parse_metadata(&data).unwrap();
+        assert!(metadata.charset.is_none());
+        assert_eq!(metadata.glyph_index_by_name("A"), None);
+        assert_eq!(metadata.char_strings.len(), 3);
+    }
+
+    #[test]
+    fn invalid_encoding_offset() {
+        let data = gen_cff_with_top_dict_offset(top_dict_operator::ENCODING_OFFSET, Some(-1), &[]);
+        let metadata = parse_metadata(&data).unwrap();
+        assert!(metadata.encoding.is_none());
+        assert_eq!(metadata.code_to_gid(b'A'), None);
+        assert_eq!(metadata.char_strings.len(), 3);
+    }
+
     #[test]
     fn only_endchar() {
         let data = gen_cff(&[], &[], &[UInt8(operator::ENDCHAR)]);
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        assert!(parse_char_string(char_str, &metadata, &mut builder).is_err());
+        assert!(parse_char_string(char_str, &metadata, CffSettings::default(), &mut builder).is_err());
     }
 
     test_cs_with_subrs!(local_subr,
@@ -2157,6 +3778,10 @@ mod tests {
         CFFInt(30000), CFFInt(30000), CFFInt(30000), CFFInt(30000), CFFInt(30000),
     ], "arguments stack limit reached");
 
+    test_cs_err!(empty_glyph, &[
+        UInt8(operator::ENDCHAR),
+    ], "zero bbox");
+
     test_cs_err!(bbox_overflow, &[
         CFFInt(32767), UInt8(operator::HORIZONTAL_MOVE_TO),
         CFFInt(32767), UInt8(operator::HORIZONTAL_LINE_TO),
@@ -2187,7 +3812,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, CffSettings::default(), &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "unused data left after 'endchar' operator");
     }
@@ -2216,7 +3841,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, CffSettings::default(), &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "unused data left after 'endchar' operator");
     }
@@ -2245,7 +3870,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, CffSettings::default(), &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "unused data left after 'endchar' operator");
     }
@@ -2269,7 +3894,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, CffSettings::default(), &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "subroutines nesting limit reached");
     }
@@ -2293,7 +3918,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, CffSettings::default(), &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "subroutines nesting limit reached");
     }
@@ -2320,11 +3945,157 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, CffSettings::default(), &mut builder);
+        assert_eq!(res.unwrap_err().to_string(),
+                   "subroutines nesting limit reached");
+    }
+
+    #[test]
+    fn custom_nesting_limit() {
+        let data = gen_cff(
+            &[],
+            &[&[
+                CFFInt(30),
+                CFFInt(40),
+                UInt8(operator::LINE_TO),
+                UInt8(operator::RETURN),
+            ]],
+            &[
+                CFFInt(10),
+                UInt8(operator::HORIZONTAL_MOVE_TO),
+                CFFInt(0 - 107), // subr index - subr bias
+                UInt8(operator::CALL_LOCAL_SUBROUTINE),
+                UInt8(operator::ENDCHAR),
+            ]
+        );
+
+        let metadata = parse_metadata(&data).unwrap();
+        let char_str = metadata.char_strings.get(0).unwrap();
+
+        let mut builder = Builder(String::new());
+        let settings = CffSettings { max_subroutine_depth: 1 };
+        assert!(parse_char_string(char_str, &metadata, settings, &mut builder).is_ok());
+
+        let mut builder = Builder(String::new());
+        let settings = CffSettings { max_subroutine_depth: 0 };
+        let res = parse_char_string(char_str, &metadata, settings, &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "subroutines nesting limit reached");
     }
 
+    #[test]
+    fn stem_hints() {
+        struct Hints(String);
+        impl HintBuilder for Hints {
+            fn horizontal_stem(&mut self, y: f32, dy: f32) {
+                write!(&mut self.0, "H {} {} ", y, dy).unwrap();
+            }
+
+            fn vertical_stem(&mut self, x: f32, dx: f32) {
+                write!(&mut self.0, "V {} {} ", x, dx).unwrap();
+            }
+        }
+
+        let data = gen_cff(&[], &[], &[
+            CFFInt(10),
+            CFFInt(20),
+            CFFInt(5),
+            CFFInt(10),
+            UInt8(operator::HORIZONTAL_STEM),
+            CFFInt(1),
+            CFFInt(2),
+            UInt8(operator::VERTICAL_STEM_HINT_MASK),
+            CFFInt(50),
+            CFFInt(5),
+            UInt8(operator::HINT_MASK),
+            UInt8(0xF0),
+            CFFInt(10),
+            CFFInt(20),
+            UInt8(operator::MOVE_TO),
+            CFFInt(30),
+            CFFInt(40),
+            UInt8(operator::LINE_TO),
+            UInt8(operator::ENDCHAR),
+        ]);
+
+        let metadata = parse_metadata(&data).unwrap();
+        let char_str = metadata.char_strings.get(0).unwrap();
+        let mut builder = Builder(String::new());
+        let mut hints = Hints(String::new());
+        let rect = parse_char_string_with_hints(
+            char_str, &metadata, CffSettings::default(), &mut builder, Some(&mut hints),
+        ).unwrap();
+
+        assert_eq!(builder.0, "M 10 20 L 40 60 Z ");
+        assert_eq!(hints.0, "H 10 20 H 35 10 V 1 2 V 50 5 ");
+        assert_eq!(rect, Rect { x_min: 10, y_min: 20, x_max: 40, y_max: 60 });
+    }
+
+    #[test]
+    fn private_dict_blue_zones() {
+        let data = writer::convert(&[
+            CFFInt(-15),
+            CFFInt(15),
+            CFFInt(485),
+            CFFInt(15),
+            UInt8(private_dict_operator::BLUE_VALUES as u8),
+            CFFInt(-250),
+            CFFInt(10),
+            UInt8(private_dict_operator::OTHER_BLUES as u8),
+            UInt8(30), // real number
+            Raw(&[0xA0, 0x39, 0x62, 0x5F]), // .039625
+            UInt8(TWO_BYTE_OPERATOR_MARK),
+            UInt8(9), // BlueScale
+            CFFInt(40),
+            UInt8(private_dict_operator::STD_HW as u8),
+            CFFInt(2),
+            UInt8(private_dict_operator::LOCAL_SUBROUTINES_OFFSET as u8),
+        ]);
+
+        let dict = parse_private_dict(&data);
+        let zones = dict.blue_zones;
+        assert_eq!(dict.local_subroutines_offset, Some(2));
+        assert_eq!(zones.blue_values(), &[-15.0, 0.0, 485.0, 500.0]);
+        assert_eq!(zones.other_blues(), &[-250.0, -240.0]);
+        assert!(zones.family_blues().is_empty());
+        assert_eq!(zones.blue_scale(), Some(0.039625));
+        assert_eq!(zones.blue_shift(), None);
+        assert_eq!(zones.std_hw(), Some(40.0));
+        assert_eq!(zones.std_vw(), None);
+    }
+
+    #[test]
+    fn private_dict_real_blue_zones() {
+        let data = writer::convert(&[
+            UInt8(30), // real number
+            Raw(&[0xE1, 0x5A, 0x5F]), // -15.5
+            CFFInt(15),
+            CFFInt(485),
+            UInt8(30), // real number
+            Raw(&[0x1D, 0xFF]), // reserved nibble
+            CFFInt(10),
+            CFFInt(10),
+            UInt8(private_dict_operator::BLUE_VALUES as u8),
+        ]);
+
+        // Only the first zone is valid.
+        let dict = parse_private_dict(&data);
+        assert_eq!(dict.blue_zones.blue_values(), &[-15.5, -0.5]);
+    }
+
+    #[test]
+    fn parse_real_numbers() {
+        fn parse(data: &[u8]) -> Option<f32> {
+            parse_float(&mut Stream::new(data))
+        }
+
+        assert_eq!(parse(&[0xE2, 0xA2, 0x5F]), Some(-2.25));
+        assert_eq!(parse(&[0x1A, 0x5B, 0x2F]), Some(150.0));
+        assert_eq!(parse(&[0x1A, 0x5C, 0x2F]), Some(0.015));
+        assert_eq!(parse(&[0x0F]), Some(0.0));
+        assert_eq!(parse(&[0x1D, 0xFF]), None);
+    }
+
     #[test]
     fn zero_char_string_offset() {
         let data = writer::convert(&[
@@ -2388,6 +4159,60 @@ mod tests {
         assert!(parse_index(&mut Stream::new(&data)).is_some());
     }
 
+    #[test]
+    fn index_data_empty() {
+        let data = writer::convert(&[
+            UInt16(0), // count
+        ]);
+
+        let index = parse_index(&mut Stream::new(&data)).unwrap();
+        assert_eq!(index.len(), 0);
+        assert_eq!(index.into_iter().count(), 0);
+        for i in &[0, 1, core::u16::MAX - 1, core::u16::MAX] {
+            assert_eq!(index.get(*i), None);
+        }
+
+        let index = DataIndex::default();
+        assert_eq!(index.len(), 0);
+        assert_eq!(index.get(0), None);
+        assert_eq!(index.get(core::u16::MAX), None);
+    }
+
+    #[test]
+    fn index_data_max_index() {
+        let data = writer::convert(&[
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(2), // index[1]
+            UInt8(7), // data
+        ]);
+
+        let index = parse_index(&mut Stream::new(&data)).unwrap();
+        assert_eq!(index.get(0), Some(&[7][..]));
+        assert_eq!(index.get(1), None);
+        assert_eq!(index.get(core::u16::MAX), None);
+    }
+
+    #[test]
+    fn index_data_reversed_offsets() {
+        let data = writer::convert(&[
+            UInt16(3), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(4), // index[1]
+            UInt8(2), // index[2]
+            UInt8(5), // index[3]
+            UInt8(1), UInt8(2), UInt8(3), UInt8(4), // data
+        ]);
+
+        let index = parse_index(&mut Stream::new(&data)).unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.get(0), Some(&[1, 2, 3][..]));
+        assert_eq!(index.get(1), None);
+        assert_eq!(index.get(2), Some(&[2, 3, 4][..]));
+    }
+
     #[test]
     fn index_data_invalid_offset_size_0() {
         let data = writer::convert(&[
@@ -2426,8 +4251,9 @@ mod tests {
             UInt8(top_dict_operator::PRIVATE_DICT_SIZE_AND_OFFSET as u8),
         ]);
 
-        assert_eq!(parse_top_dict(&mut Stream::new(&data)).unwrap(),
-                   (5, Some(2147483647..4294967294)));
+        let top_dict = parse_top_dict(&mut Stream::new(&data)).unwrap();
+        assert_eq!(top_dict.char_strings_offset, 5);
+        assert_eq!(top_dict.private_dict_range, Some(2147483647..4294967294));
     }
 
     #[test]
@@ -2489,8 +4315,4 @@ mod tests {
     // TODO: CURVE_LINE
     // TODO: LINE_CURVE
     // TODO: VH_CURVE_TO
-    // TODO: HFLEX
-    // TODO: FLEX
-    // TODO: HFLEX1
-    // TODO: FLEX1
 }
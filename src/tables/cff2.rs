@@ -5,7 +5,7 @@ use core::convert::TryFrom;
 use core::ops::Range;
 
 use crate::{GlyphId, OutlineBuilder, Rect, BBox, NormalizedCoord};
-use crate::parser::{Stream, Fixed, NumFrom, TryNumFrom};
+use crate::parser::{Stream, NumFrom, TryNumFrom};
 use crate::var_store::*;
 use crate::cff::{
     Builder, DataIndex, IsEven, Operator, ArgumentsStack, CFFError,
@@ -82,6 +82,14 @@ pub struct Metadata<'a> {
     item_variation_store: ItemVariationStore<'a>,
 }
 
+impl<'a> Metadata<'a> {
+    /// Checks that the CharStrings INDEX has the specified glyph.
+    #[inline]
+    pub fn contains_glyph(&self, glyph_id: GlyphId) -> bool {
+        glyph_id.0 < self.char_strings.len()
+    }
+}
+
 pub(crate) fn parse_metadata(data: &[u8]) -> Option<Metadata> {
     let mut s = Stream::new(data);
 
@@ -1013,8 +1021,8 @@ fn _parse_char_string(
                 stack.push(f32::from(n))?;
             }
             operator::FIXED_16_16 => {
-                let n = s.read::<Fixed>().ok_or(CFFError::ReadOutOfBounds)?;
-                stack.push(n.0)?;
+                let n = s.read_fixed().ok_or(CFFError::ReadOutOfBounds)?;
+                stack.push(n)?;
             }
         }
     }
@@ -1127,6 +1135,7 @@ mod tests {
     use crate::writer;
     use writer::TtfType::*;
     use crate::cff::parse_index_impl;
+    use std::string::ToString;
 
     #[test]
     fn index_data_offsets_len_overflow() {
@@ -1137,4 +1146,69 @@ mod tests {
 
         assert!(parse_index_impl(std::u32::MAX / 2, &mut Stream::new(&data)).is_none());
     }
+
+    #[test]
+    fn index_data_count_too_large() {
+        // 70000 items with 1-byte offsets. Offsets array length doesn't fit `u16`.
+        let mut data = writer::convert(&[UInt8(1)]); // offset size
+        data.resize(1 + 70001, 1);
+
+        assert!(parse_index_impl(70000, &mut Stream::new(&data)).is_none());
+    }
+
+    #[test]
+    fn index_data_count_max() {
+        let count = u32::from(core::u16::MAX) - 1;
+        let mut data = writer::convert(&[UInt8(1)]); // offset size
+        data.resize(1 + usize::num_from(count) + 1, 1);
+
+        let index = parse_index_impl(count, &mut Stream::new(&data)).unwrap();
+        assert_eq!(index.len(), core::u16::MAX - 1);
+        assert_eq!(index.offsets.len(), core::u16::MAX);
+    }
+
+    fn variation_store() -> std::vec::Vec<u8> {
+        writer::convert(&[
+            UInt16(1), // format
+            UInt32(12), // regionListOffset
+            UInt16(1), // itemVariationDataCount
+            UInt32(16), // itemVariationDataOffsets[0]
+            // VariationRegionList
+            UInt16(0), // axisCount
+            UInt16(0), // regionCount
+            // ItemVariationData
+            UInt16(0), // itemCount
+            UInt16(0), // shortDeltaCount
+            UInt16(0), // regionIndexCount
+        ])
+    }
+
+    fn line_to_with_args(n: usize) -> std::vec::Vec<u8> {
+        let mut data = writer::convert(&[
+            CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        ]);
+        for _ in 0..n {
+            data.extend_from_slice(&writer::convert(&[CFFInt(1)]));
+        }
+        data.push(operator::LINE_TO);
+        data
+    }
+
+    #[test]
+    fn arguments_stack_len() {
+        let store = variation_store();
+        let metadata = Metadata {
+            item_variation_store: ItemVariationStore::parse(Stream::new(&store)).unwrap(),
+            ..Metadata::default()
+        };
+
+        // CFF2 allows more than 48 arguments, unlike Type 2 charstrings.
+        let data = line_to_with_args(100);
+        let rect = parse_char_string(&data, &metadata, &[], &mut crate::DummyOutline).unwrap();
+        assert_eq!(rect, Rect { x_min: 10, y_min: 20, x_max: 60, y_max: 70 });
+
+        let data = line_to_with_args(MAX_ARGUMENTS_STACK_LEN + 1);
+        let res = parse_char_string(&data, &metadata, &[], &mut crate::DummyOutline);
+        assert_eq!(res.unwrap_err().to_string(), "arguments stack limit reached");
+    }
 }
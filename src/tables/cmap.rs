@@ -2,14 +2,19 @@
 
 use core::convert::TryFrom;
 
-use crate::parser::{Stream, Offset, LazyArray16, NumFrom};
+use crate::parser::{Stream, Offset, LazyArray16, LazyArray32};
 use crate::{GlyphId, PlatformId};
 use crate::raw::cmap as raw;
+use crate::tables::name;
+
+/// The largest valid Unicode code point.
+const MAX_CODEPOINT: u32 = 0x10FFFF;
 
 #[derive(Clone, Copy)]
 pub struct Table<'a> {
     data: &'a [u8],
     records: LazyArray16<'a, raw::EncodingRecord>,
+    subtable: Option<Subtable<'a>>,
 }
 
 impl<'a> Table<'a> {
@@ -22,15 +27,42 @@ impl<'a> Table<'a> {
         Some(Table {
             data,
             records,
+            subtable: select_subtable(data, records),
         })
     }
 }
 
-pub fn glyph_index(table: &Table, c: char) -> Option<GlyphId> {
-    for record in table.records {
-        let subtable_data = table.data.get(record.offset().to_usize()..)?;
-        let mut s = Stream::new(subtable_data);
-        let format = match parse_format(s.read()?) {
+
+/// A subtable used for glyph mapping.
+#[derive(Clone, Copy)]
+struct Subtable<'a> {
+    data: &'a [u8],
+    format: Format,
+    encoding: Encoding,
+    platform_id: PlatformId,
+    encoding_id: u16,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Encoding {
+    Unicode,
+    Symbol,
+    MacRoman,
+}
+
+// Selects the most capable subtable, so we don't have to scan encoding records on each lookup.
+fn select_subtable<'a>(
+    data: &'a [u8],
+    records: LazyArray16<'a, raw::EncodingRecord>,
+) -> Option<Subtable<'a>> {
+    let mut best: Option<(u8, Subtable)> = None;
+    for record in records {
+        let subtable_data = match data.get(record.offset().to_usize()..) {
+            Some(data) => data,
+            None => continue,
+        };
+
+        let format = match Stream::read_at::<u16>(subtable_data, 0).and_then(parse_format) {
             Some(format) => format,
             None => continue,
         };
@@ -40,53 +72,376 @@ pub fn glyph_index(table: &Table, c: char) -> Option<GlyphId> {
             None => continue,
         };
 
-        if !is_unicode_encoding(format, platform_id, record.encoding_id()) {
-            continue;
+        let encoding_id = record.encoding_id();
+        let (priority, encoding) = match subtable_priority(format, platform_id, encoding_id) {
+            Some(v) => v,
+            None => continue,
+        };
+
+        if best.map(|(p, _)| priority < p).unwrap_or(true) {
+            best = Some((priority, Subtable {
+                data: subtable_data,
+                format,
+                encoding,
+                platform_id,
+                encoding_id,
+            }));
         }
+    }
+
+    best.map(|(_, subtable)| subtable)
+}
+
+// Lower is better.
+fn subtable_priority(
+    format: Format,
+    platform_id: PlatformId,
+    encoding_id: u16,
+) -> Option<(u8, Encoding)> {
+    // https://docs.microsoft.com/en-us/typography/opentype/spec/name#windows-encoding-ids
+    const WINDOWS_SYMBOL_ENCODING_ID: u16 = 0;
+    const WINDOWS_UNICODE_BMP_ENCODING_ID: u16 = 1;
+    const WINDOWS_UNICODE_FULL_REPERTOIRE_ENCODING_ID: u16 = 10;
+
+    match format {
+        // Used only by glyph_variation_index().
+        Format::UnicodeVariationSequences => return None,
+        // Unsupported.
+        Format::MixedCoverage => return None,
+        _ => {}
+    }
+
+    let is_full_repertoire_format =
+        format == Format::SegmentedCoverage || format == Format::ManyToOneRangeMappings;
 
-        let c = u32::from(c);
-        let glyph = match format {
-            Format::ByteEncodingTable => {
-                parse_byte_encoding_table(s, c)
+    match platform_id {
+        PlatformId::Windows if encoding_id == WINDOWS_UNICODE_FULL_REPERTOIRE_ENCODING_ID => {
+            // "Fonts that support Unicode supplementary-plane characters (U+10000 to U+10FFFF)
+            // on the Windows platform must have a format 12 subtable for platform ID 3,
+            // encoding ID 10."
+            if format == Format::SegmentedCoverage {
+                Some((0, Encoding::Unicode))
+            } else {
+                None
             }
-            Format::HighByteMappingThroughTable => {
-                parse_high_byte_mapping_through_table(subtable_data, c)
+        }
+        PlatformId::Unicode if is_full_repertoire_format => Some((1, Encoding::Unicode)),
+        PlatformId::Windows if encoding_id == WINDOWS_UNICODE_BMP_ENCODING_ID => {
+            if format == Format::SegmentMappingToDeltaValues {
+                Some((2, Encoding::Unicode))
+            } else {
+                Some((4, Encoding::Unicode))
             }
+        }
+        PlatformId::Unicode => Some((3, Encoding::Unicode)),
+        PlatformId::Windows if encoding_id == WINDOWS_SYMBOL_ENCODING_ID => {
+            Some((5, Encoding::Symbol))
+        }
+        PlatformId::Macintosh
+            if encoding_id == name::MACINTOSH_ROMAN_ENCODING_ID
+            && (format == Format::ByteEncodingTable || format == Format::TrimmedTableMapping) => {
+            Some((6, Encoding::MacRoman))
+        }
+        _ => None,
+    }
+}
+
+pub fn subtable_info(table: &Table) -> Option<(PlatformId, u16, u16)> {
+    let subtable = table.subtable?;
+    Some((subtable.platform_id, subtable.encoding_id, subtable.format as u16))
+}
+
+pub fn glyph_index(table: &Table, c: char) -> Option<GlyphId> {
+    let subtable = table.subtable?;
+    map_char(subtable.encoding, c, |c| subtable_glyph_index(subtable, c))
+}
+
+// Converts a character into a subtable code point and resolves it using `lookup`.
+fn map_char(
+    encoding: Encoding,
+    c: char,
+    mut lookup: impl FnMut(u32) -> Option<u16>,
+) -> Option<GlyphId> {
+    let glyph = match encoding {
+        Encoding::Unicode => lookup(u32::from(c)),
+        Encoding::Symbol => {
+            // Symbol fonts usually map characters into the U+F000..U+F0FF range,
+            // while callers expect to use the plain 8-bit codes.
+            const SYMBOL_BASE: u32 = 0xF000;
+
+            let c = u32::from(c);
+            lookup(c).or_else(|| {
+                if c <= 0xFF {
+                    lookup(SYMBOL_BASE + c)
+                } else {
+                    None
+                }
+            })
+        }
+        Encoding::MacRoman => {
+            lookup(u32::from(name::char_to_mac_roman(c)?))
+        }
+    }?;
+
+    Some(GlyphId(glyph))
+}
+
+
+/// A cursor for resolving many glyph indices in a row.
+///
+/// Has the same results as `Font::glyph_index()`, but remembers
+/// the last matched segment of a `cmap` format 4 subtable,
+/// so consecutive code points from the same segment skip the binary search.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct GlyphIndexCursor<'a> {
+    subtable: Option<Subtable<'a>>,
+    segments: Option<SegmentMapping<'a>>,
+    last_segment: Option<u16>,
+}
+
+impl<'a> GlyphIndexCursor<'a> {
+    pub(crate) fn new(table: &Table<'a>) -> Self {
+        let subtable = table.subtable;
+        // The cache can produce different results on malformed segments,
+        // so they are resolved without it.
+        let segments = subtable
+            .filter(|subtable| subtable.format == Format::SegmentMappingToDeltaValues)
+            .and_then(|subtable| SegmentMapping::parse(subtable.data))
+            .filter(|segments| segments.is_sorted());
+
+        GlyphIndexCursor { subtable, segments, last_segment: None }
+    }
+
+    /// Resolves a Glyph ID for a code point.
+    ///
+    /// Returns `None` instead of `0` when glyph is not found.
+    pub fn glyph_index(&mut self, c: char) -> Option<GlyphId> {
+        let subtable = self.subtable?;
+        match self.segments {
+            Some(segments) => {
+                let last_segment = &mut self.last_segment;
+                map_char(subtable.encoding, c, |c| segments.glyph_index_cached(c, last_segment))
+            }
+            None => map_char(subtable.encoding, c, |c| subtable_glyph_index(subtable, c)),
+        }
+    }
+}
+
+fn subtable_glyph_index(subtable: Subtable, c: u32) -> Option<u16> {
+    let data = subtable.data;
+    let mut s = Stream::new(data);
+    s.skip::<u16>(); // format
+
+    match subtable.format {
+        Format::ByteEncodingTable => {
+            parse_byte_encoding_table(s, c)
+        }
+        Format::HighByteMappingThroughTable => {
+            parse_high_byte_mapping_through_table(data, c)
+        }
+        Format::SegmentMappingToDeltaValues => {
+            parse_segment_mapping_to_delta_values(data, c)
+        }
+        Format::TrimmedTableMapping => {
+            parse_trimmed_table_mapping(s, c)
+        }
+        Format::TrimmedArray => {
+            parse_trimmed_array(s, c)
+        }
+        Format::SegmentedCoverage | Format::ManyToOneRangeMappings => {
+            parse_segmented_coverage(s, c, subtable.format)
+        }
+        Format::MixedCoverage | Format::UnicodeVariationSequences => None,
+    }
+}
+
+pub fn codepoints<'a>(table: &Table<'a>) -> Codepoints<'a> {
+    let ranges = table.subtable
+        .filter(|subtable| subtable.encoding != Encoding::MacRoman)
+        .and_then(|subtable| CodepointRanges::parse(subtable.data, subtable.format))
+        .unwrap_or(CodepointRanges::Empty);
+
+    Codepoints { ranges, index: 0, code: 0 }
+}
+
+pub fn glyph_codepoint(table: &Table, glyph_id: GlyphId) -> Option<u32> {
+    let subtable = table.subtable?;
+    let mut codepoints = codepoints(table);
+    match codepoints.ranges {
+        CodepointRanges::SegmentedCoverage(groups) => {
+            // Groups can be huge, so resolve them arithmetically
+            // instead of checking each code point.
+            groups.into_iter()
+                .filter_map(|group| group_codepoint(group, subtable.format, glyph_id))
+                // Make sure that the result is consistent with `glyph_index()`
+                // even for overlapping groups.
+                .filter(|c| subtable_glyph_index(subtable, *c) == Some(glyph_id.0))
+                .min()
+        }
+        _ => codepoints.find(|c| subtable_glyph_index(subtable, *c) == Some(glyph_id.0)),
+    }
+}
+
+// Returns the first code point of a format 12/13 group mapped to the specified glyph.
+fn group_codepoint(group: raw::SequentialMapGroup, format: Format, glyph_id: GlyphId) -> Option<u32> {
+    let start = group.start_char_code();
+    let end = core::cmp::min(group.end_char_code(), MAX_CODEPOINT);
+    if start > end {
+        return None;
+    }
+
+    let glyph = u32::from(glyph_id.0);
+    let code_point = if format == Format::SegmentedCoverage {
+        start.checked_add(glyph.checked_sub(group.start_glyph_id())?)?
+    } else if group.start_glyph_id() == glyph {
+        // All code points in a format 13 group are mapped to the same glyph.
+        start
+    } else {
+        return None;
+    };
+
+    if code_point <= end {
+        Some(code_point)
+    } else {
+        None
+    }
+}
+
+
+/// An iterator over code points mapped by a `cmap` subtable.
+///
+/// Code points are yielded in ascending order.
+/// Only subtable formats 4, 12 and 13 are supported.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct Codepoints<'a> {
+    ranges: CodepointRanges<'a>,
+    index: u32,
+    code: u32,
+}
+
+impl Default for Codepoints<'_> {
+    fn default() -> Self {
+        Codepoints { ranges: CodepointRanges::Empty, index: 0, code: 0 }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum CodepointRanges<'a> {
+    SegmentMapping {
+        data: &'a [u8],
+        start_codes: LazyArray16<'a, u16>,
+        end_codes: LazyArray16<'a, u16>,
+    },
+    SegmentedCoverage(LazyArray32<'a, raw::SequentialMapGroup>),
+    Empty,
+}
+
+impl<'a> CodepointRanges<'a> {
+    fn parse(data: &'a [u8], format: Format) -> Option<Self> {
+        let mut s = Stream::new(data);
+        match format {
             Format::SegmentMappingToDeltaValues => {
-                parse_segment_mapping_to_delta_values(subtable_data, c)
+                s.advance(6); // format + length + language
+                let seg_count = s.read::<u16>()? / 2;
+                s.advance(6); // searchRange + entrySelector + rangeShift
+                let end_codes = s.read_array16(seg_count)?;
+                s.skip::<u16>(); // reservedPad
+                let start_codes = s.read_array16(seg_count)?;
+                Some(CodepointRanges::SegmentMapping { data, start_codes, end_codes })
             }
-            Format::TrimmedTableMapping => {
-                parse_trimmed_table_mapping(s, c)
+            Format::SegmentedCoverage | Format::ManyToOneRangeMappings => {
+                s.advance(12); // format + reserved + length + language
+                let count: u32 = s.read()?;
+                s.read_array32(count).map(CodepointRanges::SegmentedCoverage)
             }
-            Format::MixedCoverage => {
-                // Unsupported.
-                continue;
+            _ => None,
+        }
+    }
+
+    fn get(&self, index: u32) -> Option<(u32, u32)> {
+        match *self {
+            CodepointRanges::SegmentMapping { start_codes, end_codes, .. } => {
+                let index = u16::try_from(index).ok()?;
+                Some((u32::from(start_codes.get(index)?), u32::from(end_codes.get(index)?)))
             }
-            Format::TrimmedArray => {
-                parse_trimmed_array(s, c)
+            CodepointRanges::SegmentedCoverage(groups) => {
+                let group = groups.get(index)?;
+                let end = core::cmp::min(group.end_char_code(), MAX_CODEPOINT);
+                Some((group.start_char_code(), end))
             }
-            Format::SegmentedCoverage | Format::ManyToOneRangeMappings => {
-                parse_segmented_coverage(s, c, format)
+            CodepointRanges::Empty => None,
+        }
+    }
+
+    fn is_mapped(&self, code_point: u32) -> bool {
+        match *self {
+            // Not all code points in a segment are mapped.
+            CodepointRanges::SegmentMapping { data, .. } => {
+                parse_segment_mapping_to_delta_values(data, code_point).is_some()
             }
-            Format::UnicodeVariationSequences => {
-                // This subtable is used only by glyph_variation_index().
+            _ => true,
+        }
+    }
+}
+
+impl<'a> Iterator for Codepoints<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (start, end) = self.ranges.get(self.index)?;
+            if start > end {
+                // Malformed or entirely out of the Unicode range.
+                self.index += 1;
                 continue;
             }
-        };
 
-        if let Some(id) = glyph {
-            return Some(GlyphId(id));
+            if self.code < start {
+                self.code = start;
+            }
+
+            if self.code > end {
+                self.index += 1;
+                continue;
+            }
+
+            let code_point = self.code;
+            if code_point == end {
+                self.index += 1;
+            }
+            self.code = code_point.saturating_add(1);
+
+            if self.ranges.is_mapped(code_point) {
+                return Some(code_point);
+            }
         }
     }
+}
 
-    None
+
+/// A result of a variation glyph mapping.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GlyphVariationResult {
+    /// Glyph was found in the variation encoding table.
+    Found(GlyphId),
+    /// Glyph should be resolved via the default encoding table.
+    ///
+    /// Use `Font::glyph_index` in this case.
+    UseDefault,
+    /// Variation sequence is not supported.
+    NotFound,
 }
 
-pub fn glyph_variation_index(table: &Table, c: char, variation: char) -> Option<GlyphId> {
+pub fn glyph_variation_index(table: &Table, c: char, variation: char) -> GlyphVariationResult {
     for record in table.records {
-        let subtable_data = table.data.get(record.offset().to_usize()..)?;
-        let mut s = Stream::new(subtable_data);
-        let format = match parse_format(s.read()?) {
+        let subtable_data = match table.data.get(record.offset().to_usize()..) {
+            Some(data) => data,
+            None => continue,
+        };
+
+        let format = match Stream::read_at::<u16>(subtable_data, 0).and_then(parse_format) {
             Some(format) => format,
             None => continue,
         };
@@ -95,18 +450,19 @@ pub fn glyph_variation_index(table: &Table, c: char, variation: char) -> Option<
             continue;
         }
 
-        return parse_unicode_variation_sequences(table, subtable_data, c, u32::from(variation));
+        return parse_unicode_variation_sequences(subtable_data, c, u32::from(variation))
+            .unwrap_or(GlyphVariationResult::NotFound);
     }
 
-    None
+    GlyphVariationResult::NotFound
 }
 
+// https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-14-unicode-variation-sequences
 fn parse_unicode_variation_sequences(
-    table: &Table,
     data: &[u8],
     c: char,
     variation: u32,
-) -> Option<GlyphId> {
+) -> Option<GlyphVariationResult> {
     let cp = u32::from(c);
 
     let mut s = Stream::new(data);
@@ -115,10 +471,7 @@ fn parse_unicode_variation_sequences(
     let count: u32 = s.read()?;
     let records = s.read_array32::<raw::VariationSelectorRecord>(count)?;
 
-    let (_, record) = match records.binary_search_by(|v| v.var_selector().cmp(&variation)) {
-        Some(v) => v,
-        None => return None,
-    };
+    let (_, record) = records.binary_search_by_key(&variation, |v| v.var_selector())?;
 
     if let Some(offset) = record.default_uvs_offset() {
         let data = data.get(offset.to_usize()..)?;
@@ -127,8 +480,7 @@ fn parse_unicode_variation_sequences(
         let ranges = s.read_array32::<raw::UnicodeRangeRecord>(count)?;
         for range in ranges {
             if range.contains(c) {
-                // This is a default glyph.
-                return glyph_index(table, c);
+                return Some(GlyphVariationResult::UseDefault);
             }
         }
     }
@@ -138,8 +490,8 @@ fn parse_unicode_variation_sequences(
         let mut s = Stream::new(data);
         let count: u32 = s.read()?;
         let uvs_mappings = s.read_array32::<raw::UVSMappingRecord>(count)?;
-        if let Some((_, mapping)) = uvs_mappings.binary_search_by(|v| v.unicode_value().cmp(&cp)) {
-            return Some(mapping.glyph_id());
+        if let Some((_, mapping)) = uvs_mappings.binary_search_by_key(&cp, |v| v.unicode_value()) {
+            return Some(GlyphVariationResult::Found(mapping.glyph_id()));
         }
     }
 
@@ -148,15 +500,13 @@ fn parse_unicode_variation_sequences(
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-0-byte-encoding-table
 fn parse_byte_encoding_table(mut s: Stream, code_point: u32) -> Option<u16> {
-    let length: u16 = s.read()?;
+    s.skip::<u16>(); // length
     s.skip::<u16>(); // language
+    let glyphs = s.read_array16::<u8>(256)?;
 
-    if code_point < u32::from(length) {
-        s.advance(usize::num_from(code_point));
-        Some(u16::from(s.read::<u8>()?))
-    } else {
-        None
-    }
+    // This subtable supports code points only in a u8 range.
+    let id = glyphs.get(u16::try_from(code_point).ok()?)?;
+    Some(u16::from(id)).filter(|id| *id != 0)
 }
 
 // This table has a pretty complex parsing algorithm.
@@ -230,60 +580,142 @@ fn parse_segment_mapping_to_delta_values(data: &[u8], code_point: u32) -> Option
     // This subtable supports code points only in a u16 range.
     let code_point = u16::try_from(code_point).ok()?;
 
-    let mut s = Stream::new(data);
-    s.advance(6); // format + length + language
-    let seg_count_x2: u16 = s.read()?;
-    if seg_count_x2 < 2 {
-        return None;
+    let segments = SegmentMapping::parse(data)?;
+    let index = segments.find_segment(code_point)?;
+    segments.glyph_index(index, code_point)
+}
+
+#[derive(Clone, Copy)]
+struct SegmentMapping<'a> {
+    data: &'a [u8],
+    end_codes: LazyArray16<'a, u16>,
+    start_codes: LazyArray16<'a, u16>,
+    id_deltas: LazyArray16<'a, i16>,
+    id_range_offset_pos: usize,
+    id_range_offsets: LazyArray16<'a, u16>,
+}
+
+impl<'a> SegmentMapping<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        s.advance(6); // format + length + language
+        let seg_count_x2: u16 = s.read()?;
+        if seg_count_x2 < 2 {
+            return None;
+        }
+
+        let seg_count = seg_count_x2 / 2;
+        s.advance(6); // searchRange + entrySelector + rangeShift
+
+        let end_codes = s.read_array16::<u16>(seg_count)?;
+        s.skip::<u16>(); // reservedPad
+        let start_codes = s.read_array16::<u16>(seg_count)?;
+        let id_deltas = s.read_array16::<i16>(seg_count)?;
+        let id_range_offset_pos = s.offset();
+        let id_range_offsets = s.read_array16::<u16>(seg_count)?;
+
+        Some(SegmentMapping {
+            data,
+            end_codes,
+            start_codes,
+            id_deltas,
+            id_range_offset_pos,
+            id_range_offsets,
+        })
     }
 
-    let seg_count = seg_count_x2 / 2;
-    s.advance(6); // searchRange + entrySelector + rangeShift
-
-    let end_codes = s.read_array16::<u16>(seg_count)?;
-    s.skip::<u16>(); // reservedPad
-    let start_codes = s.read_array16::<u16>(seg_count)?;
-    let id_deltas = s.read_array16::<i16>(seg_count)?;
-    let id_range_offset_pos = s.offset();
-    let id_range_offsets = s.read_array16::<u16>(seg_count)?;
-
-    // A custom binary search.
-    let mut start = 0;
-    let mut end = seg_count;
-    while end > start {
-        let index = (start + end) / 2;
-        let end_value = end_codes.get(index)?;
-        if end_value >= code_point {
-            let start_value = start_codes.get(index)?;
-            if start_value > code_point {
-                end = index;
-            } else {
-                let id_range_offset = id_range_offsets.get(index)?;
-                let id_delta = id_deltas.get(index)?;
-                if id_range_offset == 0 {
-                    return Some(code_point.wrapping_add(id_delta as u16));
+    // Checks that segments are sorted and do not overlap,
+    // so a code point can belong only to a single segment.
+    fn is_sorted(&self) -> bool {
+        let mut prev_end: Option<u16> = None;
+        for (start, end) in self.start_codes.into_iter().zip(self.end_codes) {
+            if start > end {
+                return false;
+            }
+
+            if let Some(prev_end) = prev_end {
+                if start <= prev_end {
+                    return false;
                 }
+            }
 
-                let delta = (u32::from(code_point) - u32::from(start_value)) * 2;
-                let delta = u16::try_from(delta).ok()?;
+            prev_end = Some(end);
+        }
 
-                let id_range_offset_pos = (id_range_offset_pos + usize::from(index) * 2) as u16;
-                let pos = id_range_offset_pos.wrapping_add(delta);
-                let pos = pos.wrapping_add(id_range_offset);
-                let glyph_array_value: u16 = Stream::read_at(data, usize::from(pos))?;
-                if glyph_array_value == 0 {
-                    return None;
-                }
+        true
+    }
+
+    // Checks the `last_segment` first and falls back to the binary search.
+    fn glyph_index_cached(&self, code_point: u32, last_segment: &mut Option<u16>) -> Option<u16> {
+        // This subtable supports code points only in a u16 range.
+        let code_point = u16::try_from(code_point).ok()?;
 
-                let glyph_id = (glyph_array_value as i16).wrapping_add(id_delta);
-                return u16::try_from(glyph_id).ok();
+        let index = match *last_segment {
+            Some(index) if self.segment_contains(index, code_point) => index,
+            _ => {
+                let index = self.find_segment(code_point)?;
+                *last_segment = Some(index);
+                index
             }
-        } else {
-            start = index + 1;
+        };
+
+        self.glyph_index(index, code_point)
+    }
+
+    #[inline]
+    fn segment_contains(&self, index: u16, code_point: u16) -> bool {
+        match (self.start_codes.get(index), self.end_codes.get(index)) {
+            (Some(start), Some(end)) => start <= code_point && code_point <= end,
+            _ => false,
         }
     }
 
-    None
+    fn find_segment(&self, code_point: u16) -> Option<u16> {
+        // A custom binary search.
+        let mut start = 0;
+        let mut end = self.end_codes.len();
+        while end > start {
+            let index = (start + end) / 2;
+            let end_value = self.end_codes.get(index)?;
+            if end_value >= code_point {
+                let start_value = self.start_codes.get(index)?;
+                if start_value > code_point {
+                    end = index;
+                } else {
+                    return Some(index);
+                }
+            } else {
+                start = index + 1;
+            }
+        }
+
+        None
+    }
+
+    fn glyph_index(&self, index: u16, code_point: u16) -> Option<u16> {
+        let start_value = self.start_codes.get(index)?;
+        let id_range_offset = self.id_range_offsets.get(index)?;
+        let id_delta = self.id_deltas.get(index)?;
+        // All arithmetic is modulo 65536.
+        let glyph_id = if id_range_offset == 0 {
+            code_point.wrapping_add(id_delta as u16)
+        } else {
+            // 'The value of the idRangeOffset is the number of bytes
+            // past the actual location of the idRangeOffset'.
+            let pos = self.id_range_offset_pos
+                + usize::from(index) * 2
+                + usize::from(id_range_offset)
+                + usize::from(code_point - start_value) * 2;
+            let glyph_array_value: u16 = Stream::read_at(self.data, pos)?;
+            if glyph_array_value == 0 {
+                return None;
+            }
+
+            glyph_array_value.wrapping_add(id_delta as u16)
+        };
+
+        if glyph_id != 0 { Some(glyph_id) } else { None }
+    }
 }
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-6-trimmed-table-mapping
@@ -298,7 +730,7 @@ fn parse_trimmed_table_mapping(mut s: Stream, code_point: u32) -> Option<u16> {
     let glyphs = s.read_array16::<u16>(count)?;
 
     let idx = code_point.checked_sub(first_code_point)?;
-    glyphs.get(idx)
+    glyphs.get(idx).filter(|id| *id != 0)
 }
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-10-trimmed-array
@@ -323,20 +755,26 @@ fn parse_segmented_coverage(mut s: Stream, code_point: u32, format: Format) -> O
     s.skip::<u32>(); // language
     let count: u32 = s.read()?;
     let groups = s.read_array32::<raw::SequentialMapGroup>(count)?;
-    for group in groups {
-        let start_char_code = group.start_char_code();
-        if code_point >= start_char_code && code_point <= group.end_char_code() {
-            let id = if format == Format::SegmentedCoverage {
-                group.start_glyph_id().checked_add(code_point)?.checked_sub(start_char_code)?
-            } else {
-                group.start_glyph_id()
-            };
 
-            return u16::try_from(id).ok();
+    // Groups are sorted by start code and do not overlap.
+    let (_, group) = groups.binary_search_by(|group| {
+        use core::cmp::Ordering;
+        if group.end_char_code() < code_point {
+            Ordering::Less
+        } else if group.start_char_code() > code_point {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
         }
-    }
+    })?;
 
-    None
+    let id = if format == Format::SegmentedCoverage {
+        group.start_glyph_id().checked_add(code_point - group.start_char_code())?
+    } else {
+        group.start_glyph_id()
+    };
+
+    u16::try_from(id).ok()
 }
 
 
@@ -370,28 +808,498 @@ fn parse_format(v: u16) -> Option<Format> {
 
 impl raw::UnicodeRangeRecord {
     fn contains(&self, c: char) -> bool {
-        let start_unicode_value = self.start_unicode_value();
-        let end = start_unicode_value + u32::from(self.additional_count());
-        start_unicode_value >= u32::from(c) && u32::from(c) < end
+        // The range is inclusive: `additional_count` is the number of code points
+        // after the first one.
+        let start = self.start_unicode_value();
+        let end = start + u32::from(self.additional_count());
+        (start..=end).contains(&u32::from(c))
     }
 }
 
-#[inline]
-fn is_unicode_encoding(format: Format, platform_id: PlatformId, encoding_id: u16) -> bool {
-    // https://docs.microsoft.com/en-us/typography/opentype/spec/name#windows-encoding-ids
-    const WINDOWS_UNICODE_BMP_ENCODING_ID: u16 = 1;
-    const WINDOWS_UNICODE_FULL_REPERTOIRE_ENCODING_ID: u16 = 10;
 
-    match platform_id {
-        PlatformId::Unicode => true,
-        PlatformId::Windows if encoding_id == WINDOWS_UNICODE_BMP_ENCODING_ID => true,
-        PlatformId::Windows => {
-            // "Fonts that support Unicode supplementary-plane characters (U+10000 to U+10FFFF)
-            // on the Windows platform must have a format 12 subtable for platform ID 3,
-            // encoding ID 10."
-               encoding_id == WINDOWS_UNICODE_FULL_REPERTOIRE_ENCODING_ID
-            && format == Format::SegmentedCoverage
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    // A cmap table with a single Windows Unicode BMP subtable.
+    fn table(subtable: &[u8]) -> std::vec::Vec<u8> {
+        let mut data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numTables
+            UInt16(3), // platformID
+            UInt16(1), // encodingID
+            UInt32(12), // offset
+        ]);
+        data.extend_from_slice(subtable);
+        data
+    }
+
+    #[test]
+    fn segment_mapping_to_delta_values() {
+        let data = table(&writer::convert(&[
+            UInt16(4), // format
+            UInt16(44), // length
+            UInt16(0), // language
+            UInt16(6), // segCountX2
+            UInt16(4), // searchRange
+            UInt16(1), // entrySelector
+            UInt16(2), // rangeShift
+            // End character codes.
+            UInt16(67), // char code [0]
+            UInt16(257), // char code [1]
+            UInt16(65535), // char code [2]
+            UInt16(0), // reserved
+            // Start character codes.
+            UInt16(65), // char code [0]
+            UInt16(256), // char code [1]
+            UInt16(65535), // char code [2]
+            // Deltas.
+            Int16(-64), // delta [0]
+            Int16(0), // delta [1]
+            Int16(1), // delta [2]
+            // Offsets into Glyph index array.
+            UInt16(0), // offset [0]
+            UInt16(4), // offset [1]
+            UInt16(0), // offset [2]
+            // Glyph index array.
+            UInt16(0x8001), // glyph ID [0]
+            UInt16(0), // glyph ID [1]
+        ]));
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_index(&table, 'A'), Some(GlyphId(1)));
+        assert_eq!(glyph_index(&table, 'C'), Some(GlyphId(3)));
+        assert_eq!(glyph_index(&table, 'D'), None);
+        assert_eq!(glyph_index(&table, '@'), None);
+        assert_eq!(glyph_index(&table, '\u{100}'), Some(GlyphId(0x8001)));
+        assert_eq!(glyph_index(&table, '\u{101}'), None);
+        assert_eq!(glyph_index(&table, '\u{FFFF}'), None);
+        assert_eq!(glyph_index(&table, '\u{10000}'), None);
+
+        let mut cursor = GlyphIndexCursor::new(&table);
+        for c in &['A', 'B', '\u{100}', 'C', 'D', 'A', '\u{101}', '\u{FFFF}', '\u{10000}', 'C'] {
+            assert_eq!(cursor.glyph_index(*c), glyph_index(&table, *c), "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn glyph_index_cursor_overlapping_segments() {
+        let data = table(&writer::convert(&[
+            UInt16(4), // format
+            UInt16(40), // length
+            UInt16(0), // language
+            UInt16(6), // segCountX2
+            UInt16(4), // searchRange
+            UInt16(1), // entrySelector
+            UInt16(2), // rangeShift
+            // End character codes.
+            UInt16(80), // char code [0]
+            UInt16(75), // char code [1]
+            UInt16(65535), // char code [2]
+            UInt16(0), // reserved
+            // Start character codes.
+            UInt16(65), // char code [0]
+            UInt16(70), // char code [1]
+            UInt16(65535), // char code [2]
+            // Deltas.
+            Int16(0), // delta [0]
+            Int16(100), // delta [1]
+            Int16(1), // delta [2]
+            // Offsets into Glyph index array.
+            UInt16(0), // offset [0]
+            UInt16(0), // offset [1]
+            UInt16(0), // offset [2]
+        ]));
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_index(&table, 'B'), Some(GlyphId(66)));
+        assert_eq!(glyph_index(&table, 'G'), Some(GlyphId(171)));
+
+        // 'G' belongs to both segments, so the segment used for 'B' must not be reused.
+        let mut cursor = GlyphIndexCursor::new(&table);
+        for c in &['B', 'G', 'B', 'P', 'G'] {
+            assert_eq!(cursor.glyph_index(*c), glyph_index(&table, *c), "{:?}", c);
         }
-        _ => false,
+    }
+
+    #[test]
+    fn segmented_coverage() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numTables
+            UInt16(3), // platformID
+            UInt16(10), // encodingID
+            UInt32(12), // offset
+            // Subtable
+            UInt16(12), // format
+            UInt16(0), // reserved
+            UInt32(52), // length
+            UInt32(0), // language
+            UInt32(3), // numGroups
+            // Group 0
+            UInt32(0x41), // startCharCode
+            UInt32(0x43), // endCharCode
+            UInt32(1), // startGlyphID
+            // Group 1
+            UInt32(0x1F600), // startCharCode
+            UInt32(0x1F64F), // endCharCode
+            UInt32(100), // startGlyphID
+            // Group 2
+            UInt32(0x20000), // startCharCode
+            UInt32(0x20000), // endCharCode
+            UInt32(0x10000), // startGlyphID, out of range
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_index(&table, 'A'), Some(GlyphId(1)));
+        assert_eq!(glyph_index(&table, 'C'), Some(GlyphId(3)));
+        assert_eq!(glyph_index(&table, 'D'), None);
+        assert_eq!(glyph_index(&table, '\u{1F600}'), Some(GlyphId(100)));
+        assert_eq!(glyph_index(&table, '\u{1F64F}'), Some(GlyphId(179)));
+        assert_eq!(glyph_index(&table, '\u{1F650}'), None);
+        assert_eq!(glyph_index(&table, '\u{20000}'), None);
+    }
+
+    #[test]
+    fn byte_encoding_table() {
+        let mut data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numTables
+            UInt16(1), // platformID
+            UInt16(0), // encodingID
+            UInt32(12), // offset
+            // Subtable
+            UInt16(0), // format
+            UInt16(262), // length
+            UInt16(0), // language
+        ]);
+
+        let mut glyphs = [0u8; 256];
+        glyphs[0x41] = 17; // A
+        glyphs[0x8A] = 42; // ä in Mac OS Roman
+        data.extend_from_slice(&glyphs);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_index(&table, 'A'), Some(GlyphId(17)));
+        assert_eq!(glyph_index(&table, '\u{E4}'), Some(GlyphId(42)));
+        assert_eq!(glyph_index(&table, 'B'), None);
+        assert_eq!(glyph_index(&table, '\u{100}'), None);
+
+        // Truncated glyphs array.
+        let table = Table::parse(&data[..data.len() - 1]).unwrap();
+        assert_eq!(glyph_index(&table, 'A'), None);
+    }
+
+    #[test]
+    fn trimmed_table_mapping() {
+        let data = table(&writer::convert(&[
+            UInt16(6), // format
+            UInt16(16), // length
+            UInt16(0), // language
+            UInt16(0x41), // firstCode
+            UInt16(3), // entryCount
+            UInt16(5), // glyphIdArray [0]
+            UInt16(0), // glyphIdArray [1]
+            UInt16(7), // glyphIdArray [2]
+        ]));
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_index(&table, 'A'), Some(GlyphId(5)));
+        assert_eq!(glyph_index(&table, 'B'), None);
+        assert_eq!(glyph_index(&table, 'C'), Some(GlyphId(7)));
+        assert_eq!(glyph_index(&table, 'D'), None);
+        assert_eq!(glyph_index(&table, '@'), None);
+    }
+
+    #[test]
+    fn unicode_variation_sequences() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numTables
+            UInt16(0), // platformID
+            UInt16(5), // encodingID
+            UInt32(12), // offset
+            // Subtable
+            UInt16(14), // format
+            UInt32(38), // length
+            UInt32(1), // numVarSelectorRecords
+            Raw(&[0x00, 0xFE, 0x0F]), // varSelector
+            UInt32(21), // defaultUVSOffset
+            UInt32(29), // nonDefaultUVSOffset
+            // DefaultUVS
+            UInt32(1), // numUnicodeValueRanges
+            Raw(&[0x00, 0x26, 0x00]), // startUnicodeValue
+            UInt8(2), // additionalCount
+            // NonDefaultUVS
+            UInt32(1), // numUVSMappings
+            Raw(&[0x01, 0xF6, 0x00]), // unicodeValue
+            UInt16(50), // glyphID
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        let index = |c, variation| glyph_variation_index(&table, c, variation);
+        let vs16 = '\u{FE0F}';
+        assert_eq!(index('\u{2600}', vs16), GlyphVariationResult::UseDefault);
+        assert_eq!(index('\u{2602}', vs16), GlyphVariationResult::UseDefault);
+        assert_eq!(index('\u{2603}', vs16), GlyphVariationResult::NotFound);
+        assert_eq!(index('\u{1F600}', vs16), GlyphVariationResult::Found(GlyphId(50)));
+        assert_eq!(index('\u{1F600}', '\u{FE0E}'), GlyphVariationResult::NotFound);
+    }
+
+    #[test]
+    fn subtable_selection() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(2), // numTables
+            UInt16(1), // platformID
+            UInt16(0), // encodingID
+            UInt32(20), // offset
+            UInt16(3), // platformID
+            UInt16(10), // encodingID
+            UInt32(34), // offset
+            // Subtable format 6
+            UInt16(6), // format
+            UInt16(14), // length
+            UInt16(0), // language
+            UInt16(0x41), // firstCode
+            UInt16(2), // entryCount
+            UInt16(5), // glyphIdArray [0]
+            UInt16(6), // glyphIdArray [1]
+            // Subtable format 12
+            UInt16(12), // format
+            UInt16(0), // reserved
+            UInt32(28), // length
+            UInt32(0), // language
+            UInt32(1), // numGroups
+            UInt32(0x41), // startCharCode
+            UInt32(0x41), // endCharCode
+            UInt32(1), // startGlyphID
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_index(&table, 'A'), Some(GlyphId(1)));
+        // Only the selected subtable is used.
+        assert_eq!(glyph_index(&table, 'B'), None);
+        assert_eq!(subtable_info(&table), Some((PlatformId::Windows, 10, 12)));
+
+        // Fallback to the Mac OS Roman subtable when it's the only one.
+        let table = Table::parse(&data).unwrap();
+        let records = table.records.slice(0..1).unwrap();
+        let table = Table { subtable: select_subtable(&data, records), ..table };
+        assert_eq!(glyph_index(&table, 'B'), Some(GlyphId(6)));
+        assert_eq!(subtable_info(&table), Some((PlatformId::Macintosh, 0, 6)));
+    }
+
+    #[test]
+    fn codepoints_format4() {
+        let data = table(&writer::convert(&[
+            UInt16(4), // format
+            UInt16(44), // length
+            UInt16(0), // language
+            UInt16(6), // segCountX2
+            UInt16(4), // searchRange
+            UInt16(1), // entrySelector
+            UInt16(2), // rangeShift
+            // End character codes.
+            UInt16(67), // char code [0]
+            UInt16(257), // char code [1]
+            UInt16(65535), // char code [2]
+            UInt16(0), // reserved
+            // Start character codes.
+            UInt16(65), // char code [0]
+            UInt16(256), // char code [1]
+            UInt16(65535), // char code [2]
+            // Deltas.
+            Int16(-64), // delta [0]
+            Int16(0), // delta [1]
+            Int16(1), // delta [2]
+            // Offsets into Glyph index array.
+            UInt16(0), // offset [0]
+            UInt16(4), // offset [1]
+            UInt16(0), // offset [2]
+            // Glyph index array.
+            UInt16(10), // glyph ID [0]
+            UInt16(0), // glyph ID [1]
+        ]));
+
+        let table = Table::parse(&data).unwrap();
+        let codepoints: std::vec::Vec<u32> = codepoints(&table).collect();
+        assert_eq!(codepoints, &[65, 66, 67, 256]);
+
+        assert_eq!(glyph_codepoint(&table, GlyphId(2)), Some(66));
+        assert_eq!(glyph_codepoint(&table, GlyphId(10)), Some(256));
+        assert_eq!(glyph_codepoint(&table, GlyphId(0)), None);
+        assert_eq!(glyph_codepoint(&table, GlyphId(4)), None);
+    }
+
+    #[test]
+    fn codepoints_format12() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numTables
+            UInt16(3), // platformID
+            UInt16(10), // encodingID
+            UInt32(12), // offset
+            // Subtable
+            UInt16(12), // format
+            UInt16(0), // reserved
+            UInt32(40), // length
+            UInt32(0), // language
+            UInt32(2), // numGroups
+            // Group 0
+            UInt32(0x41), // startCharCode
+            UInt32(0x42), // endCharCode
+            UInt32(1), // startGlyphID
+            // Group 1
+            UInt32(0x10FFFE), // startCharCode
+            UInt32(0x10FFFF), // endCharCode
+            UInt32(3), // startGlyphID
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        let codepoints: std::vec::Vec<u32> = codepoints(&table).collect();
+        assert_eq!(codepoints, &[0x41, 0x42, 0x10FFFE, 0x10FFFF]);
+
+        assert_eq!(glyph_codepoint(&table, GlyphId(1)), Some(0x41));
+        assert_eq!(glyph_codepoint(&table, GlyphId(4)), Some(0x10FFFF));
+        assert_eq!(glyph_codepoint(&table, GlyphId(5)), None);
+    }
+
+    #[test]
+    fn codepoints_format12_malformed_groups() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numTables
+            UInt16(3), // platformID
+            UInt16(10), // encodingID
+            UInt32(12), // offset
+            // Subtable
+            UInt16(12), // format
+            UInt16(0), // reserved
+            UInt32(52), // length
+            UInt32(0), // language
+            UInt32(3), // numGroups
+            // Group 0: start > end
+            UInt32(0x50), // startCharCode
+            UInt32(0x40), // endCharCode
+            UInt32(1), // startGlyphID
+            // Group 1: end is past U+10FFFF
+            UInt32(0x10FFFE), // startCharCode
+            UInt32(0xFFFFFFFF), // endCharCode
+            UInt32(3), // startGlyphID
+            // Group 2: entirely past U+10FFFF
+            UInt32(0x110000), // startCharCode
+            UInt32(0x110001), // endCharCode
+            UInt32(5), // startGlyphID
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        let codepoints: std::vec::Vec<u32> = codepoints(&table).collect();
+        assert_eq!(codepoints, &[0x10FFFE, 0x10FFFF]);
+    }
+
+    #[test]
+    fn glyph_codepoint_huge_groups() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numTables
+            UInt16(3), // platformID
+            UInt16(10), // encodingID
+            UInt32(12), // offset
+            // Subtable
+            UInt16(12), // format
+            UInt16(0), // reserved
+            UInt32(40), // length
+            UInt32(0), // language
+            UInt32(2), // numGroups
+            // Group 0
+            UInt32(0x20), // startCharCode
+            UInt32(0xFFFFFFFF), // endCharCode
+            UInt32(1), // startGlyphID
+            // Group 1
+            UInt32(0xFFFFFFF0), // startCharCode
+            UInt32(0xFFFFFFFF), // endCharCode
+            UInt32(7), // startGlyphID
+        ]);
+
+        // Must not iterate over all code points.
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_codepoint(&table, GlyphId(0)), None);
+        assert_eq!(glyph_codepoint(&table, GlyphId(1)), Some(0x20));
+        assert_eq!(glyph_codepoint(&table, GlyphId(10)), Some(0x29));
+    }
+
+    #[test]
+    fn glyph_codepoint_format13() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numTables
+            UInt16(0), // platformID
+            UInt16(6), // encodingID
+            UInt32(12), // offset
+            // Subtable
+            UInt16(13), // format
+            UInt16(0), // reserved
+            UInt32(40), // length
+            UInt32(0), // language
+            UInt32(2), // numGroups
+            // Group 0
+            UInt32(0x41), // startCharCode
+            UInt32(0x10FFFF), // endCharCode
+            UInt32(2), // glyphID
+            // Group 1
+            UInt32(0x110000), // startCharCode
+            UInt32(0xFFFFFFFF), // endCharCode
+            UInt32(3), // glyphID
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_codepoint(&table, GlyphId(2)), Some(0x41));
+        // Out of the Unicode range.
+        assert_eq!(glyph_codepoint(&table, GlyphId(3)), None);
+    }
+
+    #[test]
+    fn symbol_encoding() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numTables
+            UInt16(3), // platformID
+            UInt16(0), // encodingID
+            UInt32(12), // offset
+            // Subtable
+            UInt16(6), // format
+            UInt16(14), // length
+            UInt16(0), // language
+            UInt16(0xF041), // firstCode
+            UInt16(2), // entryCount
+            UInt16(5), // glyphIdArray [0]
+            UInt16(6), // glyphIdArray [1]
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_index(&table, '\u{F041}'), Some(GlyphId(5)));
+        assert_eq!(glyph_index(&table, 'A'), Some(GlyphId(5)));
+        assert_eq!(glyph_index(&table, 'B'), Some(GlyphId(6)));
+        assert_eq!(glyph_index(&table, 'C'), None);
+        assert_eq!(glyph_index(&table, '\u{141}'), None);
+    }
+
+    #[test]
+    fn symbol_offset_is_not_used_for_unicode_subtables() {
+        let data = table(&writer::convert(&[
+            UInt16(6), // format
+            UInt16(12), // length
+            UInt16(0), // language
+            UInt16(0xF041), // firstCode
+            UInt16(1), // entryCount
+            UInt16(5), // glyphIdArray [0]
+        ]));
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_index(&table, '\u{F041}'), Some(GlyphId(5)));
+        assert_eq!(glyph_index(&table, 'A'), None);
     }
 }
@@ -0,0 +1,143 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/colr
+
+use crate::GlyphId;
+use crate::parser::{Stream, LazyArray16, Offset, Offset32};
+use crate::raw::colr as raw;
+
+#[derive(Clone, Copy)]
+pub struct Table<'a> {
+    base_glyphs: LazyArray16<'a, raw::BaseGlyphRecord>,
+    layers: LazyArray16<'a, raw::LayerRecord>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+
+        // Version 1 tables start with the same header, so version 0 records can still be used.
+        let version: u16 = s.read()?;
+        if version > 1 {
+            return None;
+        }
+
+        let num_base_glyphs: u16 = s.read()?;
+        let base_glyphs_offset: Offset32 = s.read()?;
+        let layers_offset: Offset32 = s.read()?;
+        let num_layers: u16 = s.read()?;
+
+        let base_glyphs = Stream::new_at(data, base_glyphs_offset.to_usize())?
+            .read_array16(num_base_glyphs)?;
+        let layers = Stream::new_at(data, layers_offset.to_usize())?
+            .read_array16(num_layers)?;
+
+        Some(Table {
+            base_glyphs,
+            layers,
+        })
+    }
+
+    pub fn glyph_layers(&self, glyph_id: GlyphId) -> Option<ColorLayers<'a>> {
+        let (_, base) = self.base_glyphs.binary_search_by_key(&glyph_id, |r| r.glyph_id())?;
+        let start = base.first_layer_index();
+        let end = start.checked_add(base.num_layers())?;
+        Some(ColorLayers {
+            layers: self.layers.slice(start..end)?,
+            index: 0,
+        })
+    }
+}
+
+
+/// An iterator over color glyph layers.
+///
+/// Yields a glyph ID and a palette entry index for each layer, from bottom to top.
+/// A palette entry index of `0xFFFF` indicates the text foreground color.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct ColorLayers<'a> {
+    layers: LazyArray16<'a, raw::LayerRecord>,
+    index: u16,
+}
+
+impl<'a> Iterator for ColorLayers<'a> {
+    type Item = (GlyphId, u16);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let layer = self.layers.get(self.index)?;
+        self.index += 1;
+        Some((layer.glyph_id(), layer.palette_index()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::from(self.layers.len().saturating_sub(self.index));
+        (len, Some(len))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn glyph_layers() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(2), // numBaseGlyphRecords
+            UInt32(14), // baseGlyphRecordsOffset
+            UInt32(26), // layerRecordsOffset
+            UInt16(3), // numLayerRecords
+            // BaseGlyphRecord 0
+            UInt16(2), // glyphID
+            UInt16(0), // firstLayerIndex
+            UInt16(2), // numLayers
+            // BaseGlyphRecord 1
+            UInt16(5), // glyphID
+            UInt16(2), // firstLayerIndex
+            UInt16(1), // numLayers
+            // LayerRecord 0
+            UInt16(10), // glyphID
+            UInt16(1), // paletteIndex
+            // LayerRecord 1
+            UInt16(11), // glyphID
+            UInt16(0xFFFF), // paletteIndex
+            // LayerRecord 2
+            UInt16(12), // glyphID
+            UInt16(3), // paletteIndex
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        let layers: std::vec::Vec<_> = table.glyph_layers(GlyphId(2)).unwrap().collect();
+        assert_eq!(layers, &[(GlyphId(10), 1), (GlyphId(11), 0xFFFF)]);
+
+        let layers: std::vec::Vec<_> = table.glyph_layers(GlyphId(5)).unwrap().collect();
+        assert_eq!(layers, &[(GlyphId(12), 3)]);
+
+        assert!(table.glyph_layers(GlyphId(3)).is_none());
+    }
+
+    #[test]
+    fn layers_out_of_bounds() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numBaseGlyphRecords
+            UInt32(14), // baseGlyphRecordsOffset
+            UInt32(20), // layerRecordsOffset
+            UInt16(1), // numLayerRecords
+            // BaseGlyphRecord 0
+            UInt16(2), // glyphID
+            UInt16(0), // firstLayerIndex
+            UInt16(2), // numLayers
+            // LayerRecord 0
+            UInt16(10), // glyphID
+            UInt16(1), // paletteIndex
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        assert!(table.glyph_layers(GlyphId(2)).is_none());
+    }
+}
@@ -0,0 +1,82 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/cpal
+
+use crate::parser::{Stream, LazyArray16, Offset, Offset32};
+use crate::raw::cpal as raw;
+
+#[derive(Clone, Copy)]
+pub struct Table<'a> {
+    palette_entries: u16,
+    color_record_indices: LazyArray16<'a, u16>,
+    colors: LazyArray16<'a, raw::ColorRecord>,
+}
+
+impl<'a> Table<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+
+        // Version 1 only appends fields to the version 0 header.
+        let version: u16 = s.read()?;
+        if version > 1 {
+            return None;
+        }
+
+        let palette_entries: u16 = s.read()?;
+        let palettes_count: u16 = s.read()?;
+        let color_records_count: u16 = s.read()?;
+        let color_records_offset: Offset32 = s.read()?;
+        let color_record_indices = s.read_array16(palettes_count)?;
+
+        let colors = Stream::new_at(data, color_records_offset.to_usize())?
+            .read_array16(color_records_count)?;
+
+        Some(Table {
+            palette_entries,
+            color_record_indices,
+            colors,
+        })
+    }
+
+    pub fn color(&self, palette: u16, index: u16) -> Option<[u8; 4]> {
+        if index >= self.palette_entries {
+            return None;
+        }
+
+        let first = self.color_record_indices.get(palette)?;
+        let record = self.colors.get(first.checked_add(index)?)?;
+        Some([record.red(), record.green(), record.blue(), record.alpha()])
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn colors() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(2), // numPaletteEntries
+            UInt16(2), // numPalettes
+            UInt16(3), // numColorRecords
+            UInt32(16), // colorRecordsArrayOffset
+            UInt16(0), // colorRecordIndices [0]
+            UInt16(1), // colorRecordIndices [1]
+            // ColorRecords in BGRA order
+            UInt8(1), UInt8(2), UInt8(3), UInt8(255),
+            UInt8(10), UInt8(20), UInt8(30), UInt8(128),
+            UInt8(0), UInt8(0), UInt8(0), UInt8(0),
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.color(0, 0), Some([3, 2, 1, 255]));
+        assert_eq!(table.color(0, 1), Some([30, 20, 10, 128]));
+        // Palettes can share color records.
+        assert_eq!(table.color(1, 0), Some([30, 20, 10, 128]));
+        assert_eq!(table.color(1, 1), Some([0, 0, 0, 0]));
+        assert_eq!(table.color(0, 2), None);
+        assert_eq!(table.color(2, 0), None);
+    }
+}
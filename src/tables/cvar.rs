@@ -0,0 +1,291 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/cvar
+// https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#tuple-variation-store
+
+use crate::NormalizedCoord;
+use crate::parser::{Stream, Offset, Offset16, LazyArray16, F2DOT14};
+use crate::gvar::{parse_tuple_variation_header, packed_points::PackedPointsIter};
+use crate::gvar::packed_deltas::PackedSingleDeltasIter;
+
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    data: &'a [u8],
+    tuple_variation_count: u16,
+    has_shared_point_numbers: bool,
+    data_offset: Offset16,
+    cvt_len: u16,
+}
+
+impl<'a> Table<'a> {
+    /// `cvt_len` is the number of values in the `cvt ` table.
+    pub fn parse(data: &'a [u8], cvt_len: u16) -> Option<Self> {
+        const SHARED_POINT_NUMBERS_FLAG: u16 = 0x8000;
+        const COUNT_MASK: u16 = 0x0FFF;
+
+        let mut s = Stream::new(data);
+        let version: u32 = s.read()?;
+        if version != 0x00010000 {
+            return None;
+        }
+
+        let tuple_variation_count: u16 = s.read()?;
+        let data_offset: Offset16 = s.read()?;
+
+        Some(Table {
+            data,
+            tuple_variation_count: tuple_variation_count & COUNT_MASK,
+            has_shared_point_numbers: tuple_variation_count & SHARED_POINT_NUMBERS_FLAG != 0,
+            data_offset,
+            cvt_len,
+        })
+    }
+
+    pub fn cvt_deltas<'b>(&self, coordinates: &'b [NormalizedCoord]) -> CvtDeltas<'b>
+        where 'a: 'b
+    {
+        self.cvt_deltas_impl(coordinates).unwrap_or_default()
+    }
+
+    fn cvt_deltas_impl<'b>(&self, coordinates: &'b [NormalizedCoord]) -> Option<CvtDeltas<'b>>
+        where 'a: 'b
+    {
+        // Not a variable font.
+        if coordinates.is_empty() {
+            return None;
+        }
+
+        // Skip version, tupleVariationCount and dataOffset.
+        let main_s = Stream::new_at(self.data, 8)?;
+        let mut serialized_s = Stream::new_at(self.data, self.data_offset.to_usize())?;
+
+        // All tuples can reference the same CVT indices,
+        // which are defined at the start of the serialized data.
+        let mut shared_point_numbers = None;
+        if self.has_shared_point_numbers {
+            shared_point_numbers = PackedPointsIter::new(&mut serialized_s)?;
+        }
+
+        Some(CvtDeltas {
+            coordinates,
+            cvt_len: self.cvt_len,
+            tuples_left: self.tuple_variation_count,
+            main_s,
+            serialized_s,
+            shared_point_numbers,
+            tuple: None,
+        })
+    }
+}
+
+
+#[derive(Clone, Copy)]
+struct TupleDeltas<'a> {
+    // `None` indicates that all CVT values are referenced.
+    point_numbers: Option<PackedPointsIter<'a>>,
+    // The next CVT index when all values are referenced
+    // or the last referenced one otherwise.
+    index: u16,
+    is_first: bool,
+    deltas: PackedSingleDeltasIter<'a>,
+}
+
+impl TupleDeltas<'_> {
+    fn next(&mut self, cvt_len: u16) -> Option<(u16, f32)> {
+        if let Some(ref mut point_numbers) = self.point_numbers {
+            // Point numbers are stored as differences from the previous one.
+            let n = point_numbers.next()?;
+            self.index = if self.is_first { n } else { self.index.checked_add(n)? };
+            self.is_first = false;
+
+            // Indices are sorted, so all the following ones are out of bounds too.
+            if self.index >= cvt_len {
+                return None;
+            }
+
+            Some((self.index, self.deltas.next()?))
+        } else {
+            if self.index >= cvt_len {
+                return None;
+            }
+
+            let index = self.index;
+            self.index += 1;
+            Some((index, self.deltas.next()?))
+        }
+    }
+}
+
+
+/// An iterator over `cvt ` value deltas for the current variation instance.
+///
+/// Each item is a CVT index and a delta that should be added to the corresponding value.
+/// The same index can be yielded multiple times, once per applicable variation tuple,
+/// in which case all deltas should be accumulated.
+///
+/// Iteration stops at the first malformed tuple.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct CvtDeltas<'a> {
+    coordinates: &'a [NormalizedCoord],
+    cvt_len: u16,
+    tuples_left: u16,
+    main_s: Stream<'a>,
+    serialized_s: Stream<'a>,
+    shared_point_numbers: Option<PackedPointsIter<'a>>,
+    tuple: Option<TupleDeltas<'a>>,
+}
+
+impl<'a> CvtDeltas<'a> {
+    fn next_tuple(&mut self) -> Option<TupleDeltas<'a>> {
+        while self.tuples_left != 0 {
+            self.tuples_left -= 1;
+
+            // There are no shared tuples in `cvar`, so all peaks must be embedded.
+            let header = parse_tuple_variation_header(
+                self.coordinates,
+                &LazyArray16::<F2DOT14>::default(),
+                &mut self.main_s,
+            )?;
+
+            let data = self.serialized_s.read_bytes(usize::from(header.serialized_data_len))?;
+            if header.scalar <= 0.0 {
+                // Serialized data for headers with non-positive scalar should be skipped.
+                continue;
+            }
+
+            let mut s = Stream::new(data);
+            let point_numbers = if header.has_private_point_numbers {
+                PackedPointsIter::new(&mut s)?
+            } else {
+                self.shared_point_numbers
+            };
+
+            return Some(TupleDeltas {
+                point_numbers,
+                index: 0,
+                is_first: true,
+                deltas: PackedSingleDeltasIter::new(header.scalar, s.tail()?),
+            });
+        }
+
+        None
+    }
+}
+
+impl Iterator for CvtDeltas<'_> {
+    type Item = (u16, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(ref mut tuple) = self.tuple {
+                if let Some(item) = tuple.next(self.cvt_len) {
+                    return Some(item);
+                }
+            }
+
+            match self.next_tuple() {
+                Some(tuple) => self.tuple = Some(tuple),
+                None => {
+                    self.tuples_left = 0;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn coords(values: &[i16]) -> std::vec::Vec<NormalizedCoord> {
+        values.iter().map(|v| NormalizedCoord::from(*v)).collect()
+    }
+
+    #[test]
+    fn private_points() {
+        let data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(1), // tupleVariationCount
+            UInt16(18), // dataOffset
+            // TupleVariationHeader [0]
+            UInt16(6), // variationDataSize
+            UInt16(0xA000), // tupleIndex: EMBEDDED_PEAK_TUPLE | PRIVATE_POINT_NUMBERS
+            Int16(0x4000), // peakTuple [0]: 1.0
+            // Padding
+            UInt16(0),
+            UInt16(0),
+            // Serialized data
+            UInt8(2), // point count
+            UInt8(1), // control: 2 byte-sized points
+            UInt8(1), // point [0]: 1
+            UInt8(2), // point [1]: 1 + 2
+            UInt8(1), // control: 2 byte-sized deltas
+            Int8(-10), // delta [0]
+        ]);
+
+        // Deltas data is truncated, so only the first one should be returned.
+        let table = Table::parse(&data, 4).unwrap();
+        let deltas: std::vec::Vec<_> = table.cvt_deltas(&coords(&[0x4000])).collect();
+        assert_eq!(deltas, &[(1, -10.0)]);
+
+        // Half way to the peak.
+        let deltas: std::vec::Vec<_> = table.cvt_deltas(&coords(&[0x2000])).collect();
+        assert_eq!(deltas, &[(1, -5.0)]);
+
+        // The second index is out of bounds.
+        let table = Table::parse(&data, 3).unwrap();
+        let deltas: std::vec::Vec<_> = table.cvt_deltas(&coords(&[0x4000])).collect();
+        assert_eq!(deltas, &[(1, -10.0)]);
+
+        let table = Table::parse(&data, 1).unwrap();
+        assert_eq!(table.cvt_deltas(&coords(&[0x4000])).count(), 0);
+
+        // Default instance.
+        assert_eq!(table.cvt_deltas(&coords(&[0])).count(), 0);
+
+        // No coordinates.
+        assert_eq!(table.cvt_deltas(&[]).count(), 0);
+    }
+
+    #[test]
+    fn all_points() {
+        let data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(2), // tupleVariationCount
+            UInt16(20), // dataOffset
+            // TupleVariationHeader [0]
+            UInt16(2), // variationDataSize
+            UInt16(0x8000), // tupleIndex: EMBEDDED_PEAK_TUPLE
+            Int16(-0x4000), // peakTuple [0]: -1.0
+            // TupleVariationHeader [1]
+            UInt16(4), // variationDataSize
+            UInt16(0x8000), // tupleIndex: EMBEDDED_PEAK_TUPLE
+            Int16(0x4000), // peakTuple [0]: 1.0
+            // Serialized data [0]
+            UInt8(0x82), // control: 3 zero deltas
+            UInt8(0), // padding
+            // Serialized data [1]
+            UInt8(0x40), // control: 1 word-sized delta
+            Int16(300), // delta [0]
+            UInt8(0x81), // control: 2 zero deltas
+        ]);
+
+        let table = Table::parse(&data, 3).unwrap();
+        let deltas: std::vec::Vec<_> = table.cvt_deltas(&coords(&[0x4000])).collect();
+        assert_eq!(deltas, &[(0, 300.0), (1, 0.0), (2, 0.0)]);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let data = writer::convert(&[
+            UInt32(0x00020000), // version
+            UInt16(0), // tupleVariationCount
+            UInt16(8), // dataOffset
+        ]);
+
+        assert!(Table::parse(&data, 0).is_none());
+    }
+}
@@ -3,7 +3,7 @@
 use core::num::NonZeroU16;
 
 use crate::{Tag, NormalizedCoord};
-use crate::parser::{Stream, Offset16, Offset, LazyArray16, LazyArrayIter16, f32_bound};
+use crate::parser::{Stream, Offset16, Offset, f32_bound};
 use crate::raw::fvar as raw;
 
 
@@ -43,7 +43,7 @@ impl VariationAxis {
 
 #[derive(Clone, Copy)]
 pub(crate) struct Table<'a> {
-    axes: LazyArray16<'a, raw::VariationAxisRecord>,
+    axes: VariationAxes<'a>,
 }
 
 impl<'a> Table<'a> {
@@ -57,20 +57,53 @@ impl<'a> Table<'a> {
         let axes_array_offset: Offset16 = s.read()?;
         s.skip::<u16>(); // reserved
         let axis_count: u16 = s.read()?;
+        let axis_size: u16 = s.read()?;
 
         // 'If axisCount is zero, then the font is not functional as a variable font,
         // and must be treated as a non-variable font;
         // any variation-specific tables or data is ignored.'
         let axis_count = NonZeroU16::new(axis_count)?;
 
+        // Records can be extended by future minor versions, so we have to use `axisSize`
+        // as a stride and not the record size.
+        let axis_size = usize::from(axis_size);
+        if axis_size < raw::VariationAxisRecord::SIZE {
+            return None;
+        }
+
         let mut s = Stream::new_at(data, axes_array_offset.to_usize())?;
-        let axes = s.read_array16(axis_count.get())?;
+        let data = s.read_bytes(usize::from(axis_count.get()) * axis_size)?;
 
-        Some(Table { axes })
+        Some(Table {
+            axes: VariationAxes {
+                data,
+                axis_size,
+                index: 0,
+                total: axis_count.get(),
+            }
+        })
     }
 
     pub fn axes(&self) -> VariationAxes<'a> {
-        VariationAxes { iter: self.axes.into_iter() }
+        self.axes
+    }
+
+    /// Converts user-space coordinates into normalized ones.
+    ///
+    /// `user_coords` are in the axes order. Missing coordinates are set to axis defaults.
+    ///
+    /// Returns `None` when `normalized` length doesn't match the number of axes.
+    pub fn normalize(&self, user_coords: &[f32], normalized: &mut [NormalizedCoord]) -> Option<()> {
+        if usize::from(self.axes.total) != normalized.len() {
+            return None;
+        }
+
+        for (i, (axis, coord)) in self.axes().zip(normalized.iter_mut()).enumerate() {
+            let value = user_coords.get(i).cloned().unwrap_or(axis.def_value);
+            *coord = axis.normalized_value(value);
+        }
+
+        Some(())
     }
 
     // TODO: add axis_by_tag
@@ -81,7 +114,10 @@ impl<'a> Table<'a> {
 #[allow(missing_debug_implementations)]
 #[derive(Clone, Copy, Default)]
 pub struct VariationAxes<'a> {
-    iter: LazyArrayIter16<'a, raw::VariationAxisRecord>,
+    data: &'a [u8],
+    axis_size: usize,
+    index: u16,
+    total: u16,
 }
 
 impl<'a> Iterator for VariationAxes<'a> {
@@ -89,7 +125,16 @@ impl<'a> Iterator for VariationAxes<'a> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let record = self.iter.next()?;
+        // https://docs.microsoft.com/en-us/typography/opentype/spec/fvar#variationaxisrecord
+        const HIDDEN_AXIS_FLAG: u16 = 0x0001;
+
+        if self.index >= self.total {
+            return None;
+        }
+
+        let offset = usize::from(self.index) * self.axis_size;
+        let record: raw::VariationAxisRecord = Stream::read_at(self.data, offset)?;
+        self.index += 1;
 
         let def_value = record.def_value();
         let min_value = def_value.min(record.min_value());
@@ -101,12 +146,133 @@ impl<'a> Iterator for VariationAxes<'a> {
             def_value,
             max_value,
             name_id: record.axis_name_id(),
-            hidden: (record.flags() >> 3) & 1 == 1,
+            hidden: record.flags() & HIDDEN_AXIS_FLAG != 0,
         })
     }
 
     #[inline]
     fn count(self) -> usize {
-        self.iter.count()
+        usize::from(self.total.saturating_sub(self.index))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn axes() {
+        let data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(16), // axesArrayOffset
+            UInt16(2), // reserved
+            UInt16(2), // axisCount
+            UInt16(22), // axisSize, extended record
+            UInt16(0), // instanceCount
+            UInt16(0), // instanceSize
+            // Axis 0
+            Raw(b"wght"), // axisTag
+            Int32(100 << 16), // minValue
+            Int32(400 << 16), // defaultValue
+            Int32(900 << 16), // maxValue
+            UInt16(0), // flags
+            UInt16(256), // axisNameID
+            UInt16(0), // unknown field
+            // Axis 1
+            Raw(b"slnt"), // axisTag
+            Int32(-12 << 16), // minValue
+            Int32(0), // defaultValue
+            Int32(0), // maxValue
+            UInt16(1), // flags: HIDDEN_AXIS
+            UInt16(257), // axisNameID
+            UInt16(0), // unknown field
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.axes().count(), 2);
+
+        let mut axes = table.axes();
+        assert_eq!(axes.next(), Some(VariationAxis {
+            tag: Tag::from_bytes(b"wght"),
+            min_value: 100.0,
+            def_value: 400.0,
+            max_value: 900.0,
+            name_id: 256,
+            hidden: false,
+        }));
+        assert_eq!(axes.next(), Some(VariationAxis {
+            tag: Tag::from_bytes(b"slnt"),
+            min_value: -12.0,
+            def_value: 0.0,
+            max_value: 0.0,
+            name_id: 257,
+            hidden: true,
+        }));
+        assert_eq!(axes.next(), None);
+    }
+
+    #[test]
+    fn normalize() {
+        let data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(16), // axesArrayOffset
+            UInt16(2), // reserved
+            UInt16(2), // axisCount
+            UInt16(20), // axisSize
+            UInt16(0), // instanceCount
+            UInt16(0), // instanceSize
+            // Axis 0
+            Raw(b"wght"), // axisTag
+            Int32(100 << 16), // minValue
+            Int32(400 << 16), // defaultValue
+            Int32(900 << 16), // maxValue
+            UInt16(0), // flags
+            UInt16(256), // axisNameID
+            // Axis 1
+            Raw(b"wdth"), // axisTag
+            Int32(50 << 16), // minValue
+            Int32(100 << 16), // defaultValue
+            Int32(100 << 16), // maxValue
+            UInt16(0), // flags
+            UInt16(257), // axisNameID
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+
+        let mut coords = [NormalizedCoord::default(); 2];
+        table.normalize(&[650.0, 75.0], &mut coords).unwrap();
+        assert_eq!(coords, [NormalizedCoord(8192), NormalizedCoord(-8192)]);
+
+        // Values are clamped.
+        table.normalize(&[1000.0, 200.0], &mut coords).unwrap();
+        assert_eq!(coords, [NormalizedCoord(16384), NormalizedCoord(0)]);
+
+        table.normalize(&[100.0, 0.0], &mut coords).unwrap();
+        assert_eq!(coords, [NormalizedCoord(-16384), NormalizedCoord(-16384)]);
+
+        // Missing values are set to defaults.
+        table.normalize(&[250.0], &mut coords).unwrap();
+        assert_eq!(coords, [NormalizedCoord(-8192), NormalizedCoord(0)]);
+
+        assert!(table.normalize(&[400.0], &mut [NormalizedCoord::default()]).is_none());
+    }
+
+    #[test]
+    fn invalid_axis_size() {
+        let data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(16), // axesArrayOffset
+            UInt16(2), // reserved
+            UInt16(1), // axisCount
+            UInt16(18), // axisSize
+            UInt16(0), // instanceCount
+            UInt16(0), // instanceSize
+            Raw(&[0; 18]), // axis
+        ]);
+
+        assert!(Table::parse(&data).is_none());
     }
 }
@@ -1,5 +1,7 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/gdef
 
+use core::convert::TryFrom;
+
 use crate::GlyphId;
 use crate::parser::{Stream, Offset, Offset16, Offset32, LazyArray16};
 use crate::ggg::{Class, ClassDefinitionTable, CoverageTable};
@@ -21,6 +23,7 @@ pub struct Table<'a> {
     glyph_classes: Option<ClassDefinitionTable<'a>>,
     mark_attach_classes: Option<ClassDefinitionTable<'a>>,
     mark_glyph_coverage_offsets: Option<(&'a [u8], LazyArray16<'a, Offset32>)>,
+    lig_caret_list: Option<&'a [u8]>,
 }
 
 impl<'a> Table<'a> {
@@ -33,7 +36,7 @@ impl<'a> Table<'a> {
 
         let glyph_class_def_offset: Option<Offset16> = s.read()?;
         s.skip::<Offset16>(); // attachListOffset
-        s.skip::<Offset16>(); // ligCaretListOffset
+        let lig_caret_list_offset: Option<Offset16> = s.read()?;
         let mark_attach_class_def_offset: Option<Offset16> = s.read()?;
 
         let mut mark_glyph_sets_def_offset: Option<Offset16> = None;
@@ -52,6 +55,10 @@ impl<'a> Table<'a> {
             }
         }
 
+        if let Some(offset) = lig_caret_list_offset {
+            table.lig_caret_list = data.get(offset.to_usize()..);
+        }
+
         if let Some(offset) = mark_attach_class_def_offset {
             if let Some(subdata) = data.get(offset.to_usize()..) {
                 table.mark_attach_classes = Some(ClassDefinitionTable::new(subdata));
@@ -98,12 +105,80 @@ impl<'a> Table<'a> {
             .unwrap_or(Class(0))
     }
 
+    pub fn ligature_carets(&self, glyph_id: GlyphId) -> LigatureCarets<'a> {
+        self.lig_caret_list
+            .and_then(|data| ligature_carets_impl(data, glyph_id))
+            .unwrap_or_default()
+    }
+
     #[inline]
     pub fn is_mark_glyph(&self, glyph_id: GlyphId, set_index: Option<u16>) -> bool {
         is_mark_glyph_impl(self, glyph_id, set_index).is_some()
     }
 }
 
+// https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#ligature-caret-list-table
+fn ligature_carets_impl<'a>(data: &'a [u8], glyph_id: GlyphId) -> Option<LigatureCarets<'a>> {
+    let mut s = Stream::new(data);
+    let coverage_offset: Offset16 = s.read()?;
+    let count: u16 = s.read()?;
+    let offsets = s.read_array16::<Offset16>(count)?;
+
+    let coverage = CoverageTable::new(data.get(coverage_offset.to_usize()..)?);
+    let index = coverage.coverage_index(glyph_id)?;
+    let lig_glyph = data.get(offsets.get(index)?.to_usize()..)?;
+
+    let mut s = Stream::new(lig_glyph);
+    let count: u16 = s.read()?;
+    let offsets = s.read_array16(count)?;
+    Some(LigatureCarets { data: lig_glyph, offsets, index: 0 })
+}
+
+
+/// An iterator over ligature caret positions.
+///
+/// Yields a caret X coordinate for
+/// [CaretValue](https://docs.microsoft.com/en-us/typography/opentype/spec/gdef#caret-value-tables)
+/// formats 1 and 3 (the device table adjustment is ignored)
+/// and a contour point index for format 2.
+///
+/// Malformed caret values are skipped.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct LigatureCarets<'a> {
+    data: &'a [u8],
+    offsets: LazyArray16<'a, Offset16>,
+    index: u16,
+}
+
+impl<'a> Iterator for LigatureCarets<'a> {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.offsets.len() {
+            let offset = self.offsets.get(self.index)?;
+            self.index += 1;
+            let data = self.data.get(offset.to_usize()..);
+            if let Some(value) = data.and_then(parse_caret_value) {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+}
+
+fn parse_caret_value(data: &[u8]) -> Option<i16> {
+    let mut s = Stream::new(data);
+    let format: u16 = s.read()?;
+    match format {
+        1 | 3 => s.read::<i16>(), // coordinate
+        2 => s.read::<u16>().and_then(|n| i16::try_from(n).ok()), // caretValuePointIndex
+        _ => None,
+    }
+}
+
+
 #[inline(never)]
 fn is_mark_glyph_impl(
     table: &Table,
@@ -130,3 +205,88 @@ fn is_mark_glyph_impl(
 
     None
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn ligature_carets() {
+        let data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(0), // glyphClassDefOffset
+            UInt16(0), // attachListOffset
+            UInt16(12), // ligCaretListOffset
+            UInt16(0), // markAttachClassDefOffset
+            // LigCaretList
+            UInt16(6), // coverageOffset
+            UInt16(1), // ligGlyphCount
+            UInt16(12), // ligGlyphOffsets [0]
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(1), // glyphCount
+            UInt16(5), // glyphArray [0]
+            // LigGlyph
+            UInt16(3), // caretCount
+            UInt16(8), // caretValueOffsets [0]
+            UInt16(12), // caretValueOffsets [1]
+            UInt16(16), // caretValueOffsets [2]
+            // CaretValue format 1
+            UInt16(1), // caretValueFormat
+            Int16(300), // coordinate
+            // CaretValue format 2
+            UInt16(2), // caretValueFormat
+            UInt16(7), // caretValuePointIndex
+            // CaretValue format 3
+            UInt16(3), // caretValueFormat
+            Int16(600), // coordinate
+            UInt16(0), // deviceOffset
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        let mut carets = table.ligature_carets(GlyphId(5));
+        assert_eq!(carets.next(), Some(300));
+        assert_eq!(carets.next(), Some(7));
+        assert_eq!(carets.next(), Some(600));
+        assert_eq!(carets.next(), None);
+
+        assert_eq!(table.ligature_carets(GlyphId(4)).count(), 0);
+    }
+
+    #[test]
+    fn ligature_carets_out_of_bounds() {
+        let data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(0), // glyphClassDefOffset
+            UInt16(0), // attachListOffset
+            UInt16(12), // ligCaretListOffset
+            UInt16(0), // markAttachClassDefOffset
+            // LigCaretList
+            UInt16(6), // coverageOffset
+            UInt16(1), // ligGlyphCount
+            UInt16(12), // ligGlyphOffsets [0]
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(1), // glyphCount
+            UInt16(5), // glyphArray [0]
+            // LigGlyph
+            UInt16(3), // caretCount
+            UInt16(8), // caretValueOffsets [0]
+            UInt16(1000), // caretValueOffsets [1]
+            UInt16(12), // caretValueOffsets [2]
+            // CaretValue format 1
+            UInt16(1), // caretValueFormat
+            Int16(300), // coordinate
+            // CaretValue format 1
+            UInt16(1), // caretValueFormat
+            Int16(600), // coordinate
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        let carets: std::vec::Vec<_> = table.ligature_carets(GlyphId(5)).collect();
+        assert_eq!(carets, &[300, 600]);
+    }
+}
@@ -4,7 +4,7 @@
 
 use core::num::NonZeroU16;
 
-use crate::parser::{Stream, F2DOT14, LazyArray16, NumFrom, f32_bound};
+use crate::parser::{Stream, LazyArray16, NumFrom, f32_bound};
 use crate::{loca, GlyphId, OutlineBuilder, Rect, BBox};
 
 pub(crate) struct Builder<'a> {
@@ -236,6 +236,7 @@ impl<'a> Iterator for CompositeGlyphIter<'a> {
 
         let mut ts = Transform::default();
 
+        // Arguments are always present, even when they are not offsets.
         if flags.args_are_xy_values() {
             if flags.arg_1_and_2_are_words() {
                 ts.e = f32::from(self.stream.read::<i16>()?);
@@ -244,23 +245,40 @@ impl<'a> Iterator for CompositeGlyphIter<'a> {
                 ts.e = f32::from(self.stream.read::<i8>()?);
                 ts.f = f32::from(self.stream.read::<i8>()?);
             }
+        } else {
+            // Point numbers used for points matching, which is not supported.
+            if flags.arg_1_and_2_are_words() {
+                self.stream.skip::<u16>();
+                self.stream.skip::<u16>();
+            } else {
+                self.stream.skip::<u8>();
+                self.stream.skip::<u8>();
+            }
         }
 
         if flags.we_have_a_two_by_two() {
-            ts.a = self.stream.read::<F2DOT14>()?.to_f32();
-            ts.b = self.stream.read::<F2DOT14>()?.to_f32();
-            ts.c = self.stream.read::<F2DOT14>()?.to_f32();
-            ts.d = self.stream.read::<F2DOT14>()?.to_f32();
+            ts.a = self.stream.read_f2dot14()?;
+            ts.b = self.stream.read_f2dot14()?;
+            ts.c = self.stream.read_f2dot14()?;
+            ts.d = self.stream.read_f2dot14()?;
         } else if flags.we_have_an_x_and_y_scale() {
-            ts.a = self.stream.read::<F2DOT14>()?.to_f32();
-            ts.d = self.stream.read::<F2DOT14>()?.to_f32();
+            ts.a = self.stream.read_f2dot14()?;
+            ts.d = self.stream.read_f2dot14()?;
         } else if flags.we_have_a_scale() {
             // 'If the bit WE_HAVE_A_SCALE is set, the scale value is read in 2.14 format.
             // The value can be between -2 to almost +2.'
-            ts.a = f32_bound(-2.0, self.stream.read::<F2DOT14>()?.to_f32(), 2.0);
+            ts.a = f32_bound(-2.0, self.stream.read_f2dot14()?, 2.0);
             ts.d = ts.a;
         }
 
+        // By default, an offset is applied after the scale/transform, like in most renderers.
+        // Otherwise, an offset has to be transformed as well.
+        if flags.scaled_component_offset() && !flags.unscaled_component_offset() {
+            let (e, f) = (ts.e, ts.f);
+            ts.e = ts.a * e + ts.c * f;
+            ts.f = ts.b * e + ts.d * f;
+        }
+
         if !flags.more_components() {
             // Finish the iterator even if stream still has some data.
             self.stream.jump_to_end();
@@ -279,17 +297,39 @@ impl<'a> Iterator for CompositeGlyphIter<'a> {
 // makes the code ~10% slower. At least on my machine.
 // I guess it's due to the fact that with i16 the struct
 // fits into the machine word.
-#[derive(Clone, Copy, Debug)]
+/// A `glyf` outline point.
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct GlyphPoint {
+    /// X coordinate.
     pub x: i16,
+    /// Y coordinate.
     pub y: i16,
     /// Indicates that a point is a point on curve
     /// and not a control point.
     pub on_curve_point: bool,
+    /// Indicates that a point is the last point of a contour.
     pub last_point: bool,
 }
 
 
+/// An iterator over simple glyph points.
+///
+/// Points are returned as stored in the `glyf` table,
+/// without any transformations or variations applied.
+#[derive(Clone, Default)]
+#[allow(missing_debug_implementations)]
+pub struct GlyphPoints<'a>(GlyphPointsIter<'a>);
+
+impl<'a> Iterator for GlyphPoints<'a> {
+    type Item = GlyphPoint;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+
 #[derive(Clone, Default)]
 pub struct GlyphPointsIter<'a> {
     endpoints: EndpointsIter<'a>,
@@ -492,12 +532,19 @@ impl CompositeGlyphFlags {
     #[inline] pub fn more_components(self) -> bool { self.0 & 0x0020 != 0 }
     #[inline] pub fn we_have_an_x_and_y_scale(self) -> bool { self.0 & 0x0040 != 0 }
     #[inline] pub fn we_have_a_two_by_two(self) -> bool { self.0 & 0x0080 != 0 }
+    #[inline] pub fn scaled_component_offset(self) -> bool { self.0 & 0x0800 != 0 }
+    #[inline] pub fn unscaled_component_offset(self) -> bool { self.0 & 0x1000 != 0 }
 }
 
 
 // It's not defined in the spec, so we are using our own value.
 pub const MAX_COMPONENTS: u8 = 32;
 
+// The maximum number of components that will be outlined for a single glyph.
+// Limits the depth alone is not enough, since each nesting level can multiply
+// the number of components exponentially.
+pub const MAX_TOTAL_COMPONENTS: u16 = 4096;
+
 #[inline]
 pub(crate) fn outline(
     loca_table: loca::Table,
@@ -508,7 +555,8 @@ pub(crate) fn outline(
     let mut b = Builder::new(Transform::default(), None, builder);
     let range = loca_table.glyph_range(glyph_id)?;
     let glyph_data = glyf_table.get(range)?;
-    outline_impl(loca_table, glyf_table, glyph_data, 0, &mut b)
+    let mut components_left = MAX_TOTAL_COMPONENTS;
+    outline_impl(loca_table, glyf_table, glyph_data, 0, &mut components_left, &mut b)
 }
 
 #[inline]
@@ -530,14 +578,39 @@ pub(crate) fn glyph_bbox(
     })
 }
 
+#[inline]
+pub(crate) fn glyph_points<'a>(
+    loca_table: loca::Table,
+    glyf_table: &'a [u8],
+    glyph_id: GlyphId,
+) -> Option<GlyphPoints<'a>> {
+    let range = loca_table.glyph_range(glyph_id)?;
+    let glyph_data = glyf_table.get(range)?;
+    let mut s = Stream::new(glyph_data);
+    let number_of_contours: i16 = s.read()?;
+    if number_of_contours <= 0 {
+        // A composite or an empty glyph.
+        return None;
+    }
+
+    s.advance(8); // bbox
+
+    // u16 casting is safe, since we already checked that the value is positive.
+    let number_of_contours = NonZeroU16::new(number_of_contours as u16)?;
+    parse_simple_outline(s.tail()?, number_of_contours).map(GlyphPoints)
+}
+
 #[inline]
 fn outline_impl(
     loca_table: loca::Table,
     glyf_table: &[u8],
     data: &[u8],
     depth: u8,
+    components_left: &mut u16,
     builder: &mut Builder,
 ) -> Option<Rect> {
+    // This also prevents infinite recursion in case of
+    // a composite glyph that references itself.
     if depth >= MAX_COMPONENTS {
         return None;
     }
@@ -564,11 +637,14 @@ fn outline_impl(
     } else if number_of_contours < 0 {
         // Composite glyph.
         for comp in CompositeGlyphIter::new(s.tail()?) {
+            *components_left = components_left.checked_sub(1)?;
+
             if let Some(range) = loca_table.glyph_range(comp.glyph_id) {
                 if let Some(glyph_data) = glyf_table.get(range) {
                     let transform = Transform::combine(builder.transform, comp.transform);
                     let mut b = Builder::new(transform, None, builder.builder);
-                    outline_impl(loca_table, glyf_table, glyph_data, depth + 1, &mut b)?;
+                    outline_impl(loca_table, glyf_table, glyph_data, depth + 1,
+                                 components_left, &mut b)?;
                 }
             }
         }
@@ -664,3 +740,275 @@ fn resolve_coords_len(
 
     Some((x_coords_len, y_coords_len))
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use crate::{gvar, IndexToLocationFormat, NormalizedCoord};
+    use writer::TtfType::*;
+    use std::vec::Vec;
+
+    #[derive(Default)]
+    struct Builder {
+        contours: usize,
+        points: Vec<(f32, f32)>,
+    }
+
+    impl OutlineBuilder for Builder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            self.contours += 1;
+            self.points.push((x, y));
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            self.points.push((x, y));
+        }
+
+        fn quad_to(&mut self, _: f32, _: f32, _: f32, _: f32) {}
+        fn curve_to(&mut self, _: f32, _: f32, _: f32, _: f32, _: f32, _: f32) {}
+        fn close(&mut self) {}
+    }
+
+    fn simple_glyph() -> Vec<u8> {
+        writer::convert(&[
+            Int16(1), // numberOfContours
+            Int16(0), Int16(0), Int16(10), Int16(10), // bbox
+            UInt16(2), // endPtsOfContours[0]
+            UInt16(0), // instructionLength
+            UInt8(0x01), UInt8(0x01), UInt8(0x01), // flags: ON_CURVE_POINT
+            Int16(0), Int16(10), Int16(-10), // x coordinates
+            Int16(0), Int16(0), Int16(10), // y coordinates
+        ])
+    }
+
+    fn composite_glyph(components: &[u16]) -> Vec<u8> {
+        let mut data = writer::convert(&[
+            Int16(-1), // numberOfContours
+            Int16(0), Int16(0), Int16(10), Int16(10), // bbox
+        ]);
+
+        for (i, id) in components.iter().enumerate() {
+            let mut flags = 0x0001 | 0x0002; // ARG_1_AND_2_ARE_WORDS | ARGS_ARE_XY_VALUES
+            if i + 1 != components.len() {
+                flags |= 0x0020; // MORE_COMPONENTS
+            }
+
+            data.extend_from_slice(&writer::convert(&[
+                UInt16(flags), UInt16(*id), Int16(0), Int16(0),
+            ]));
+        }
+
+        data
+    }
+
+    // Returns `loca` and `glyf` tables data.
+    fn tables_data(glyphs: &[Vec<u8>]) -> (Vec<u8>, Vec<u8>) {
+        let mut loca_data = writer::convert(&[UInt32(0)]);
+        let mut glyf_data = Vec::new();
+        for glyph in glyphs {
+            glyf_data.extend_from_slice(glyph);
+            loca_data.extend_from_slice(&writer::convert(&[UInt32(glyf_data.len() as u32)]));
+        }
+
+        (loca_data, glyf_data)
+    }
+
+    fn parse_loca(loca_data: &[u8], number_of_glyphs: usize) -> loca::Table<'_> {
+        let number_of_glyphs = NonZeroU16::new(number_of_glyphs as u16).unwrap();
+        loca::Table::parse(loca_data, number_of_glyphs, IndexToLocationFormat::Long).unwrap()
+    }
+
+    fn outline_glyph(glyphs: &[Vec<u8>], glyph_id: GlyphId) -> (Option<Rect>, Builder) {
+        let (loca_data, glyf_data) = tables_data(glyphs);
+        let loca_table = parse_loca(&loca_data, glyphs.len());
+
+        let mut builder = Builder::default();
+        let rect = outline(loca_table, &glyf_data, glyph_id, &mut builder);
+        (rect, builder)
+    }
+
+    // Outlines glyphs using an empty `gvar` table.
+    fn outline_var_glyph(glyphs: &[Vec<u8>], glyph_id: GlyphId) -> (Option<Rect>, Builder) {
+        let (loca_data, glyf_data) = tables_data(glyphs);
+        let loca_table = parse_loca(&loca_data, glyphs.len());
+
+        let offsets_len = (glyphs.len() + 1) * 2;
+        let mut gvar_data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(1), // axisCount
+            UInt16(0), // sharedTupleCount
+            UInt32(20), // sharedTuplesOffset
+            UInt16(glyphs.len() as u16), // glyphCount
+            UInt16(0), // flags
+            UInt32(20 + offsets_len as u32), // glyphVariationDataArrayOffset
+        ]);
+        gvar_data.resize(gvar_data.len() + offsets_len, 0);
+        let gvar_table = gvar::Table::parse(&gvar_data).unwrap();
+
+        let coordinates = [NormalizedCoord::from(0.5)];
+        let mut builder = Builder::default();
+        let rect = gvar::outline(
+            loca_table, &glyf_data, &gvar_table, &coordinates, glyph_id, &mut builder,
+        );
+        (rect, builder)
+    }
+
+    #[test]
+    fn composite() {
+        let glyphs = [simple_glyph(), composite_glyph(&[0, 0])];
+        let (rect, builder) = outline_glyph(&glyphs, GlyphId(1));
+        assert!(rect.is_some());
+        assert_eq!(builder.contours, 2);
+    }
+
+    fn transformed_composite_glyph(flags: u16, transform: &[writer::TtfType]) -> Vec<u8> {
+        let mut data = writer::convert(&[
+            Int16(-1), // numberOfContours
+            Int16(0), Int16(0), Int16(10), Int16(10), // bbox
+            UInt16(flags | 0x0001 | 0x0002), // ARG_1_AND_2_ARE_WORDS | ARGS_ARE_XY_VALUES
+            UInt16(0), // glyphIndex
+            Int16(100), // argument1
+            Int16(50), // argument2
+        ]);
+        data.extend_from_slice(&writer::convert(transform));
+        data
+    }
+
+    #[test]
+    fn composite_two_by_two() {
+        let glyphs = [
+            simple_glyph(),
+            transformed_composite_glyph(0x0080, &[ // WE_HAVE_A_TWO_BY_TWO
+                Int16(0), Int16(16384), // xscale: 0, scale01: 1
+                Int16(-16384), Int16(0), // scale10: -1, yscale: 0
+            ]),
+        ];
+
+        let (_, builder) = outline_glyph(&glyphs, GlyphId(1));
+        // A 90 degrees rotation followed by a translation.
+        assert_eq!(builder.points, &[(100.0, 50.0), (100.0, 60.0), (90.0, 50.0), (100.0, 50.0)]);
+    }
+
+    #[test]
+    fn composite_x_and_y_scale() {
+        let glyphs = [
+            simple_glyph(),
+            transformed_composite_glyph(0x0040, &[ // WE_HAVE_AN_X_AND_Y_SCALE
+                Int16(32767), Int16(8192), // xscale: ~2, yscale: 0.5
+            ]),
+        ];
+
+        let (_, builder) = outline_glyph(&glyphs, GlyphId(1));
+        let x = (2.0 - 1.0 / 16384.0) * 10.0 + 100.0;
+        assert_eq!(builder.points, &[(100.0, 50.0), (x, 50.0), (100.0, 55.0), (100.0, 50.0)]);
+    }
+
+    #[test]
+    fn composite_scaled_offset() {
+        let glyphs = [
+            simple_glyph(),
+            transformed_composite_glyph(0x0008 | 0x0800, &[ // WE_HAVE_A_SCALE | SCALED_OFFSET
+                Int16(8192), // scale: 0.5
+            ]),
+        ];
+
+        let (_, builder) = outline_glyph(&glyphs, GlyphId(1));
+        assert_eq!(builder.points, &[(50.0, 25.0), (55.0, 25.0), (50.0, 30.0), (50.0, 25.0)]);
+    }
+
+    #[test]
+    fn composite_point_matching() {
+        // Points matching is not supported, but arguments still must be skipped.
+        let mut data = writer::convert(&[
+            Int16(-1), // numberOfContours
+            Int16(0), Int16(0), Int16(10), Int16(10), // bbox
+            UInt16(0x0008 | 0x0020), // WE_HAVE_A_SCALE | MORE_COMPONENTS
+            UInt16(0), // glyphIndex
+            UInt8(0), // argument1
+            UInt8(0), // argument2
+            Int16(8192), // scale: 0.5
+        ]);
+        data.extend_from_slice(&writer::convert(&[
+            UInt16(0x0001), // ARG_1_AND_2_ARE_WORDS
+            UInt16(0), // glyphIndex
+            UInt16(0), // argument1
+            UInt16(0), // argument2
+        ]));
+
+        let glyphs = [simple_glyph(), data];
+        let (rect, builder) = outline_glyph(&glyphs, GlyphId(1));
+        assert!(rect.is_some());
+        assert_eq!(builder.contours, 2);
+        assert_eq!(&builder.points[..3], &[(0.0, 0.0), (5.0, 0.0), (0.0, 5.0)]);
+    }
+
+    #[test]
+    fn self_referencing_composite() {
+        let glyphs = [simple_glyph(), composite_glyph(&[0, 1])];
+        assert_eq!(outline_glyph(&glyphs, GlyphId(1)).0, None);
+        assert_eq!(outline_var_glyph(&glyphs, GlyphId(1)).0, None);
+
+        // Must not be affected by the limits.
+        let glyphs = [simple_glyph(), composite_glyph(&[0, 0])];
+        let (rect, builder) = outline_var_glyph(&glyphs, GlyphId(1));
+        assert!(rect.is_some());
+        assert_eq!(builder.contours, 2);
+    }
+
+    #[test]
+    fn cyclic_composite() {
+        let glyphs = [simple_glyph(), composite_glyph(&[2]), composite_glyph(&[0, 1])];
+        assert_eq!(outline_glyph(&glyphs, GlyphId(1)).0, None);
+        assert_eq!(outline_glyph(&glyphs, GlyphId(2)).0, None);
+    }
+
+    #[test]
+    fn exponential_composite() {
+        // Each glyph references the next one twice, which results in 2^30 components.
+        let mut glyphs = Vec::new();
+        for i in 0..30 {
+            glyphs.push(composite_glyph(&[i + 1, i + 1]));
+        }
+        glyphs.push(simple_glyph());
+
+        assert_eq!(outline_glyph(&glyphs, GlyphId(0)).0, None);
+        assert_eq!(outline_var_glyph(&glyphs, GlyphId(0)).0, None);
+
+        // But a reasonable amount of nesting is fine.
+        assert!(outline_glyph(&glyphs, GlyphId(20)).0.is_some());
+        assert!(outline_var_glyph(&glyphs, GlyphId(20)).0.is_some());
+    }
+
+    #[test]
+    fn points() {
+        let mut two_contours = writer::convert(&[
+            Int16(2), // numberOfContours
+            Int16(0), Int16(0), Int16(10), Int16(10), // bbox
+            UInt16(1), // endPtsOfContours[0]
+            UInt16(2), // endPtsOfContours[1]
+            UInt16(0), // instructionLength
+            UInt8(0x01), UInt8(0x00), UInt8(0x01), // flags
+        ]);
+        two_contours.extend_from_slice(&writer::convert(&[
+            Int16(0), Int16(10), Int16(-5), // x coordinates
+            Int16(0), Int16(5), Int16(5), // y coordinates
+        ]));
+
+        let glyphs = [two_contours, composite_glyph(&[0]), Vec::new()];
+        let (loca_data, glyf_data) = tables_data(&glyphs);
+        let loca_table = parse_loca(&loca_data, glyphs.len());
+
+        let points: Vec<_> = glyph_points(loca_table, &glyf_data, GlyphId(0)).unwrap().collect();
+        assert_eq!(points, &[
+            GlyphPoint { x: 0, y: 0, on_curve_point: true, last_point: false },
+            GlyphPoint { x: 10, y: 5, on_curve_point: false, last_point: true },
+            GlyphPoint { x: 5, y: 10, on_curve_point: true, last_point: true },
+        ]);
+
+        assert!(glyph_points(loca_table, &glyf_data, GlyphId(1)).is_none());
+        assert!(glyph_points(loca_table, &glyf_data, GlyphId(2)).is_none());
+        assert!(glyph_points(loca_table, &glyf_data, GlyphId(3)).is_none());
+    }
+}
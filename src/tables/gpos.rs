@@ -0,0 +1,774 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/gpos
+
+use core::convert::TryFrom;
+
+use crate::GlyphId;
+use crate::parser::{Stream, Offset, Offset16};
+use crate::ggg::*;
+
+
+mod lookup_type {
+    pub const SINGLE_ADJUSTMENT: u16 = 1;
+    pub const PAIR_ADJUSTMENT: u16 = 2;
+    pub const MARK_TO_BASE_ATTACHMENT: u16 = 4;
+    pub const EXTENSION: u16 = 9;
+}
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#value-record
+mod value_format {
+    pub const X_PLACEMENT: u16 = 0x0001;
+    pub const Y_PLACEMENT: u16 = 0x0002;
+    pub const X_ADVANCE: u16 = 0x0004;
+    pub const Y_ADVANCE: u16 = 0x0008;
+    pub const X_PLACEMENT_DEVICE: u16 = 0x0010;
+    pub const Y_PLACEMENT_DEVICE: u16 = 0x0020;
+    pub const X_ADVANCE_DEVICE: u16 = 0x0040;
+    pub const Y_ADVANCE_DEVICE: u16 = 0x0080;
+}
+
+
+/// A [Value Record](https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#value-record).
+///
+/// Fields not present in the record's value format are set to `None`.
+/// Device tables are not supported.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct ValueRecord {
+    /// Horizontal adjustment for placement.
+    pub x_placement: Option<i16>,
+    /// Vertical adjustment for placement.
+    pub y_placement: Option<i16>,
+    /// Horizontal adjustment for advance.
+    pub x_advance: Option<i16>,
+    /// Vertical adjustment for advance.
+    pub y_advance: Option<i16>,
+}
+
+/// A [Glyph Positioning Table](https://docs.microsoft.com/en-us/typography/opentype/spec/gpos).
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct PositioningTable<'a> {
+    table: LayoutTable<'a>,
+}
+
+impl<'a> GlyphPosSubTable<'a> for PositioningTable<'a> {
+    #[inline]
+    fn scripts(&self) -> Scripts<'a> {
+        self.table.scripts
+    }
+
+    #[inline]
+    fn features(&self) -> Features<'a> {
+        self.table.features
+    }
+
+    #[inline]
+    fn lookups(&self) -> Lookups<'a> {
+        self.table.lookups
+    }
+}
+
+impl<'a> PositioningTable<'a> {
+    pub(crate) fn parse(data: &'a [u8]) -> Option<Self> {
+        LayoutTable::parse(data, lookup_type::EXTENSION).map(|table| PositioningTable { table })
+    }
+
+    /// Returns a glyph's adjustment from a
+    /// [single adjustment](https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-1-single-adjustment-positioning-subtable)
+    /// lookup.
+    ///
+    /// Returns `None` when lookup has a different type or glyph is not covered.
+    pub fn single_position(&self, lookup: Lookup<'a>, glyph_id: GlyphId) -> Option<ValueRecord> {
+        lookup.subtables_of_type(lookup_type::SINGLE_ADJUSTMENT)
+            .find_map(|data| single_adjustment(data, glyph_id))
+    }
+
+    /// Returns a mark offset relative to a base glyph from a
+    /// [mark-to-base attachment](https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-4-mark-to-base-attachment-positioning-subtable)
+    /// lookup.
+    ///
+    /// The offset is the difference between the base anchor and the mark anchor.
+    /// Only anchor coordinates are used, so anchor formats 2 and 3
+    /// are treated as format 1.
+    ///
+    /// Returns `None` when lookup has a different type, glyphs are not covered
+    /// or the base has no anchor for the mark's class.
+    pub fn mark_to_base(
+        &self,
+        lookup: Lookup<'a>,
+        mark: GlyphId,
+        base: GlyphId,
+    ) -> Option<(i16, i16)> {
+        lookup.subtables_of_type(lookup_type::MARK_TO_BASE_ATTACHMENT)
+            .find_map(|data| mark_to_base_attachment(data, mark, base))
+    }
+
+    /// Returns the horizontal advance adjustment of the `left` glyph
+    /// from the first PairPos subtable that covers the pair.
+    pub(crate) fn glyphs_kerning(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        for lookup in self.table.lookups {
+            for data in lookup.subtables_of_type(lookup_type::PAIR_ADJUSTMENT) {
+                let value = pair_adjustment(data, left, right).and_then(|(v, _)| v.x_advance);
+                if value.is_some() {
+                    return value;
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-1-single-adjustment-positioning-subtable
+fn single_adjustment(data: &[u8], glyph_id: GlyphId) -> Option<ValueRecord> {
+    let mut s = Stream::new(data);
+    let format: u16 = s.read()?;
+    let coverage_offset: Offset16 = s.read()?;
+    let value_format: u16 = s.read()?;
+
+    let coverage = CoverageTable::new(data.get(coverage_offset.to_usize()..)?);
+    let coverage_index = coverage.coverage_index(glyph_id)?;
+
+    match format {
+        1 => read_value_record(&mut s, value_format),
+        2 => {
+            let count: u16 = s.read()?;
+            if coverage_index >= count {
+                return None;
+            }
+
+            let offset = usize::from(coverage_index) * value_record_size(value_format);
+            s.advance_checked(offset)?;
+            read_value_record(&mut s, value_format)
+        }
+        _ => None,
+    }
+}
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-2-pair-adjustment-positioning-subtable
+fn pair_adjustment(
+    data: &[u8],
+    left: GlyphId,
+    right: GlyphId,
+) -> Option<(ValueRecord, ValueRecord)> {
+    let mut s = Stream::new(data);
+    let format: u16 = s.read()?;
+    let coverage_offset: Offset16 = s.read()?;
+    let value_format1: u16 = s.read()?;
+    let value_format2: u16 = s.read()?;
+
+    let coverage = CoverageTable::new(data.get(coverage_offset.to_usize()..)?);
+    let coverage_index = coverage.coverage_index(left)?;
+
+    let value1_size = value_record_size(value_format1);
+    let value2_size = value_record_size(value_format2);
+
+    match format {
+        1 => {
+            let count: u16 = s.read()?;
+            let offsets = s.read_array16::<Offset16>(count)?;
+            let pair_set = data.get(offsets.get(coverage_index)?.to_usize()..)?;
+
+            let mut s = Stream::new(pair_set);
+            let count: u16 = s.read()?;
+            let record_size = 2 + value1_size + value2_size;
+            let records = s.read_bytes(usize::from(count) * record_size)?;
+
+            // PairValueRecords are ordered by the second glyph ID.
+            let mut size = usize::from(count);
+            let mut base = 0;
+            while size > 1 {
+                let half = size / 2;
+                let mid = base + half;
+                let glyph: GlyphId = Stream::read_at(records, mid * record_size)?;
+                if glyph <= right {
+                    base = mid;
+                }
+
+                size -= half;
+            }
+
+            if size == 0 {
+                return None;
+            }
+
+            let offset = base * record_size;
+            let glyph: GlyphId = Stream::read_at(records, offset)?;
+            if glyph != right {
+                return None;
+            }
+
+            let mut s = Stream::new_at(records, offset + 2)?;
+            let value1 = read_value_record(&mut s, value_format1)?;
+            let value2 = read_value_record(&mut s, value_format2)?;
+            Some((value1, value2))
+        }
+        2 => {
+            let class_def1_offset: Offset16 = s.read()?;
+            let class_def2_offset: Offset16 = s.read()?;
+            let class1_count: u16 = s.read()?;
+            let class2_count: u16 = s.read()?;
+
+            let class1 = ClassDefinitionTable::new(data.get(class_def1_offset.to_usize()..)?)
+                .get(left).0;
+            let class2 = ClassDefinitionTable::new(data.get(class_def2_offset.to_usize()..)?)
+                .get(right).0;
+            if class1 >= class1_count || class2 >= class2_count {
+                return None;
+            }
+
+            let record_size = value1_size + value2_size;
+            let index = usize::from(class1) * usize::from(class2_count) + usize::from(class2);
+            s.advance_checked(index.checked_mul(record_size)?)?;
+            let value1 = read_value_record(&mut s, value_format1)?;
+            let value2 = read_value_record(&mut s, value_format2)?;
+            Some((value1, value2))
+        }
+        _ => None,
+    }
+}
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-4-mark-to-base-attachment-positioning-subtable
+fn mark_to_base_attachment(data: &[u8], mark: GlyphId, base: GlyphId) -> Option<(i16, i16)> {
+    let mut s = Stream::new(data);
+    let format: u16 = s.read()?;
+    if format != 1 {
+        return None;
+    }
+
+    let mark_coverage_offset: Offset16 = s.read()?;
+    let base_coverage_offset: Offset16 = s.read()?;
+    let mark_class_count: u16 = s.read()?;
+    let mark_array_offset: Offset16 = s.read()?;
+    let base_array_offset: Offset16 = s.read()?;
+
+    let mark_coverage = CoverageTable::new(data.get(mark_coverage_offset.to_usize()..)?);
+    let mark_index = mark_coverage.coverage_index(mark)?;
+    let base_coverage = CoverageTable::new(data.get(base_coverage_offset.to_usize()..)?);
+    let base_index = base_coverage.coverage_index(base)?;
+
+    // MarkArray
+    let mark_array = data.get(mark_array_offset.to_usize()..)?;
+    let mut s = Stream::new(mark_array);
+    let mark_count: u16 = s.read()?;
+    if mark_index >= mark_count {
+        return None;
+    }
+
+    s.advance_checked(usize::from(mark_index) * 4)?; // MarkRecord size
+    let mark_class: u16 = s.read()?;
+    let mark_anchor_offset: Offset16 = s.read()?;
+    if mark_class >= mark_class_count {
+        return None;
+    }
+
+    let mark_anchor = parse_anchor(mark_array.get(mark_anchor_offset.to_usize()..)?)?;
+
+    // BaseArray
+    let base_array = data.get(base_array_offset.to_usize()..)?;
+    let mut s = Stream::new(base_array);
+    let base_count: u16 = s.read()?;
+    if base_index >= base_count {
+        return None;
+    }
+
+    let index = usize::from(base_index) * usize::from(mark_class_count) + usize::from(mark_class);
+    s.advance_checked(index * 2)?;
+    // A NULL offset means that the base has no anchor for this class.
+    let base_anchor_offset: Offset16 = s.read::<Option<Offset16>>()??;
+    let base_anchor = parse_anchor(base_array.get(base_anchor_offset.to_usize()..)?)?;
+
+    let dx = i32::from(base_anchor.0) - i32::from(mark_anchor.0);
+    let dy = i32::from(base_anchor.1) - i32::from(mark_anchor.1);
+    Some((i16::try_from(dx).ok()?, i16::try_from(dy).ok()?))
+}
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#anchor-tables
+fn parse_anchor(data: &[u8]) -> Option<(i16, i16)> {
+    let mut s = Stream::new(data);
+    let format: u16 = s.read()?;
+    // All formats start with the same coordinates.
+    // Contour points and device tables from formats 2 and 3 are ignored.
+    if !(1..=3).contains(&format) {
+        return None;
+    }
+
+    Some((s.read()?, s.read()?))
+}
+
+#[inline]
+fn value_record_size(format: u16) -> usize {
+    // Each of the 8 defined flags adds a 16-bit field.
+    (format & 0x00FF).count_ones() as usize * 2
+}
+
+/// Reads a `ValueRecord` with a specified value format.
+///
+/// All fields present in the format are consumed, including device table offsets,
+/// so the stream is positioned right after the record.
+pub(crate) fn read_value_record(s: &mut Stream, format: u16) -> Option<ValueRecord> {
+    use value_format::*;
+
+    let mut record = ValueRecord::default();
+
+    if format & X_PLACEMENT != 0 {
+        record.x_placement = Some(s.read()?);
+    }
+
+    if format & Y_PLACEMENT != 0 {
+        record.y_placement = Some(s.read()?);
+    }
+
+    if format & X_ADVANCE != 0 {
+        record.x_advance = Some(s.read()?);
+    }
+
+    if format & Y_ADVANCE != 0 {
+        record.y_advance = Some(s.read()?);
+    }
+
+    for flag in &[X_PLACEMENT_DEVICE, Y_PLACEMENT_DEVICE, X_ADVANCE_DEVICE, Y_ADVANCE_DEVICE] {
+        if format & flag != 0 {
+            s.read::<Option<Offset16>>()?;
+        }
+    }
+
+    Some(record)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tag;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    // A GPOS table with a single lookup.
+    fn table(lookup_type: u16, subtable: &[u8]) -> std::vec::Vec<u8> {
+        let mut data = writer::convert(&[
+            UInt16(1), // majorVersion
+            UInt16(0), // minorVersion
+            UInt16(0), // scriptListOffset
+            UInt16(0), // featureListOffset
+            UInt16(10), // lookupListOffset
+            // LookupList
+            UInt16(1), // lookupCount
+            UInt16(4), // lookupOffset [0]
+            // Lookup
+            UInt16(lookup_type), // lookupType
+            UInt16(0), // lookupFlag
+            UInt16(1), // subTableCount
+            UInt16(8), // subtableOffset [0]
+        ]);
+        data.extend_from_slice(subtable);
+        data
+    }
+
+    #[test]
+    fn value_record_all_fields() {
+        let data = writer::convert(&[
+            Int16(1), // xPlacement
+            Int16(-2), // yPlacement
+            Int16(3), // xAdvance
+            Int16(-4), // yAdvance
+            UInt16(0), // xPlaDeviceOffset
+            UInt16(0), // yPlaDeviceOffset
+            UInt16(0), // xAdvDeviceOffset
+            UInt16(0), // yAdvDeviceOffset
+            UInt16(0xABCD), // trailing data
+        ]);
+
+        let mut s = Stream::new(&data);
+        assert_eq!(read_value_record(&mut s, 0x00FF), Some(ValueRecord {
+            x_placement: Some(1),
+            y_placement: Some(-2),
+            x_advance: Some(3),
+            y_advance: Some(-4),
+        }));
+        assert_eq!(s.read::<u16>(), Some(0xABCD));
+    }
+
+    #[test]
+    fn value_record_truncated() {
+        let data = writer::convert(&[
+            Int16(1), // xPlacement
+        ]);
+
+        let mut s = Stream::new(&data);
+        assert_eq!(read_value_record(&mut s, 0x0011), None);
+    }
+
+    #[test]
+    fn pair_adjustment_with_device_tables() {
+        let data = table(2, &writer::convert(&[
+            UInt16(1), // posFormat
+            UInt16(12), // coverageOffset
+            UInt16(0x0050), // valueFormat1: XPlaDevice | XAdvDevice
+            UInt16(0x0014), // valueFormat2: XAdvance | XPlaDevice
+            UInt16(1), // pairSetCount
+            UInt16(18), // pairSetOffset [0]
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(1), // glyphCount
+            UInt16(1), // glyphArray [0]
+            // PairSet
+            UInt16(1), // pairValueCount
+            UInt16(2), // secondGlyph
+            UInt16(0), // value1.xPlaDeviceOffset
+            UInt16(0), // value1.xAdvDeviceOffset
+            Int16(-40), // value2.xAdvance
+            UInt16(0), // value2.xPlaDeviceOffset
+        ]));
+
+        let table = PositioningTable::parse(&data).unwrap();
+        let subtable = table.lookups().get(LookupIndex(0)).unwrap().subtables().next().unwrap();
+        let (value1, value2) = pair_adjustment(subtable, GlyphId(1), GlyphId(2)).unwrap();
+        assert_eq!(value1, ValueRecord::default());
+        assert_eq!(value2.x_advance, Some(-40));
+
+        // Only the first glyph's advance is used for kerning.
+        assert_eq!(table.glyphs_kerning(GlyphId(1), GlyphId(2)), None);
+    }
+
+    #[test]
+    fn single_adjustment_format1() {
+        let data = table(1, &writer::convert(&[
+            UInt16(1), // posFormat
+            UInt16(10), // coverageOffset
+            UInt16(0x0006), // valueFormat: YPlacement | XAdvance
+            Int16(-20), // yPlacement
+            Int16(35), // xAdvance
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(2), // glyphCount
+            UInt16(3), // glyphArray [0]
+            UInt16(4), // glyphArray [1]
+        ]));
+
+        let table = PositioningTable::parse(&data).unwrap();
+        let lookup = table.lookups().get(LookupIndex(0)).unwrap();
+        let record = ValueRecord {
+            y_placement: Some(-20),
+            x_advance: Some(35),
+            ..ValueRecord::default()
+        };
+        assert_eq!(table.single_position(lookup, GlyphId(3)), Some(record));
+        assert_eq!(table.single_position(lookup, GlyphId(4)), Some(record));
+        assert_eq!(table.single_position(lookup, GlyphId(5)), None);
+    }
+
+    #[test]
+    fn single_adjustment_format2() {
+        let data = table(1, &writer::convert(&[
+            UInt16(2), // posFormat
+            UInt16(16), // coverageOffset
+            UInt16(0x0009), // valueFormat: XPlacement | YAdvance
+            UInt16(2), // valueCount
+            Int16(10), // xPlacement
+            Int16(-5), // yAdvance
+            Int16(20), // xPlacement
+            Int16(-15), // yAdvance
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(3), // glyphCount
+            UInt16(3), // glyphArray [0]
+            UInt16(4), // glyphArray [1]
+            UInt16(8), // glyphArray [2], no value record
+        ]));
+
+        let table = PositioningTable::parse(&data).unwrap();
+        let lookup = table.lookups().get(LookupIndex(0)).unwrap();
+        assert_eq!(table.single_position(lookup, GlyphId(4)), Some(ValueRecord {
+            x_placement: Some(20),
+            y_advance: Some(-15),
+            ..ValueRecord::default()
+        }));
+        assert_eq!(table.single_position(lookup, GlyphId(8)), None);
+    }
+
+    #[test]
+    fn single_adjustment_wrong_lookup_type() {
+        let data = table(2, &writer::convert(&[
+            UInt16(1), // posFormat
+            UInt16(8), // coverageOffset
+            UInt16(0x0004), // valueFormat: XAdvance
+            Int16(35), // xAdvance
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(1), // glyphCount
+            UInt16(3), // glyphArray [0]
+        ]));
+
+        let table = PositioningTable::parse(&data).unwrap();
+        let lookup = table.lookups().get(LookupIndex(0)).unwrap();
+        assert_eq!(table.single_position(lookup, GlyphId(3)), None);
+    }
+
+    #[test]
+    fn single_adjustment_extension() {
+        let mut subtable = writer::convert(&[
+            UInt16(1), // posFormat
+            UInt16(1), // extensionLookupType
+            UInt32(8), // extensionOffset
+        ]);
+        subtable.extend_from_slice(&writer::convert(&[
+            UInt16(1), // posFormat
+            UInt16(8), // coverageOffset
+            UInt16(0x0004), // valueFormat: XAdvance
+            Int16(35), // xAdvance
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(1), // glyphCount
+            UInt16(3), // glyphArray [0]
+        ]));
+
+        let data = table(9, &subtable);
+        let table = PositioningTable::parse(&data).unwrap();
+        let lookup = table.lookups().get(LookupIndex(0)).unwrap();
+        assert_eq!(lookup.lookup_type(), 9);
+        assert_eq!(table.single_position(lookup, GlyphId(3)).unwrap().x_advance, Some(35));
+    }
+
+    #[test]
+    fn mark_to_base() {
+        let data = table(4, &writer::convert(&[
+            UInt16(1), // posFormat
+            UInt16(12), // markCoverageOffset
+            UInt16(20), // baseCoverageOffset
+            UInt16(2), // markClassCount
+            UInt16(26), // markArrayOffset
+            UInt16(42), // baseArrayOffset
+            // Mark coverage
+            UInt16(1), // coverageFormat
+            UInt16(2), // glyphCount
+            UInt16(10), // glyphArray [0]
+            UInt16(11), // glyphArray [1]
+            // Base coverage
+            UInt16(1), // coverageFormat
+            UInt16(1), // glyphCount
+            UInt16(5), // glyphArray [0]
+            // MarkArray
+            UInt16(2), // markCount
+            UInt16(1), // markRecords [0].markClass
+            UInt16(10), // markRecords [0].markAnchorOffset
+            UInt16(0), // markRecords [1].markClass
+            UInt16(10), // markRecords [1].markAnchorOffset
+            // Anchor
+            UInt16(1), // anchorFormat
+            Int16(100), // xCoordinate
+            Int16(-20), // yCoordinate
+            // BaseArray
+            UInt16(1), // baseCount
+            UInt16(0), // baseRecords [0].baseAnchorOffsets [0]
+            UInt16(6), // baseRecords [0].baseAnchorOffsets [1]
+            // Anchor
+            UInt16(2), // anchorFormat
+            Int16(300), // xCoordinate
+            Int16(500), // yCoordinate
+            UInt16(3), // anchorPoint
+        ]));
+
+        let table = PositioningTable::parse(&data).unwrap();
+        let lookup = table.lookups().get(LookupIndex(0)).unwrap();
+        assert_eq!(table.mark_to_base(lookup, GlyphId(10), GlyphId(5)), Some((200, 520)));
+        // No base anchor for the mark's class.
+        assert_eq!(table.mark_to_base(lookup, GlyphId(11), GlyphId(5)), None);
+        // Not covered.
+        assert_eq!(table.mark_to_base(lookup, GlyphId(5), GlyphId(10)), None);
+        assert_eq!(table.mark_to_base(lookup, GlyphId(10), GlyphId(6)), None);
+        // Wrong lookup type.
+        assert_eq!(table.single_position(lookup, GlyphId(10)), None);
+    }
+
+    #[test]
+    fn pair_adjustment_format1() {
+        let data = table(2, &writer::convert(&[
+            UInt16(1), // posFormat
+            UInt16(12), // coverageOffset
+            UInt16(0x0005), // valueFormat1: XPlacement | XAdvance
+            UInt16(0), // valueFormat2
+            UInt16(1), // pairSetCount
+            UInt16(18), // pairSetOffset [0]
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(1), // glyphCount
+            UInt16(1), // glyphArray [0]
+            // PairSet
+            UInt16(2), // pairValueCount
+            UInt16(2), // secondGlyph
+            Int16(5), // xPlacement
+            Int16(-50), // xAdvance
+            UInt16(4), // secondGlyph
+            Int16(5), // xPlacement
+            Int16(-30), // xAdvance
+        ]));
+
+        let table = PositioningTable::parse(&data).unwrap();
+        assert_eq!(table.glyphs_kerning(GlyphId(1), GlyphId(2)), Some(-50));
+        assert_eq!(table.glyphs_kerning(GlyphId(1), GlyphId(4)), Some(-30));
+        assert_eq!(table.glyphs_kerning(GlyphId(1), GlyphId(3)), None);
+        assert_eq!(table.glyphs_kerning(GlyphId(2), GlyphId(4)), None);
+    }
+
+    #[test]
+    fn pair_adjustment_format2() {
+        let data = table(2, &writer::convert(&[
+            UInt16(2), // posFormat
+            UInt16(24), // coverageOffset
+            UInt16(0x0004), // valueFormat1: XAdvance
+            UInt16(0), // valueFormat2
+            UInt16(32), // classDef1Offset
+            UInt16(40), // classDef2Offset
+            UInt16(2), // class1Count
+            UInt16(2), // class2Count
+            Int16(0), // class 0 x class 0
+            Int16(-10), // class 0 x class 1
+            Int16(0), // class 1 x class 0
+            Int16(-20), // class 1 x class 1
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(2), // glyphCount
+            UInt16(1), // glyphArray [0]
+            UInt16(5), // glyphArray [1]
+            // ClassDef1
+            UInt16(1), // classFormat
+            UInt16(5), // startGlyphID
+            UInt16(1), // glyphCount
+            UInt16(1), // classValueArray [0]
+            // ClassDef2
+            UInt16(1), // classFormat
+            UInt16(2), // startGlyphID
+            UInt16(2), // glyphCount
+            UInt16(1), // classValueArray [0]
+            UInt16(0), // classValueArray [1]
+        ]));
+
+        let table = PositioningTable::parse(&data).unwrap();
+        assert_eq!(table.glyphs_kerning(GlyphId(1), GlyphId(2)), Some(-10));
+        assert_eq!(table.glyphs_kerning(GlyphId(5), GlyphId(2)), Some(-20));
+        assert_eq!(table.glyphs_kerning(GlyphId(5), GlyphId(3)), Some(0));
+        assert_eq!(table.glyphs_kerning(GlyphId(2), GlyphId(2)), None);
+    }
+
+    #[test]
+    fn pair_adjustment_format2_edge_cases() {
+        let data = table(2, &writer::convert(&[
+            UInt16(2), // posFormat
+            UInt16(20), // coverageOffset
+            UInt16(0x0004), // valueFormat1: XAdvance
+            UInt16(0), // valueFormat2
+            UInt16(28), // classDef1Offset
+            UInt16(36), // classDef2Offset
+            UInt16(1), // class1Count
+            UInt16(2), // class2Count
+            Int16(-5), // class 0 x class 0
+            Int16(-10), // class 0 x class 1
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(2), // glyphCount
+            UInt16(1), // glyphArray [0]
+            UInt16(5), // glyphArray [1]
+            // ClassDef1
+            UInt16(1), // classFormat
+            UInt16(5), // startGlyphID
+            UInt16(1), // glyphCount
+            UInt16(1), // classValueArray [0]
+            // ClassDef2
+            UInt16(1), // classFormat
+            UInt16(2), // startGlyphID
+            UInt16(1), // glyphCount
+            UInt16(1), // classValueArray [0]
+        ]));
+
+        let table = PositioningTable::parse(&data).unwrap();
+        assert_eq!(table.glyphs_kerning(GlyphId(1), GlyphId(2)), Some(-10));
+        // Glyphs not listed in ClassDef2 are in class 0.
+        assert_eq!(table.glyphs_kerning(GlyphId(1), GlyphId(9)), Some(-5));
+        // Class 1 is out of the class1Count bounds.
+        assert_eq!(table.glyphs_kerning(GlyphId(5), GlyphId(2)), None);
+        // Not in the coverage at all.
+        assert_eq!(table.glyphs_kerning(GlyphId(3), GlyphId(2)), None);
+        assert_eq!(table.glyphs_kerning(GlyphId(3), GlyphId(9)), None);
+    }
+
+    #[test]
+    fn scripts_and_features() {
+        let data = writer::convert(&[
+            UInt16(1), // majorVersion
+            UInt16(0), // minorVersion
+            UInt16(10), // scriptListOffset
+            UInt16(56), // featureListOffset
+            UInt16(80), // lookupListOffset
+            // ScriptList
+            UInt16(2), // scriptCount
+            Raw(b"DFLT"), // scriptTag
+            UInt16(14), // scriptOffset
+            Raw(b"latn"), // scriptTag
+            UInt16(26), // scriptOffset
+            // Script DFLT
+            UInt16(4), // defaultLangSysOffset
+            UInt16(0), // langSysCount
+            // LangSys
+            UInt16(0), // lookupOrderOffset
+            UInt16(0xFFFF), // requiredFeatureIndex
+            UInt16(1), // featureIndexCount
+            UInt16(0), // featureIndices [0]
+            // Script latn
+            UInt16(0), // defaultLangSysOffset
+            UInt16(1), // langSysCount
+            Raw(b"TRK "), // langSysTag
+            UInt16(10), // langSysOffset
+            // LangSys
+            UInt16(0), // lookupOrderOffset
+            UInt16(1), // requiredFeatureIndex
+            UInt16(2), // featureIndexCount
+            UInt16(0), // featureIndices [0]
+            UInt16(1), // featureIndices [1]
+            // FeatureList, not sorted
+            UInt16(2), // featureCount
+            Raw(b"liga"), // featureTag
+            UInt16(14), // featureOffset
+            Raw(b"kern"), // featureTag
+            UInt16(18), // featureOffset
+            // Feature liga
+            UInt16(0), // featureParamsOffset
+            UInt16(0), // lookupIndexCount
+            // Feature kern
+            UInt16(0), // featureParamsOffset
+            UInt16(1), // lookupIndexCount
+            UInt16(0), // lookupListIndices [0]
+            // LookupList
+            UInt16(0), // lookupCount
+        ]);
+
+        let table = PositioningTable::parse(&data).unwrap();
+        assert_eq!(table.scripts().count(), 2);
+        assert_eq!(table.features().count(), 2);
+        assert!(table.lookups().is_empty());
+
+        let (index, script) = table.script_by_tag(Tag::from_bytes(b"DFLT")).unwrap();
+        assert_eq!(index, ScriptIndex(0));
+        let language = script.default_language().unwrap();
+        assert_eq!(language.tag(), Tag::from_bytes(b"dflt"));
+        assert_eq!(language.required_feature(), None);
+        assert_eq!(language.features().count(), 1);
+        assert_eq!(script.languages().count(), 0);
+        assert_eq!(script.default_or_first_language().unwrap().tag(), Tag::from_bytes(b"dflt"));
+
+        let (index, script) = table.script_by_tag(Tag::from_bytes(b"latn")).unwrap();
+        assert_eq!(index, ScriptIndex(1));
+        assert!(script.default_language().is_none());
+        let language = script.language_by_tag(Tag::from_bytes(b"TRK ")).unwrap();
+        assert_eq!(language.required_feature(), Some(FeatureIndex(1)));
+        assert_eq!(language.features().last(), Some(FeatureIndex(1)));
+        assert!(script.language_by_tag(Tag::from_bytes(b"DEU ")).is_none());
+        assert_eq!(script.default_or_first_language().unwrap().tag(), Tag::from_bytes(b"TRK "));
+
+        assert!(table.script_by_tag(Tag::from_bytes(b"cyrl")).is_none());
+
+        let (index, feature) = table.feature_by_tag(Tag::from_bytes(b"kern")).unwrap();
+        assert_eq!(index, FeatureIndex(1));
+        assert_eq!(feature.lookups().collect::<std::vec::Vec<_>>(), &[LookupIndex(0)]);
+        assert!(table.feature_by_tag(Tag::from_bytes(b"smcp")).is_none());
+    }
+}
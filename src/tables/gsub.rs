@@ -0,0 +1,443 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/gsub
+
+use crate::GlyphId;
+use crate::parser::{Stream, Offset, Offset16, LazyArray16};
+use crate::ggg::*;
+
+
+mod lookup_type {
+    pub const MULTIPLE: u16 = 2;
+    pub const ALTERNATE: u16 = 3;
+    pub const CONTEXT: u16 = 5;
+    pub const CHAINING_CONTEXT: u16 = 6;
+    pub const EXTENSION: u16 = 7;
+}
+
+/// A [Glyph Substitution Table](https://docs.microsoft.com/en-us/typography/opentype/spec/gsub).
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct SubstitutionTable<'a> {
+    table: LayoutTable<'a>,
+}
+
+impl<'a> GlyphPosSubTable<'a> for SubstitutionTable<'a> {
+    #[inline]
+    fn scripts(&self) -> Scripts<'a> {
+        self.table.scripts
+    }
+
+    #[inline]
+    fn features(&self) -> Features<'a> {
+        self.table.features
+    }
+
+    #[inline]
+    fn lookups(&self) -> Lookups<'a> {
+        self.table.lookups
+    }
+}
+
+impl<'a> SubstitutionTable<'a> {
+    pub(crate) fn parse(data: &'a [u8]) -> Option<Self> {
+        LayoutTable::parse(data, lookup_type::EXTENSION).map(|table| SubstitutionTable { table })
+    }
+
+    /// Returns a glyph's replacement sequence from a
+    /// [multiple substitution](https://docs.microsoft.com/en-us/typography/opentype/spec/gsub#lookuptype-2-multiple-substitution-subtable)
+    /// lookup.
+    ///
+    /// An empty sequence means that the glyph should be deleted.
+    ///
+    /// Returns `None` when lookup has a different type or glyph is not covered.
+    pub fn multiple_substitution(
+        &self,
+        lookup: Lookup<'a>,
+        glyph_id: GlyphId,
+    ) -> Option<GlyphIds<'a>> {
+        lookup.subtables_of_type(lookup_type::MULTIPLE)
+            .find_map(|data| sequence_table(data, glyph_id))
+    }
+
+    /// Returns a glyph's alternates from an
+    /// [alternate substitution](https://docs.microsoft.com/en-us/typography/opentype/spec/gsub#lookuptype-3-alternate-substitution-subtable)
+    /// lookup.
+    ///
+    /// It's up to the caller to choose which alternate to use.
+    ///
+    /// Returns `None` when lookup has a different type or glyph is not covered.
+    pub fn alternate_substitutes(
+        &self,
+        lookup: Lookup<'a>,
+        glyph_id: GlyphId,
+    ) -> Option<GlyphIds<'a>> {
+        lookup.subtables_of_type(lookup_type::ALTERNATE)
+            .find_map(|data| sequence_table(data, glyph_id))
+    }
+
+    /// Returns lookups that should be applied to a matched glyph sequence from a
+    /// [contextual](https://docs.microsoft.com/en-us/typography/opentype/spec/gsub#lookuptype-5-contextual-substitution-subtable)
+    /// or a [chained contextual](https://docs.microsoft.com/en-us/typography/opentype/spec/gsub#lookuptype-6-chained-contexts-substitution-subtable)
+    /// substitution lookup.
+    ///
+    /// `glyphs` is a glyph run and `index` is a position of the first input glyph in it.
+    /// Sequence indices in the returned records are relative to `index`.
+    ///
+    /// Only format 1 (simple glyph contexts) is supported. Glyphs are matched as is,
+    /// so glyphs skipped by the lookup flags should be removed from the run beforehand.
+    ///
+    /// Returns `None` when lookup has a different type or no rule matches.
+    pub fn contextual_substitution(
+        &self,
+        lookup: Lookup<'a>,
+        glyphs: &[GlyphId],
+        index: usize,
+    ) -> Option<SequenceLookupRecords<'a>> {
+        let glyph_id = *glyphs.get(index)?;
+        lookup.resolved_subtables().find_map(|(kind, data)| {
+            let rule_set = context_rule_set(data, glyph_id)?;
+            match kind {
+                lookup_type::CONTEXT => {
+                    rules(rule_set).find_map(|rule| match_sequence_rule(rule, glyphs, index))
+                }
+                lookup_type::CHAINING_CONTEXT => {
+                    rules(rule_set).find_map(|rule| match_chained_sequence_rule(rule, glyphs, index))
+                }
+                _ => None,
+            }
+        })
+    }
+}
+
+// AlternateSubstFormat1 and MultipleSubstFormat1 share the same layout:
+// a coverage and an array of offsets to glyph ID arrays.
+fn sequence_table<'a>(data: &'a [u8], glyph_id: GlyphId) -> Option<GlyphIds<'a>> {
+    let mut s = Stream::new(data);
+    let format: u16 = s.read()?;
+    if format != 1 {
+        return None;
+    }
+
+    let coverage_offset: Offset16 = s.read()?;
+    let coverage = CoverageTable::new(data.get(coverage_offset.to_usize()..)?);
+    let coverage_index = coverage.coverage_index(glyph_id)?;
+
+    let count: u16 = s.read()?;
+    let offsets = s.read_array16::<Offset16>(count)?;
+    let offset = offsets.get(coverage_index)?;
+
+    let mut s = Stream::new_at(data, offset.to_usize())?;
+    let count: u16 = s.read()?;
+    s.read_array16::<GlyphId>(count).map(GlyphIds::new)
+}
+
+// SequenceContextFormat1 and ChainedSequenceContextFormat1 share the same layout:
+// a coverage and an array of offsets to rule sets.
+fn context_rule_set(data: &[u8], glyph_id: GlyphId) -> Option<&[u8]> {
+    let mut s = Stream::new(data);
+    let format: u16 = s.read()?;
+    if format != 1 {
+        return None;
+    }
+
+    let coverage_offset: Offset16 = s.read()?;
+    let coverage = CoverageTable::new(data.get(coverage_offset.to_usize()..)?);
+    let coverage_index = coverage.coverage_index(glyph_id)?;
+
+    let count: u16 = s.read()?;
+    let offsets = s.read_array16::<Option<Offset16>>(count)?;
+    let offset = offsets.get(coverage_index)??;
+    data.get(offset.to_usize()..)
+}
+
+// Returns an iterator over rules data in a rule set. Malformed rules are skipped.
+fn rules(data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut s = Stream::new(data);
+    let offsets = s.read::<u16>()
+        .and_then(|count| s.read_array16::<Offset16>(count))
+        .unwrap_or_default();
+    offsets.into_iter().filter_map(move |offset| data.get(offset.to_usize()..))
+}
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#sequence-context-format-1-simple-glyph-contexts
+fn match_sequence_rule<'a>(
+    data: &'a [u8],
+    glyphs: &[GlyphId],
+    index: usize,
+) -> Option<SequenceLookupRecords<'a>> {
+    let mut s = Stream::new(data);
+    let glyph_count: u16 = s.read()?;
+    let lookup_count: u16 = s.read()?;
+    // The first input glyph is matched by the coverage.
+    let input = s.read_array16::<GlyphId>(glyph_count.checked_sub(1)?)?;
+    let records = s.read_array16(lookup_count)?;
+
+    if !match_forward(input, glyphs, index.checked_add(1)?) {
+        return None;
+    }
+
+    Some(SequenceLookupRecords::new(records))
+}
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#chained-sequence-context-format-1-simple-glyph-contexts
+fn match_chained_sequence_rule<'a>(
+    data: &'a [u8],
+    glyphs: &[GlyphId],
+    index: usize,
+) -> Option<SequenceLookupRecords<'a>> {
+    let mut s = Stream::new(data);
+    let backtrack_count: u16 = s.read()?;
+    let backtrack = s.read_array16::<GlyphId>(backtrack_count)?;
+    let input_count: u16 = s.read()?;
+    // The first input glyph is matched by the coverage.
+    let input = s.read_array16::<GlyphId>(input_count.checked_sub(1)?)?;
+    let lookahead_count: u16 = s.read()?;
+    let lookahead = s.read_array16::<GlyphId>(lookahead_count)?;
+    let lookup_count: u16 = s.read()?;
+    let records = s.read_array16(lookup_count)?;
+
+    // 'The backtrack sequence is specified in reverse logical order.'
+    if usize::from(backtrack.len()) > index {
+        return None;
+    }
+
+    let is_backtrack_matched = backtrack.into_iter().enumerate()
+        .all(|(i, glyph_id)| glyphs[index - 1 - i] == glyph_id);
+
+    if !is_backtrack_matched
+        || !match_forward(input, glyphs, index.checked_add(1)?)
+        || !match_forward(lookahead, glyphs, index.checked_add(usize::from(input_count))?)
+    {
+        return None;
+    }
+
+    Some(SequenceLookupRecords::new(records))
+}
+
+fn match_forward(sequence: LazyArray16<GlyphId>, glyphs: &[GlyphId], start: usize) -> bool {
+    sequence.into_iter().enumerate()
+        .all(|(i, glyph_id)| glyphs.get(start + i) == Some(&glyph_id))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    // A GSUB table with a single lookup.
+    fn table(lookup_type: u16, subtable: &[u8]) -> std::vec::Vec<u8> {
+        let mut data = writer::convert(&[
+            UInt16(1), // majorVersion
+            UInt16(0), // minorVersion
+            UInt16(0), // scriptListOffset
+            UInt16(0), // featureListOffset
+            UInt16(10), // lookupListOffset
+            // LookupList
+            UInt16(1), // lookupCount
+            UInt16(4), // lookupOffset [0]
+            // Lookup
+            UInt16(lookup_type), // lookupType
+            UInt16(0), // lookupFlag
+            UInt16(1), // subTableCount
+            UInt16(8), // subtableOffset [0]
+        ]);
+        data.extend_from_slice(subtable);
+        data
+    }
+
+    #[test]
+    fn multiple_substitution() {
+        let data = table(2, &writer::convert(&[
+            UInt16(1), // substFormat
+            UInt16(10), // coverageOffset
+            UInt16(2), // sequenceCount
+            UInt16(18), // sequenceOffsets [0]
+            UInt16(26), // sequenceOffsets [1]
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(2), // glyphCount
+            UInt16(3), // glyphArray [0]
+            UInt16(7), // glyphArray [1]
+            // Sequence 0
+            UInt16(3), // glyphCount
+            UInt16(10), // substituteGlyphIDs [0]
+            UInt16(11), // substituteGlyphIDs [1]
+            UInt16(12), // substituteGlyphIDs [2]
+            // Sequence 1
+            UInt16(0), // glyphCount
+        ]));
+
+        let table = SubstitutionTable::parse(&data).unwrap();
+        let lookup = table.lookups().get(LookupIndex(0)).unwrap();
+
+        let glyphs = table.multiple_substitution(lookup, GlyphId(3)).unwrap();
+        assert_eq!(glyphs.collect::<std::vec::Vec<_>>(), &[GlyphId(10), GlyphId(11), GlyphId(12)]);
+
+        // An empty sequence deletes the glyph.
+        let glyphs = table.multiple_substitution(lookup, GlyphId(7)).unwrap();
+        assert!(glyphs.is_empty());
+
+        assert!(table.multiple_substitution(lookup, GlyphId(4)).is_none());
+        assert!(table.alternate_substitutes(lookup, GlyphId(3)).is_none());
+    }
+
+    #[test]
+    fn alternate_substitution() {
+        let data = table(3, &writer::convert(&[
+            UInt16(1), // substFormat
+            UInt16(10), // coverageOffset
+            UInt16(2), // alternateSetCount
+            UInt16(18), // alternateSetOffsets [0]
+            UInt16(24), // alternateSetOffsets [1]
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(2), // glyphCount
+            UInt16(3), // glyphArray [0]
+            UInt16(7), // glyphArray [1]
+            // AlternateSet 0
+            UInt16(2), // glyphCount
+            UInt16(10), // alternateGlyphIDs [0]
+            UInt16(11), // alternateGlyphIDs [1]
+            // AlternateSet 1
+            UInt16(1), // glyphCount
+            UInt16(12), // alternateGlyphIDs [0]
+        ]));
+
+        let table = SubstitutionTable::parse(&data).unwrap();
+        let lookup = table.lookups().get(LookupIndex(0)).unwrap();
+
+        let alternates = table.alternate_substitutes(lookup, GlyphId(3)).unwrap();
+        assert_eq!(alternates.len(), 2);
+        assert_eq!(alternates.get(1), Some(GlyphId(11)));
+        assert_eq!(alternates.collect::<std::vec::Vec<_>>(), &[GlyphId(10), GlyphId(11)]);
+
+        let alternates = table.alternate_substitutes(lookup, GlyphId(7)).unwrap();
+        assert_eq!(alternates.collect::<std::vec::Vec<_>>(), &[GlyphId(12)]);
+
+        assert!(table.alternate_substitutes(lookup, GlyphId(4)).is_none());
+    }
+
+    #[test]
+    fn alternate_substitution_wrong_lookup_type() {
+        let data = table(1, &writer::convert(&[
+            UInt16(1), // substFormat
+            UInt16(8), // coverageOffset
+            UInt16(1), // alternateSetCount
+            UInt16(14), // alternateSetOffsets [0]
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(1), // glyphCount
+            UInt16(3), // glyphArray [0]
+            // AlternateSet
+            UInt16(1), // glyphCount
+            UInt16(10), // alternateGlyphIDs [0]
+        ]));
+
+        let table = SubstitutionTable::parse(&data).unwrap();
+        let lookup = table.lookups().get(LookupIndex(0)).unwrap();
+        assert!(table.alternate_substitutes(lookup, GlyphId(3)).is_none());
+    }
+
+    #[test]
+    fn contextual_substitution() {
+        let data = table(5, &writer::convert(&[
+            UInt16(1), // format
+            UInt16(8), // coverageOffset
+            UInt16(1), // seqRuleSetCount
+            UInt16(14), // seqRuleSetOffsets [0]
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(1), // glyphCount
+            UInt16(3), // glyphArray [0]
+            // SequenceRuleSet
+            UInt16(2), // seqRuleCount
+            UInt16(6), // seqRuleOffsets [0]
+            UInt16(18), // seqRuleOffsets [1]
+            // SequenceRule 0
+            UInt16(3), // glyphCount
+            UInt16(1), // seqLookupCount
+            UInt16(4), // inputSequence [0]
+            UInt16(5), // inputSequence [1]
+            UInt16(2), // sequenceIndex
+            UInt16(1), // lookupListIndex
+            // SequenceRule 1
+            UInt16(2), // glyphCount
+            UInt16(2), // seqLookupCount
+            UInt16(4), // inputSequence [0]
+            UInt16(0), // sequenceIndex
+            UInt16(1), // lookupListIndex
+            UInt16(1), // sequenceIndex
+            UInt16(2), // lookupListIndex
+        ]));
+
+        let table = SubstitutionTable::parse(&data).unwrap();
+        let lookup = table.lookups().get(LookupIndex(0)).unwrap();
+
+        let glyphs = [GlyphId(1), GlyphId(3), GlyphId(4), GlyphId(5)];
+        let records = table.contextual_substitution(lookup, &glyphs, 1).unwrap();
+        assert_eq!(records.collect::<std::vec::Vec<_>>(), &[
+            SequenceLookupRecord { sequence_index: 2, lookup_index: LookupIndex(1) },
+        ]);
+
+        // The first rule doesn't match, since the run is too short.
+        let records = table.contextual_substitution(lookup, &glyphs[..3], 1).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records.get(1), Some(SequenceLookupRecord {
+            sequence_index: 1,
+            lookup_index: LookupIndex(2),
+        }));
+
+        // Not covered.
+        assert!(table.contextual_substitution(lookup, &glyphs, 0).is_none());
+        // Out of bounds.
+        assert!(table.contextual_substitution(lookup, &glyphs, 4).is_none());
+        // No matching rule.
+        assert!(table.contextual_substitution(lookup, &[GlyphId(3), GlyphId(5)], 0).is_none());
+    }
+
+    #[test]
+    fn chained_contextual_substitution() {
+        let data = table(6, &writer::convert(&[
+            UInt16(1), // format
+            UInt16(8), // coverageOffset
+            UInt16(1), // chainedSeqRuleSetCount
+            UInt16(14), // chainedSeqRuleSetOffsets [0]
+            // Coverage
+            UInt16(1), // coverageFormat
+            UInt16(1), // glyphCount
+            UInt16(3), // glyphArray [0]
+            // ChainedSequenceRuleSet
+            UInt16(1), // chainedSeqRuleCount
+            UInt16(4), // chainedSeqRuleOffsets [0]
+            // ChainedSequenceRule
+            UInt16(2), // backtrackGlyphCount
+            UInt16(2), // backtrackSequence [0]
+            UInt16(1), // backtrackSequence [1]
+            UInt16(2), // inputGlyphCount
+            UInt16(4), // inputSequence [0]
+            UInt16(1), // lookaheadGlyphCount
+            UInt16(5), // lookaheadSequence [0]
+            UInt16(1), // seqLookupCount
+            UInt16(1), // sequenceIndex
+            UInt16(7), // lookupListIndex
+        ]));
+
+        let table = SubstitutionTable::parse(&data).unwrap();
+        let lookup = table.lookups().get(LookupIndex(0)).unwrap();
+
+        let glyphs = [GlyphId(1), GlyphId(2), GlyphId(3), GlyphId(4), GlyphId(5)];
+        let records = table.contextual_substitution(lookup, &glyphs, 2).unwrap();
+        assert_eq!(records.collect::<std::vec::Vec<_>>(), &[
+            SequenceLookupRecord { sequence_index: 1, lookup_index: LookupIndex(7) },
+        ]);
+
+        // Missing backtrack.
+        assert!(table.contextual_substitution(lookup, &glyphs[1..], 1).is_none());
+        // Missing lookahead.
+        assert!(table.contextual_substitution(lookup, &glyphs[..4], 2).is_none());
+        // Wrong backtrack order.
+        let glyphs = [GlyphId(2), GlyphId(1), GlyphId(3), GlyphId(4), GlyphId(5)];
+        assert!(table.contextual_substitution(lookup, &glyphs, 2).is_none());
+    }
+}
@@ -124,8 +124,9 @@ pub(crate) fn outline(
     let range = loca_table.glyph_range(glyph_id)?;
     let glyph_data = glyf_table.get(range)?;
 
+    let mut components_left = glyf::MAX_TOTAL_COMPONENTS;
     outline_var_impl(loca_table, glyf_table, gvar_table,
-                     glyph_id, glyph_data, coordinates, 0, &mut b);
+                     glyph_id, glyph_data, coordinates, 0, &mut components_left, &mut b)?;
     b.bbox.and_then(|bbox| bbox.to_rect())
 }
 
@@ -137,6 +138,7 @@ fn outline_var_impl<'a>(
     data: &[u8],
     coordinates: &[NormalizedCoord],
     depth: u8,
+    components_left: &mut u16,
     builder: &mut glyf::Builder,
 ) -> Option<()> {
     if depth >= glyf::MAX_COMPONENTS {
@@ -191,6 +193,8 @@ fn outline_var_impl<'a>(
         gvar_table.parse_variation_data(glyph_id, coordinates, components_count, &mut tuples)?;
 
         while let Some(component) = components.next() {
+            *components_left = components_left.checked_sub(1)?;
+
             let (tx, ty) = tuples.apply_null()?;
 
             let mut transform = builder.transform;
@@ -208,7 +212,7 @@ fn outline_var_impl<'a>(
             let glyph_data = glyf_table.get(range)?;
             outline_var_impl(
                 loca_table, glyf_table, gvar_table, component.glyph_id,
-                glyph_data, coordinates, depth + 1, &mut b,
+                glyph_data, coordinates, depth + 1, components_left, &mut b,
             )?;
 
             // Take updated bbox.
@@ -248,10 +252,6 @@ fn parse_variation_data<'a>(
         return None;
     }
 
-    if tuple_variation_count >= MAX_TUPLES_LEN {
-        return None;
-    }
-
     // A glyph variation data consists of three parts: header + variation tuples + serialized data.
     // Each tuple has it's own chunk in the serialized data.
     // Because of that, we are using two parsing streams: one for tuples and one for serialized data.
@@ -296,7 +296,7 @@ struct VariationTuple<'a> {
     prev_point: Option<PointAndDelta>,
 }
 
-/// The maximum number of variation tuples.
+/// The maximum number of applicable variation tuples.
 ///
 /// The TrueType spec allows up to 4095 tuples, which is way larger
 /// than we do. But in reality, an average font will have less than 10 tuples.
@@ -403,10 +403,10 @@ impl<'a> VariationTuples<'a> {
 
 
 #[derive(Clone, Copy, Default, Debug)]
-struct TupleVariationHeaderData {
-    scalar: f32,
-    has_private_point_numbers: bool,
-    serialized_data_len: u16,
+pub(crate) struct TupleVariationHeaderData {
+    pub scalar: f32,
+    pub has_private_point_numbers: bool,
+    pub serialized_data_len: u16,
 }
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#tuplevariationheader
@@ -467,6 +467,12 @@ fn parse_variation_tuples<'a>(
             prev_point: None,
         };
 
+        // Only applicable tuples are stored, so a glyph can have more tuples
+        // than we support, as long as most of them are inactive for the current coordinates.
+        if tuples.len == MAX_TUPLES_LEN {
+            return None;
+        }
+
         tuples.headers[usize::from(tuples.len)] = tuple;
         tuples.len += 1;
     }
@@ -475,7 +481,7 @@ fn parse_variation_tuples<'a>(
 }
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#tuplevariationheader
-fn parse_tuple_variation_header(
+pub(crate) fn parse_tuple_variation_header(
     coordinates: &[NormalizedCoord],
     shared_tuple_records: &LazyArray16<F2DOT14>,
     s: &mut Stream,
@@ -561,7 +567,7 @@ fn parse_tuple_variation_header(
 
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#packed-point-numbers
-mod packed_points {
+pub(crate) mod packed_points {
     use crate::parser::{Stream, FromData};
 
     struct Control(u8);
@@ -965,7 +971,7 @@ use packed_points::*;
 
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/otvarcommonformats#packed-deltas
-mod packed_deltas {
+pub(crate) mod packed_deltas {
     use crate::parser::Stream;
 
     struct Control(u8);
@@ -1112,6 +1118,36 @@ mod packed_deltas {
         }
     }
 
+
+    /// Just like `PackedDeltasIter`, but for data with a single delta per point,
+    /// like in the `cvar` table.
+    #[derive(Clone, Copy, Default)]
+    pub struct PackedSingleDeltasIter<'a> {
+        data: &'a [u8],
+        run: RunState,
+        scalar: f32,
+    }
+
+    impl<'a> PackedSingleDeltasIter<'a> {
+        #[inline]
+        pub fn new(scalar: f32, data: &'a [u8]) -> Self {
+            PackedSingleDeltasIter {
+                data,
+                run: RunState::default(),
+                scalar,
+            }
+        }
+    }
+
+    impl Iterator for PackedSingleDeltasIter<'_> {
+        type Item = f32;
+
+        #[inline]
+        fn next(&mut self) -> Option<Self::Item> {
+            self.run.next(self.data, self.scalar)
+        }
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
@@ -1559,8 +1595,68 @@ fn infer_delta(
         //
         // 'Target point delta is derived from the adjacent point deltas
         // using linear interpolation.'
-        let d =   f32::from(try_opt_or!(target_point.checked_sub(prev_point), 0.0))
-                / f32::from(try_opt_or!(next_point.checked_sub(prev_point), 0.0));
+        let d =   (i32::from(target_point) - i32::from(prev_point)) as f32
+                / (i32::from(next_point) - i32::from(prev_point)) as f32;
         (1.0 - d) * prev_delta + d * next_delta
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn inactive_tuples_are_skipped() {
+        let mut data = writer::convert(&[
+            UInt16(MAX_TUPLES_LEN + 1), // tupleVariationCount
+            UInt16(4 + (MAX_TUPLES_LEN + 1) * 6), // dataOffset
+        ]);
+
+        // Tuples that are not applicable for a positive coordinate.
+        for _ in 0..MAX_TUPLES_LEN {
+            data.extend_from_slice(&writer::convert(&[
+                UInt16(0), // variationDataSize
+                UInt16(0x8000), // tupleIndex: EMBEDDED_PEAK_TUPLE
+                Int16(-16384), // peakTuple [0]
+            ]));
+        }
+
+        data.extend_from_slice(&writer::convert(&[
+            UInt16(7), // variationDataSize
+            UInt16(0x8000), // tupleIndex: EMBEDDED_PEAK_TUPLE
+            Int16(16384), // peakTuple [0]
+            // Serialized data
+            UInt8(0x04), // control: 5 bytes
+            Int8(10), Int8(0), Int8(0), Int8(0), Int8(-4),
+            UInt8(0x84), // control: 5 zeros
+        ]));
+
+        let coordinates = [NormalizedCoord::from(0.5)];
+        let mut tuples = VariationTuples {
+            headers: [VariationTuple::default(); MAX_TUPLES_LEN as usize],
+            len: 0,
+        };
+
+        let shared_tuple_records = LazyArray16::default();
+        parse_variation_data(&coordinates, &shared_tuple_records, 1, &data, &mut tuples).unwrap();
+        assert_eq!(tuples.len, 1);
+
+        let deltas = &mut tuples.headers[0].deltas;
+        assert_eq!(deltas.next(), Some((5.0, 0.0)));
+        assert_eq!(deltas.next(), Some((0.0, 0.0)));
+        assert_eq!(deltas.next(), Some((0.0, 0.0)));
+        assert_eq!(deltas.next(), Some((0.0, 0.0)));
+        assert_eq!(deltas.next(), Some((-2.0, 0.0)));
+        assert_eq!(deltas.next(), None);
+    }
+
+    #[test]
+    fn infer_delta_large_coordinates() {
+        assert_eq!(infer_delta(-20000, 0, 20000, 10.0, 20.0), 15.0);
+        assert_eq!(infer_delta(10, 5, 10, 3.0, 3.0), 3.0);
+        assert_eq!(infer_delta(0, -5, 10, 1.0, 2.0), 1.0);
+    }
+}
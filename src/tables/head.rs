@@ -9,15 +9,93 @@ pub(crate) enum IndexToLocationFormat {
     Long,
 }
 
+const MAGIC_NUMBER: u32 = 0x5F0F3CF5;
+
+/// A [macStyle](https://docs.microsoft.com/en-us/typography/opentype/spec/head) value.
+///
+/// Should be in sync with the `OS/2` table's `fsSelection`.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct MacStyle(u16);
+
+impl MacStyle {
+    /// Returns raw style bits.
+    #[inline]
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// Checks that the *Bold* bit is set.
+    #[inline]
+    pub fn is_bold(&self) -> bool {
+        self.0 & (1 << 0) != 0
+    }
+
+    /// Checks that the *Italic* bit is set.
+    #[inline]
+    pub fn is_italic(&self) -> bool {
+        self.0 & (1 << 1) != 0
+    }
+}
+
 #[inline]
 pub fn parse(data: &[u8]) -> Option<&[u8]> {
-    if data.len() == raw::TABLE_SIZE {
-        Some(data)
+    if data.len() != raw::TABLE_SIZE {
+        return None;
+    }
+
+    let magic: u32 = Stream::read_at(data, raw::MAGIC_NUMBER_OFFSET)?;
+    if magic != MAGIC_NUMBER {
+        return None;
+    }
+
+    Some(data)
+}
+
+#[inline]
+pub fn flags(data: &[u8]) -> Option<u16> {
+    Stream::read_at(data, raw::FLAGS_OFFSET)
+}
+
+#[inline]
+pub fn mac_style(data: &[u8]) -> Option<MacStyle> {
+    Stream::read_at(data, raw::MAC_STYLE_OFFSET).map(MacStyle)
+}
+
+#[inline]
+pub fn created(data: &[u8]) -> Option<i64> {
+    Stream::read_at(data, raw::CREATED_OFFSET)
+}
+
+#[inline]
+pub fn modified(data: &[u8]) -> Option<i64> {
+    Stream::read_at(data, raw::MODIFIED_OFFSET)
+}
+
+/// Converts a `LONGDATETIME` into a `SystemTime`.
+///
+/// Returns `None` on overflow.
+#[cfg(feature = "std")]
+pub fn to_system_time(seconds: i64) -> Option<std::time::SystemTime> {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // 'Date and time represented in number of seconds since 12:00 midnight,
+    // January 1, 1904, UTC.'
+    const SECONDS_FROM_1904_TO_1970: i64 = 2_082_844_800;
+
+    let unix_seconds = seconds.checked_sub(SECONDS_FROM_1904_TO_1970)?;
+    if unix_seconds >= 0 {
+        UNIX_EPOCH.checked_add(Duration::from_secs(unix_seconds as u64))
     } else {
-        None
+        // Cannot overflow, since `unix_seconds` is always larger than `i64::MIN`.
+        UNIX_EPOCH.checked_sub(Duration::from_secs(unix_seconds.wrapping_neg() as u64))
     }
 }
 
+#[inline]
+pub fn glyph_data_format(data: &[u8]) -> Option<i16> {
+    Stream::read_at(data, raw::GLYPH_DATA_FORMAT_OFFSET)
+}
+
 #[inline]
 pub fn units_per_em(data: &[u8]) -> Option<u16> {
     let num: u16 = Stream::read_at(data, raw::UNITS_PER_EM_OFFSET)?;
@@ -37,3 +115,73 @@ pub(crate) fn index_to_loc_format(data: &[u8]) -> Option<IndexToLocationFormat>
         _ => None,
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head_with_units_per_em(units_per_em: u16) -> [u8; raw::TABLE_SIZE] {
+        let mut data = [0u8; raw::TABLE_SIZE];
+        let offset = raw::UNITS_PER_EM_OFFSET;
+        data[offset..offset + 2].copy_from_slice(&units_per_em.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn units_per_em_range() {
+        assert_eq!(units_per_em(&head_with_units_per_em(0)), None);
+        assert_eq!(units_per_em(&head_with_units_per_em(15)), None);
+        assert_eq!(units_per_em(&head_with_units_per_em(16)), Some(16));
+        assert_eq!(units_per_em(&head_with_units_per_em(1000)), Some(1000));
+        assert_eq!(units_per_em(&head_with_units_per_em(16384)), Some(16384));
+        assert_eq!(units_per_em(&head_with_units_per_em(16385)), None);
+        assert_eq!(units_per_em(&[]), None);
+    }
+
+    #[test]
+    fn magic_number() {
+        let mut data = [0u8; raw::TABLE_SIZE];
+        assert!(parse(&data).is_none());
+
+        let offset = raw::MAGIC_NUMBER_OFFSET;
+        data[offset..offset + 4].copy_from_slice(&MAGIC_NUMBER.to_be_bytes());
+        assert!(parse(&data).is_some());
+        assert!(parse(&data[1..]).is_none());
+    }
+
+    #[test]
+    fn style_and_flags() {
+        let mut data = [0u8; raw::TABLE_SIZE];
+        data[raw::FLAGS_OFFSET..raw::FLAGS_OFFSET + 2].copy_from_slice(&0x000Bu16.to_be_bytes());
+        data[raw::MAC_STYLE_OFFSET..raw::MAC_STYLE_OFFSET + 2].copy_from_slice(&2u16.to_be_bytes());
+
+        let style = mac_style(&data).unwrap();
+        assert_eq!(style.bits(), 2);
+        assert!(!style.is_bold());
+        assert!(style.is_italic());
+        assert_eq!(flags(&data), Some(0x000B));
+        assert_eq!(glyph_data_format(&data), Some(0));
+    }
+
+    #[test]
+    fn timestamps() {
+        let mut data = [0u8; raw::TABLE_SIZE];
+        // 2020-01-01T00:00:00Z
+        let seconds = 1_577_836_800i64 + 2_082_844_800;
+        data[raw::CREATED_OFFSET..raw::CREATED_OFFSET + 8].copy_from_slice(&seconds.to_be_bytes());
+        data[raw::MODIFIED_OFFSET..raw::MODIFIED_OFFSET + 8].copy_from_slice(&(-1i64).to_be_bytes());
+
+        assert_eq!(created(&data), Some(seconds));
+        assert_eq!(modified(&data), Some(-1));
+
+        let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_577_836_800);
+        assert_eq!(to_system_time(seconds), Some(time));
+
+        // Before the Unix epoch.
+        let time = std::time::UNIX_EPOCH - std::time::Duration::from_secs(2_082_844_800);
+        assert_eq!(to_system_time(0), Some(time));
+
+        assert_eq!(to_system_time(core::i64::MIN), None);
+    }
+}
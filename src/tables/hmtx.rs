@@ -24,8 +24,10 @@ impl<'a> Table<'a> {
         // 'If the number_of_hmetrics is less than the total number of glyphs,
         // then that array is followed by an array for the left side bearing values
         // of the remaining glyphs.'
+        //
+        // A truncated array is an error, otherwise bearings for some glyphs will be lost.
         let bearings = if number_of_hmetrics < number_of_glyphs {
-            s.read_array16(number_of_glyphs.get() - number_of_hmetrics.get())
+            Some(s.read_array16(number_of_glyphs.get() - number_of_hmetrics.get())?)
         } else {
             None
         };
@@ -48,29 +50,95 @@ impl<'a> Table<'a> {
         }
     }
 
+    /// Returns an iterator over advances of all glyphs.
+    #[inline]
+    pub fn advances(&self, number_of_glyphs: NonZeroU16) -> GlyphAdvances<'a> {
+        GlyphAdvances {
+            metrics: self.metrics,
+            index: 0,
+            total: number_of_glyphs.get(),
+        }
+    }
+
     #[inline]
     pub fn side_bearing(&self, glyph_id: GlyphId) -> Option<i16> {
         if let Some(metrics) = self.metrics.get(glyph_id.0) {
             Some(metrics.lsb())
-        } else if let Some(bearings) = self.bearings {
-            // 'If the number_of_hmetrics is less than the total number of glyphs,
-            // then that array is followed by an array for the left side bearing values
-            // of the remaining glyphs.'
+        } else {
+            self.extra_side_bearing(glyph_id)
+        }
+    }
 
-            let number_of_hmetrics = self.metrics.len();
+    /// Returns an advance and a side bearing using a single metrics lookup.
+    ///
+    /// Applies the same fallback rules as `advance()` and `side_bearing()`.
+    #[inline]
+    pub fn metrics(&self, glyph_id: GlyphId) -> Option<(u16, i16)> {
+        if let Some(metrics) = self.metrics.get(glyph_id.0) {
+            Some((metrics.advance_width(), metrics.lsb()))
+        } else {
+            let advance = self.metrics.last()?.advance_width();
+            Some((advance, self.extra_side_bearing(glyph_id)?))
+        }
+    }
 
-            // Check for overflow.
-            if glyph_id.0 < number_of_hmetrics {
-                return None;
-            }
+    #[inline]
+    fn extra_side_bearing(&self, glyph_id: GlyphId) -> Option<i16> {
+        // 'If the number_of_hmetrics is less than the total number of glyphs,
+        // then that array is followed by an array for the left side bearing values
+        // of the remaining glyphs.'
+        let bearings = self.bearings?;
 
-            bearings.get(glyph_id.0 - number_of_hmetrics)
-        } else {
-            None
+        let number_of_hmetrics = self.metrics.len();
+
+        // Check for overflow.
+        if glyph_id.0 < number_of_hmetrics {
+            return None;
         }
+
+        bearings.get(glyph_id.0 - number_of_hmetrics)
     }
 }
 
+
+/// An iterator over glyph advances.
+///
+/// Yields an advance for each glyph ID in order.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct GlyphAdvances<'a> {
+    metrics: LazyArray16<'a, raw::HorizontalMetrics>,
+    index: u16,
+    total: u16,
+}
+
+impl<'a> Iterator for GlyphAdvances<'a> {
+    type Item = u16;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.total {
+            return None;
+        }
+
+        let metrics = match self.metrics.get(self.index) {
+            Some(metrics) => metrics,
+            // The last record applies to all remaining glyphs.
+            None => self.metrics.last()?,
+        };
+
+        self.index += 1;
+        Some(metrics.advance_width())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::from(self.total - self.index);
+        (len, Some(len))
+    }
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,6 +181,20 @@ mod tests {
         assert_eq!(table.side_bearing(GlyphId(1)), Some(3));
     }
 
+    #[test]
+    fn metrics() {
+        let data = writer::convert(&[
+            UInt16(1), // advanceWidth[0]
+            Int16(2), // sideBearing[0]
+            Int16(3), // sideBearing[1]
+        ]);
+
+        let table = Table::parse(&data, nzu16!(1), nzu16!(2)).unwrap();
+        assert_eq!(table.metrics(GlyphId(0)), Some((1, 2)));
+        assert_eq!(table.metrics(GlyphId(1)), Some((1, 3)));
+        assert_eq!(table.metrics(GlyphId(2)), None);
+    }
+
     #[test]
     fn less_metrics_than_glyphs() {
         let data = writer::convert(&[
@@ -128,4 +210,32 @@ mod tests {
         assert_eq!(table.side_bearing(GlyphId(1)), Some(4));
         assert_eq!(table.side_bearing(GlyphId(2)), None);
     }
+
+    #[test]
+    fn advances() {
+        let data = writer::convert(&[
+            UInt16(1), // advanceWidth[0]
+            Int16(2), // sideBearing[0]
+            UInt16(3), // advanceWidth[1]
+            Int16(4), // sideBearing[1]
+            Int16(5), // sideBearing[2]
+            Int16(6), // sideBearing[3]
+        ]);
+
+        let table = Table::parse(&data, nzu16!(2), nzu16!(4)).unwrap();
+        let advances: std::vec::Vec<u16> = table.advances(nzu16!(4)).collect();
+        assert_eq!(advances, &[1, 3, 3, 3]);
+    }
+
+    #[test]
+    fn truncated_bearings() {
+        let data = writer::convert(&[
+            UInt16(1), // advanceWidth[0]
+            Int16(2), // sideBearing[0]
+            Int16(3), // sideBearing[1]
+            // sideBearing[2] is missing
+        ]);
+
+        assert!(Table::parse(&data, nzu16!(1), nzu16!(3)).is_none());
+    }
 }
@@ -108,7 +108,90 @@ pub(crate) fn glyph_side_bearing_offset(
     glyph_id: GlyphId,
     coordinates: &[NormalizedCoord],
 ) -> Option<f32> {
-    let set_data = table.data.get(table.lsb_mapping_offset?.to_usize()..)?;
+    // Unlike advances, side bearings have no implicit mapping,
+    // so they are not varied without an explicit one.
+    let offset = match table.lsb_mapping_offset {
+        Some(offset) => offset,
+        None => return Some(0.0),
+    };
+
+    let set_data = table.data.get(offset.to_usize()..)?;
     let (outer_idx, inner_idx) = DeltaSetIndexMap::new(set_data).map(glyph_id)?;
     table.variation_store.parse_delta(outer_idx, inner_idx, coordinates)
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn table_data(advance_width_mapping_offset: u32) -> std::vec::Vec<u8> {
+        writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt32(20), // itemVariationStoreOffset
+            UInt32(advance_width_mapping_offset), // advanceWidthMappingOffset
+            UInt32(0), // lsbMappingOffset
+            UInt32(0), // rsbMappingOffset
+            // ItemVariationStore
+            UInt16(1), // format
+            UInt32(12), // variationRegionListOffset
+            UInt16(1), // itemVariationDataCount
+            UInt32(22), // itemVariationDataOffsets[0]
+            // VariationRegionList
+            UInt16(1), // axisCount
+            UInt16(1), // regionCount
+            Int16(0), Int16(16384), Int16(16384), // region 0
+            // ItemVariationData
+            UInt16(3), // itemCount
+            UInt16(1), // wordDeltaCount
+            UInt16(1), // regionIndexCount
+            UInt16(0), // regionIndexes
+            Int16(10), Int16(-20), Int16(30), // deltas
+            // DeltaSetIndexMap
+            UInt16(0x0007), // entryFormat: 1 byte entries, 8 bits inner index
+            UInt16(2), // mapCount
+            UInt8(2), UInt8(1), // mapData
+        ])
+    }
+
+    #[test]
+    fn implicit_advance_mapping() {
+        let data = table_data(0);
+        let table = Table::parse(&data).unwrap();
+        let coords = [NormalizedCoord::from(0.5)];
+        assert_eq!(glyph_advance_offset(table, GlyphId(0), &coords), Some(5.0));
+        assert_eq!(glyph_advance_offset(table, GlyphId(2), &coords), Some(15.0));
+        assert_eq!(glyph_advance_offset(table, GlyphId(3), &coords), None);
+    }
+
+    #[test]
+    fn advance_mapping() {
+        let data = table_data(56);
+        let table = Table::parse(&data).unwrap();
+        let coords = [NormalizedCoord::from(0.5)];
+        assert_eq!(glyph_advance_offset(table, GlyphId(0), &coords), Some(15.0));
+        assert_eq!(glyph_advance_offset(table, GlyphId(1), &coords), Some(-10.0));
+        // The last entry is used for glyphs outside the map.
+        assert_eq!(glyph_advance_offset(table, GlyphId(5), &coords), Some(-10.0));
+    }
+
+    #[test]
+    fn side_bearing_mapping() {
+        let coords = [NormalizedCoord::from(0.5)];
+
+        // No lsbMapping.
+        let data = table_data(0);
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_side_bearing_offset(table, GlyphId(0), &coords), Some(0.0));
+        assert_eq!(glyph_side_bearing_offset(table, GlyphId(5), &coords), Some(0.0));
+
+        // Use the advance mapping data as lsbMapping.
+        let mut data = table_data(0);
+        data[12..16].copy_from_slice(&56u32.to_be_bytes()); // lsbMappingOffset
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(glyph_side_bearing_offset(table, GlyphId(0), &coords), Some(15.0));
+        assert_eq!(glyph_side_bearing_offset(table, GlyphId(1), &coords), Some(-10.0));
+    }
+}
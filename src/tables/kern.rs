@@ -1,57 +1,254 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/kern
+// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kern.html
 
 use crate::GlyphId;
-use crate::parser::Stream;
-use crate::raw::kern::*;
+use crate::parser::{Stream, NumFrom};
+use crate::raw::kern::KerningRecord;
 
 
+// The only difference between the OpenType and Apple subtables
+// is a header and coverage flags.
+#[derive(Clone, Copy)]
+struct Coverage {
+    horizontal: bool,
+    cross_stream: bool,
+    variable: bool,
+    minimum: bool,
+    override_: bool,
+    format: u8,
+}
+
+impl Coverage {
+    #[inline]
+    fn from_ot(n: u16) -> Self {
+        Coverage {
+            horizontal: n & 0x0001 != 0,
+            minimum: n & 0x0002 != 0,
+            cross_stream: n & 0x0004 != 0,
+            override_: n & 0x0008 != 0,
+            variable: false,
+            format: (n >> 8) as u8,
+        }
+    }
+
+    #[inline]
+    fn from_apple(n: u16) -> Self {
+        Coverage {
+            horizontal: n & 0x8000 == 0,
+            cross_stream: n & 0x4000 != 0,
+            variable: n & 0x2000 != 0,
+            minimum: false,
+            override_: false,
+            format: (n & 0x00FF) as u8,
+        }
+    }
+
+    #[inline]
+    fn is_horizontal_kerning(&self) -> bool {
+        self.horizontal && !self.cross_stream && !self.variable && !self.minimum
+    }
+}
+
+
+/// Returns a horizontal kerning of a glyphs pair.
+///
+/// All format 0 subtables with a horizontal kerning are checked.
+/// Values are accumulated, unless a subtable has the override flag set.
 pub fn glyphs_kerning(kern_table: &[u8], glyph_id1: GlyphId, glyph_id2: GlyphId) -> Option<i16> {
     let mut s = Stream::new(kern_table);
 
+    // The OpenType `kern` table has a u16 version set to 0,
+    // while the Apple one has a u32 version set to 0x00010000.
     let version: u16 = s.read()?;
-    if version != 0 {
-        return None;
-    }
+    let (is_apple, number_of_subtables) = match version {
+        0 => (false, u32::from(s.read::<u16>()?)),
+        1 => {
+            s.skip::<u16>(); // the second half of the version
+            (true, s.read::<u32>()?)
+        }
+        _ => return None,
+    };
 
-    let number_of_subtables: u16 = s.read()?;
+    let mut kerning: Option<i16> = None;
+    for _ in 0..number_of_subtables {
+        // The next subtable cannot be located after a malformed one,
+        // so keep the kerning that was already accumulated.
+        let (coverage, data) = match parse_subtable(&mut s, is_apple) {
+            Some(v) => v,
+            None => break,
+        };
 
-    // TODO: Technically, we have to iterate over all tables,
-    //       but I'm not sure how exactly this should be implemented.
-    //       Also, I have to find a font, that actually has more that one table.
-    if number_of_subtables == 0 {
-        return None;
+        if !coverage.is_horizontal_kerning() || coverage.format != 0 {
+            continue;
+        }
+
+        if let Some(value) = parse_format0(data, glyph_id1, glyph_id2) {
+            kerning = if coverage.override_ {
+                Some(value)
+            } else {
+                Some(kerning.unwrap_or(0).saturating_add(value))
+            };
+        }
     }
 
-    s.skip::<u16>(); // subtable_version
-    s.skip::<u16>(); // length
-    let coverage: Coverage = s.read()?;
+    kerning
+}
 
-    if !coverage.is_horizontal() {
-        return None;
-    }
+fn parse_subtable<'a>(s: &mut Stream<'a>, is_apple: bool) -> Option<(Coverage, &'a [u8])> {
+    let (len, coverage, header_len) = if is_apple {
+        let len: u32 = s.read()?;
+        let coverage = Coverage::from_apple(s.read()?);
+        s.skip::<u16>(); // tuple_index
+        (usize::num_from(len), coverage, 8)
+    } else {
+        s.skip::<u16>(); // subtable_version
+        let len: u16 = s.read()?;
+        let coverage = Coverage::from_ot(s.read()?);
+        (usize::from(len), coverage, 6)
+    };
 
-    if coverage.format() != 0 {
-        return None;
-    }
+    let data = if coverage.format == 0 && !is_apple {
+        // A format 0 subtable length is stored as u16 and can overflow
+        // in OpenType fonts with a lot of pairs. So we have to calculate it manually.
+        let mut s2 = *s;
+        let number_of_pairs: u16 = s2.read()?;
+        let len = 8 + usize::from(number_of_pairs) * KerningRecord::SIZE;
+        s.read_bytes(len)?
+    } else {
+        s.read_bytes(len.checked_sub(header_len)?)?
+    };
 
-    parse_format1(&mut s, glyph_id1, glyph_id2)
+    Some((coverage, data))
 }
 
-fn parse_format1(s: &mut Stream, glyph_id1: GlyphId, glyph_id2: GlyphId) -> Option<i16> {
+fn parse_format0(data: &[u8], glyph_id1: GlyphId, glyph_id2: GlyphId) -> Option<i16> {
+    let mut s = Stream::new(data);
     let number_of_pairs: u16 = s.read()?;
     s.advance(6); // search_range (u16) + entry_selector (u16) + range_shift (u16)
     let pairs = s.read_array16::<KerningRecord>(number_of_pairs)?;
 
     let needle = u32::from(glyph_id1.0) << 16 | u32::from(glyph_id2.0);
-    pairs.binary_search_by(|v| v.pair().cmp(&needle)).map(|(_, v)| v.value())
+    pairs.binary_search_by_key(&needle, |v| v.pair()).map(|(_, v)| v.value())
 }
 
-impl Coverage {
-    const HORIZONTAL_BIT: u8 = 0;
 
-    #[inline]
-    fn is_horizontal(&self) -> bool {
-        (self.coverage() >> Coverage::HORIZONTAL_BIT) & 1 == 1
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    type Pairs<'a> = &'a [(u16, u16, i16)];
+
+    fn format0(pairs: Pairs) -> std::vec::Vec<u8> {
+        let mut data = writer::convert(&[
+            UInt16(pairs.len() as u16), // nPairs
+            UInt16(0), // searchRange
+            UInt16(0), // entrySelector
+            UInt16(0), // rangeShift
+        ]);
+
+        for (left, right, value) in pairs {
+            let record = writer::convert(&[UInt16(*left), UInt16(*right), Int16(*value)]);
+            data.extend_from_slice(&record);
+        }
+
+        data
     }
-}
 
+    fn ot_table(subtables: &[(u16, Pairs)]) -> std::vec::Vec<u8> {
+        let mut data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(subtables.len() as u16), // nTables
+        ]);
+
+        for (coverage, pairs) in subtables {
+            let subtable = format0(pairs);
+            data.extend_from_slice(&writer::convert(&[
+                UInt16(0), // version
+                UInt16(subtable.len() as u16 + 6), // length
+                UInt16(*coverage), // coverage
+            ]));
+            data.extend_from_slice(&subtable);
+        }
+
+        data
+    }
+
+    #[test]
+    fn ot_format0() {
+        let data = ot_table(&[(0x0001, &[(1, 2, -50), (1, 3, 20), (4, 1, -10)])]);
+        assert_eq!(glyphs_kerning(&data, GlyphId(1), GlyphId(2)), Some(-50));
+        assert_eq!(glyphs_kerning(&data, GlyphId(1), GlyphId(3)), Some(20));
+        assert_eq!(glyphs_kerning(&data, GlyphId(4), GlyphId(1)), Some(-10));
+        assert_eq!(glyphs_kerning(&data, GlyphId(2), GlyphId(1)), None);
+    }
+
+    #[test]
+    fn ot_multiple_subtables() {
+        let data = ot_table(&[
+            (0x0001, &[(1, 2, -50), (1, 3, 20)]),
+            (0x0000, &[(1, 2, -100)]), // vertical
+            (0x0005, &[(1, 2, -100)]), // cross-stream
+            (0x0003, &[(1, 2, -100)]), // minimum
+            (0x0001, &[(1, 2, -5), (4, 5, 30)]),
+            (0x0009, &[(1, 3, 10)]), // override
+        ]);
+
+        assert_eq!(glyphs_kerning(&data, GlyphId(1), GlyphId(2)), Some(-55));
+        assert_eq!(glyphs_kerning(&data, GlyphId(1), GlyphId(3)), Some(10));
+        assert_eq!(glyphs_kerning(&data, GlyphId(4), GlyphId(5)), Some(30));
+        assert_eq!(glyphs_kerning(&data, GlyphId(5), GlyphId(4)), None);
+    }
+
+    #[test]
+    fn ot_length_overflow() {
+        // The length field is u16 and can overflow for large subtables.
+        let mut data = ot_table(&[(0x0001, &[(1, 2, -50)]), (0x0001, &[(1, 2, -5)])]);
+        data[6..8].copy_from_slice(&[0, 4]);
+        assert_eq!(glyphs_kerning(&data, GlyphId(1), GlyphId(2)), Some(-55));
+    }
+
+    #[test]
+    fn ot_malformed_subtable() {
+        let mut data = ot_table(&[(0x0001, &[(1, 2, -50)]), (0x0001, &[(1, 2, -5)])]);
+        // Truncate the second subtable.
+        let len = data.len();
+        data.truncate(len - 2);
+        assert_eq!(glyphs_kerning(&data, GlyphId(1), GlyphId(2)), Some(-50));
+    }
+
+    #[test]
+    fn apple_format0() {
+        let subtable = format0(&[(1, 2, -50)]);
+        let mut data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt32(2), // nTables
+            // Subtable 0
+            UInt32(subtable.len() as u32 + 8), // length
+            UInt16(0x0000), // coverage
+            UInt16(0), // tupleIndex
+        ]);
+        data.extend_from_slice(&subtable);
+        data.extend_from_slice(&writer::convert(&[
+            // Subtable 1
+            UInt32(subtable.len() as u32 + 8), // length
+            UInt16(0x8000), // coverage: vertical
+            UInt16(0), // tupleIndex
+        ]));
+        data.extend_from_slice(&subtable);
+
+        assert_eq!(glyphs_kerning(&data, GlyphId(1), GlyphId(2)), Some(-50));
+        assert_eq!(glyphs_kerning(&data, GlyphId(2), GlyphId(1)), None);
+    }
+
+    #[test]
+    fn invalid_version() {
+        let data = writer::convert(&[
+            UInt16(2), // version
+            UInt16(0), // nTables
+        ]);
+
+        assert_eq!(glyphs_kerning(&data, GlyphId(1), GlyphId(2)), None);
+    }
+}
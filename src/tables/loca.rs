@@ -4,12 +4,12 @@ use core::num::NonZeroU16;
 use core::ops::Range;
 
 use crate::{GlyphId, IndexToLocationFormat};
-use crate::parser::{Stream, LazyArray16, NumFrom};
+use crate::parser::{Stream, LazyArray32, NumFrom};
 
 #[derive(Clone, Copy)]
 pub(crate) enum Table<'a> {
-    Short(LazyArray16<'a, u16>),
-    Long(LazyArray16<'a, u32>),
+    Short(LazyArray32<'a, u16>),
+    Long(LazyArray32<'a, u32>),
 }
 
 impl<'a> Table<'a> {
@@ -19,27 +19,22 @@ impl<'a> Table<'a> {
         format: IndexToLocationFormat,
     ) -> Option<Self> {
         // The number of ranges is `maxp.numGlyphs + 1`.
-        //
-        // Check for overflow first.
-        let total = if number_of_glyphs.get() == core::u16::MAX {
-            number_of_glyphs.get()
-        } else {
-            number_of_glyphs.get() + 1
-        };
+        // Which doesn't fit into u16 when a font has 65535 glyphs.
+        let total = u32::from(number_of_glyphs.get()) + 1;
 
         let mut s = Stream::new(data);
         match format {
             IndexToLocationFormat::Short => {
-                Some(Table::Short(s.read_array16(total)?))
+                Some(Table::Short(s.read_array32(total)?))
             }
             IndexToLocationFormat::Long => {
-                Some(Table::Long(s.read_array16(total)?))
+                Some(Table::Long(s.read_array32(total)?))
             }
         }
     }
 
     #[inline]
-    fn len(&self) -> u16 {
+    fn len(&self) -> u32 {
         match self {
             Table::Short(ref array) => array.len(),
             Table::Long(ref array) => array.len(),
@@ -47,11 +42,8 @@ impl<'a> Table<'a> {
     }
 
     #[inline]
-    pub fn glyph_range(&self, glyph_id: GlyphId) -> Option<Range<usize>> {
-        let glyph_id = glyph_id.0;
-        if glyph_id == core::u16::MAX {
-            return None;
-        }
+    fn offsets(&self, glyph_id: GlyphId) -> Option<Range<usize>> {
+        let glyph_id = u32::from(glyph_id.0);
 
         // Glyph ID must be smaller than total number of values in a `loca` array.
         if glyph_id + 1 >= self.len() {
@@ -68,6 +60,23 @@ impl<'a> Table<'a> {
             }
         };
 
+        Some(range)
+    }
+
+    /// Checks that a glyph has no data, like a space.
+    ///
+    /// Returns `false` for glyphs outside the table.
+    #[inline]
+    pub fn is_empty_glyph(&self, glyph_id: GlyphId) -> bool {
+        match self.offsets(glyph_id) {
+            Some(range) => range.start == range.end,
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub fn glyph_range(&self, glyph_id: GlyphId) -> Option<Range<usize>> {
+        let range = self.offsets(glyph_id)?;
         if range.start >= range.end {
             // 'The offsets must be in ascending order.'
             // And range cannot be empty.
@@ -77,3 +86,73 @@ impl<'a> Table<'a> {
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn short_format() {
+        let data = writer::convert(&[
+            UInt16(0), // offset [0]
+            UInt16(5), // offset [1]
+            UInt16(5), // offset [2]
+            UInt16(12), // offset [3]
+        ]);
+
+        let number_of_glyphs = NonZeroU16::new(3).unwrap();
+        let table = Table::parse(&data, number_of_glyphs, IndexToLocationFormat::Short).unwrap();
+        // Offsets are stored divided by 2.
+        assert_eq!(table.glyph_range(GlyphId(0)), Some(0..10));
+        // An empty glyph.
+        assert_eq!(table.glyph_range(GlyphId(1)), None);
+        assert_eq!(table.glyph_range(GlyphId(2)), Some(10..24));
+        assert_eq!(table.glyph_range(GlyphId(3)), None);
+
+        assert!(!table.is_empty_glyph(GlyphId(0)));
+        assert!(table.is_empty_glyph(GlyphId(1)));
+        assert!(!table.is_empty_glyph(GlyphId(3)));
+    }
+
+    #[test]
+    fn long_format() {
+        let data = writer::convert(&[
+            UInt32(0), // offset [0]
+            UInt32(70000), // offset [1]
+            UInt32(60000), // offset [2]
+        ]);
+
+        let number_of_glyphs = NonZeroU16::new(2).unwrap();
+        let table = Table::parse(&data, number_of_glyphs, IndexToLocationFormat::Long).unwrap();
+        assert_eq!(table.glyph_range(GlyphId(0)), Some(0..70000));
+        // Offsets are not in ascending order.
+        assert_eq!(table.glyph_range(GlyphId(1)), None);
+    }
+
+    #[test]
+    fn not_enough_offsets() {
+        let data = writer::convert(&[
+            UInt16(0), // offset [0]
+            UInt16(5), // offset [1]
+        ]);
+
+        let number_of_glyphs = NonZeroU16::new(2).unwrap();
+        assert!(Table::parse(&data, number_of_glyphs, IndexToLocationFormat::Short).is_none());
+    }
+
+    #[test]
+    fn max_number_of_glyphs() {
+        let mut data = std::vec::Vec::new();
+        for i in 0..=u32::from(core::u16::MAX) {
+            data.extend_from_slice(&writer::convert(&[UInt32(i * 4)]));
+        }
+
+        let number_of_glyphs = NonZeroU16::new(core::u16::MAX).unwrap();
+        let table = Table::parse(&data, number_of_glyphs, IndexToLocationFormat::Long).unwrap();
+        assert_eq!(table.glyph_range(GlyphId(core::u16::MAX - 1)), Some(262136..262140));
+        assert_eq!(table.glyph_range(GlyphId(core::u16::MAX)), None);
+    }
+}
@@ -4,14 +4,102 @@ use core::num::NonZeroU16;
 
 use crate::parser::Stream;
 
-// We care only about `numGlyphs`.
+const VERSION_0_5: u32 = 0x00005000;
+const VERSION_1_0: u32 = 0x00010000;
+
+const MAX_POINTS_OFFSET: usize = 6;
+const MAX_COMPONENT_ELEMENTS_OFFSET: usize = 28;
+const MAX_COMPONENT_DEPTH_OFFSET: usize = 30;
+
 pub fn parse(data: &[u8]) -> Option<NonZeroU16> {
     let mut s = Stream::new(data);
     let version: u32 = s.read()?;
-    if !(version == 0x00005000 || version == 0x00010000) {
+    if !(version == VERSION_0_5 || version == VERSION_1_0) {
         return None;
     }
 
     let n: u16 = s.read()?;
     NonZeroU16::new(n)
 }
+
+#[inline]
+pub fn max_points(data: &[u8]) -> Option<u16> {
+    read_version1_field(data, MAX_POINTS_OFFSET)
+}
+
+#[inline]
+pub fn max_component_elements(data: &[u8]) -> Option<u16> {
+    read_version1_field(data, MAX_COMPONENT_ELEMENTS_OFFSET)
+}
+
+#[inline]
+pub fn max_component_depth(data: &[u8]) -> Option<u16> {
+    read_version1_field(data, MAX_COMPONENT_DEPTH_OFFSET)
+}
+
+// Only the version 1.0 table has fields after `numGlyphs`.
+fn read_version1_field(data: &[u8], offset: usize) -> Option<u16> {
+    let version: u32 = Stream::read_at(data, 0)?;
+    if version != VERSION_1_0 {
+        return None;
+    }
+
+    Stream::read_at(data, offset)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn version_0_5() {
+        let data = writer::convert(&[
+            UInt32(0x00005000), // version
+            UInt16(10), // numGlyphs
+        ]);
+
+        assert_eq!(parse(&data), NonZeroU16::new(10));
+        assert_eq!(max_points(&data), None);
+        assert_eq!(max_component_elements(&data), None);
+        assert_eq!(max_component_depth(&data), None);
+    }
+
+    #[test]
+    fn version_1_0() {
+        let data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(10), // numGlyphs
+            UInt16(120), // maxPoints
+            UInt16(0), // maxContours
+            UInt16(0), // maxCompositePoints
+            UInt16(0), // maxCompositeContours
+            UInt16(0), // maxZones
+            UInt16(0), // maxTwilightPoints
+            UInt16(0), // maxStorage
+            UInt16(0), // maxFunctionDefs
+            UInt16(0), // maxInstructionDefs
+            UInt16(0), // maxStackElements
+            UInt16(0), // maxSizeOfInstructions
+            UInt16(3), // maxComponentElements
+            UInt16(2), // maxComponentDepth
+        ]);
+
+        assert_eq!(parse(&data), NonZeroU16::new(10));
+        assert_eq!(max_points(&data), Some(120));
+        assert_eq!(max_component_elements(&data), Some(3));
+        assert_eq!(max_component_depth(&data), Some(2));
+    }
+
+    #[test]
+    fn zero_glyphs() {
+        let data = writer::convert(&[
+            UInt32(0x00005000), // version
+            UInt16(0), // numGlyphs
+        ]);
+
+        assert_eq!(parse(&data), None);
+    }
+}
@@ -4,9 +4,14 @@ pub mod cblc;
 pub mod cff2;
 pub mod cff;
 pub mod cmap;
+pub mod colr;
+pub mod cpal;
+pub mod cvar;
 pub mod fvar;
 pub mod gdef;
 pub mod glyf;
+pub mod gpos;
+pub mod gsub;
 pub mod gvar;
 pub mod head;
 pub mod hhea;
@@ -43,7 +43,7 @@ impl<'a> Table<'a> {
     }
 
     pub fn metrics_offset(&self, tag: Tag, coordinates: &[NormalizedCoord]) -> Option<f32> {
-        let (_, record) = self.records.binary_search_by(|r| r.value_tag().cmp(&tag))?;
+        let (_, record) = self.records.binary_search_by_key(&tag, |r| r.value_tag())?;
         self.variation_store.parse_delta(
             record.delta_set_outer_index(),
             record.delta_set_inner_index(),
@@ -1,14 +1,11 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/name
 
-#[cfg(feature = "std")]
-use std::vec::Vec;
 #[cfg(feature = "std")]
 use std::string::String;
 
-#[cfg(feature = "std")]
-use crate::parser::LazyArray16;
+use core::convert::TryFrom;
 
-use crate::parser::Stream;
+use crate::parser::{Stream, LazyArray16, Offset, Offset16};
 use crate::raw::name as raw;
 
 
@@ -70,7 +67,54 @@ impl PlatformId {
 }
 
 
-#[cfg(feature = "std")]
+// https://docs.microsoft.com/en-us/typography/opentype/spec/name#macintosh-encoding-ids-script-manager-codes
+pub(crate) const MACINTOSH_ROMAN_ENCODING_ID: u16 = 0;
+
+/// Unicode code points of the upper half of the Mac OS Roman character set.
+///
+/// The lower half is identical to ASCII.
+const MAC_ROMAN: [u16; 128] = [
+    0x00C4, 0x00C5, 0x00C7, 0x00C9, 0x00D1, 0x00D6, 0x00DC, 0x00E1,
+    0x00E0, 0x00E2, 0x00E4, 0x00E3, 0x00E5, 0x00E7, 0x00E9, 0x00E8,
+    0x00EA, 0x00EB, 0x00ED, 0x00EC, 0x00EE, 0x00EF, 0x00F1, 0x00F3,
+    0x00F2, 0x00F4, 0x00F6, 0x00F5, 0x00FA, 0x00F9, 0x00FB, 0x00FC,
+    0x2020, 0x00B0, 0x00A2, 0x00A3, 0x00A7, 0x2022, 0x00B6, 0x00DF,
+    0x00AE, 0x00A9, 0x2122, 0x00B4, 0x00A8, 0x2260, 0x00C6, 0x00D8,
+    0x221E, 0x00B1, 0x2264, 0x2265, 0x00A5, 0x00B5, 0x2202, 0x2211,
+    0x220F, 0x03C0, 0x222B, 0x00AA, 0x00BA, 0x03A9, 0x00E6, 0x00F8,
+    0x00BF, 0x00A1, 0x00AC, 0x221A, 0x0192, 0x2248, 0x2206, 0x00AB,
+    0x00BB, 0x2026, 0x00A0, 0x00C0, 0x00C3, 0x00D5, 0x0152, 0x0153,
+    0x2013, 0x2014, 0x201C, 0x201D, 0x2018, 0x2019, 0x00F7, 0x25CA,
+    0x00FF, 0x0178, 0x2044, 0x20AC, 0x2039, 0x203A, 0xFB01, 0xFB02,
+    0x2021, 0x00B7, 0x201A, 0x201E, 0x2030, 0x00C2, 0x00CA, 0x00C1,
+    0x00CB, 0x00C8, 0x00CD, 0x00CE, 0x00CF, 0x00CC, 0x00D3, 0x00D4,
+    0xF8FF, 0x00D2, 0x00DA, 0x00DB, 0x00D9, 0x0131, 0x02C6, 0x02DC,
+    0x00AF, 0x02D8, 0x02D9, 0x02DA, 0x00B8, 0x02DD, 0x02DB, 0x02C7,
+];
+
+#[inline]
+fn mac_roman_to_char(b: u8) -> char {
+    if b < 128 {
+        char::from(b)
+    } else {
+        // All Mac OS Roman characters are inside the BMP.
+        core::char::from_u32(u32::from(MAC_ROMAN[usize::from(b - 128)]))
+            .unwrap_or(core::char::REPLACEMENT_CHARACTER)
+    }
+}
+
+pub(crate) fn char_to_mac_roman(c: char) -> Option<u8> {
+    let code = u32::from(c);
+    if code < 128 {
+        return Some(code as u8);
+    }
+
+    let code = u16::try_from(code).ok()?;
+    let index = MAC_ROMAN.iter().position(|v| *v == code)?;
+    Some(128 + index as u8)
+}
+
+
 #[inline]
 fn is_unicode_encoding(platform_id: PlatformId, encoding_id: u16) -> bool {
     // https://docs.microsoft.com/en-us/typography/opentype/spec/name#windows-encoding-ids
@@ -89,6 +133,7 @@ fn is_unicode_encoding(platform_id: PlatformId, encoding_id: u16) -> bool {
 pub struct Name<'a> {
     data: raw::NameRecord,
     strings: &'a [u8],
+    lang_tags: LazyArray16<'a, raw::LangTagRecord>,
 }
 
 impl<'a> Name<'a> {
@@ -123,42 +168,189 @@ impl<'a> Name<'a> {
         self.strings.get(start..end).unwrap_or(&[])
     }
 
+    /// Returns the language-tag referenced by the language ID.
+    ///
+    /// Language-tags are stored only in the `name` table format 1 and are used
+    /// when the language ID is 0x8000 or greater.
+    ///
+    /// The tag is stored as an UTF-16BE IETF BCP 47 string.
+    pub fn language_tag(&self) -> Option<&'a [u8]> {
+        // https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1
+        const LANG_TAG_BASE_ID: u16 = 0x8000;
+
+        let index = self.language_id().checked_sub(LANG_TAG_BASE_ID)?;
+        let record = self.lang_tags.get(index)?;
+        let start = usize::from(record.offset());
+        let end = start + usize::from(record.length());
+        self.strings.get(start..end)
+    }
+
+    /// Parses the language-tag as a UTF-8 string.
+    ///
+    /// See `language_tag()` for details.
+    #[cfg(feature = "std")]
+    #[inline(never)]
+    pub fn language_tag_utf8(&self) -> Option<String> {
+        utf16_be_to_string(self.language_tag()?)
+    }
+
+    /// Returns an iterator over the Name's characters.
+    ///
+    /// Unlike `name_utf8()`, doesn't allocate and is available in `no_std`.
+    ///
+    /// Supports the same encodings as `name_utf8()`. For unsupported encodings
+    /// the iterator will be empty. Malformed UTF-16 sequences are replaced with
+    /// `U+FFFD REPLACEMENT CHARACTER`.
+    #[inline]
+    pub fn chars(&self) -> NameChars<'a> {
+        let encoding = if self.is_unicode() {
+            NameEncoding::Utf16Be
+        } else if self.is_mac_roman() {
+            NameEncoding::MacRoman
+        } else {
+            NameEncoding::Unsupported
+        };
+
+        NameChars {
+            data: self.name(),
+            offset: 0,
+            encoding,
+        }
+    }
+
     /// Parses the Name's data as a UTF-8 string.
     ///
-    /// Only Unicode names are supported. And since they are stored as UTF-16BE,
-    /// we can't return `&str` and have to allocate a `String`.
+    /// Only Unicode and Mac OS Roman names are supported. And since they are not stored
+    /// as UTF-8, we can't return `&str` and have to allocate a `String`.
     ///
     /// Supports:
     /// - Unicode Platform ID
     /// - Windows Platform ID + Unicode BMP
+    /// - Macintosh Platform ID + Roman
+    ///
+    /// Malformed UTF-16 sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`.
     #[cfg(feature = "std")]
     #[inline(never)]
     pub fn name_utf8(&self) -> Option<String> {
         if self.is_unicode() {
             self.name_from_utf16_be()
+        } else if self.is_mac_roman() {
+            Some(self.name_from_mac_roman())
         } else {
             None
         }
     }
 
-    #[cfg(feature = "std")]
     #[inline]
     pub(crate) fn is_unicode(&self) -> bool {
-        is_unicode_encoding(self.platform_id().unwrap(), self.encoding_id())
+        match self.platform_id() {
+            Some(platform_id) => is_unicode_encoding(platform_id, self.encoding_id()),
+            None => false,
+        }
+    }
+
+    #[inline]
+    fn is_mac_roman(&self) -> bool {
+           self.platform_id() == Some(PlatformId::Macintosh)
+        && self.encoding_id() == MACINTOSH_ROMAN_ENCODING_ID
+    }
+
+    /// Checks that the name can be decoded by `name_utf8()`.
+    #[inline]
+    pub(crate) fn is_supported_encoding(&self) -> bool {
+        self.is_unicode() || self.is_mac_roman()
     }
 
     #[cfg(feature = "std")]
     #[inline(never)]
-    pub(crate) fn name_from_utf16_be(&self) -> Option<String> {
-        let mut name: Vec<u16> = Vec::new();
-        for c in LazyArray16::<u16>::new(self.name()) {
-            name.push(c);
-        }
+    fn name_from_mac_roman(&self) -> String {
+        self.chars().collect()
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn name_from_utf16_be(&self) -> Option<String> {
+        utf16_be_to_string(self.name())
+    }
+}
+
+// Malformed UTF-16 sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`,
+// so a single bad code unit will not discard the whole string.
+// A trailing odd byte is ignored, like in `NameChars`.
+#[cfg(feature = "std")]
+#[inline(never)]
+fn utf16_be_to_string(data: &[u8]) -> Option<String> {
+    let units = LazyArray16::<u16>::new(data);
+    let name = core::char::decode_utf16(units)
+        .map(|c| c.unwrap_or(core::char::REPLACEMENT_CHARACTER))
+        .collect();
+    Some(name)
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum NameEncoding {
+    Utf16Be,
+    MacRoman,
+    Unsupported,
+}
 
-        String::from_utf16(&name).ok()
+/// An iterator over [`Name`] characters.
+///
+/// [`Name`]: struct.Name.html
+#[derive(Clone, Copy, Debug)]
+pub struct NameChars<'a> {
+    data: &'a [u8],
+    offset: usize,
+    encoding: NameEncoding,
+}
+
+impl<'a> NameChars<'a> {
+    #[inline]
+    fn read_u16(&mut self) -> Option<u16> {
+        let n = Stream::read_at(self.data, self.offset)?;
+        self.offset += 2;
+        Some(n)
     }
 }
 
+impl<'a> Iterator for NameChars<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.encoding {
+            NameEncoding::Utf16Be => {
+                let c = self.read_u16()?;
+                if !(0xD800..0xDC00).contains(&c) {
+                    // A lone low surrogate will be replaced by `from_u32`.
+                    return Some(core::char::from_u32(u32::from(c))
+                        .unwrap_or(core::char::REPLACEMENT_CHARACTER));
+                }
+
+                // A high surrogate must be followed by a low one.
+                // Otherwise it will be replaced and the next code unit will be kept.
+                let offset = self.offset;
+                match self.read_u16() {
+                    Some(c2) if (0xDC00..0xE000).contains(&c2) => {
+                        let n = 0x10000 + ((u32::from(c) - 0xD800) << 10) + (u32::from(c2) - 0xDC00);
+                        Some(core::char::from_u32(n).unwrap_or(core::char::REPLACEMENT_CHARACTER))
+                    }
+                    _ => {
+                        self.offset = offset;
+                        Some(core::char::REPLACEMENT_CHARACTER)
+                    }
+                }
+            }
+            NameEncoding::MacRoman => {
+                let b = *self.data.get(self.offset)?;
+                self.offset += 1;
+                Some(mac_roman_to_char(b))
+            }
+            NameEncoding::Unsupported => None,
+        }
+    }
+}
+
+
 #[cfg(feature = "std")]
 impl<'a> core::fmt::Debug for Name<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
@@ -196,6 +388,7 @@ impl<'a> core::fmt::Debug for Name<'a> {
 pub struct Names<'a> {
     names: &'a [u8],
     storage: &'a [u8],
+    lang_tags: LazyArray16<'a, raw::LangTagRecord>,
     index: u16,
     total: u16,
 }
@@ -205,6 +398,7 @@ impl Default for Names<'_> {
         Names {
             names: &[],
             storage: &[],
+            lang_tags: LazyArray16::default(),
             index: 0,
             total: 0,
         }
@@ -212,14 +406,101 @@ impl Default for Names<'_> {
 }
 
 impl<'a> Names<'a> {
-    fn new(names: &'a [u8], storage: &'a [u8], total: u16) -> Self {
+    fn new(
+        names: &'a [u8],
+        storage: &'a [u8],
+        lang_tags: LazyArray16<'a, raw::LangTagRecord>,
+        total: u16,
+    ) -> Self {
         Names {
             names,
             storage,
+            lang_tags,
             index: 0,
             total,
         }
     }
+
+    /// Returns the total number of name records.
+    ///
+    /// Unlike `count()`, doesn't consume the iterator.
+    #[inline]
+    pub fn len(&self) -> u16 {
+        self.total
+    }
+
+    /// Checks if there are any name records.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Returns a name record at the specified index.
+    ///
+    /// Unlike `nth()`, doesn't affect the iterator position.
+    ///
+    /// Not to be confused with `get()`, which looks up a record by the Name ID.
+    #[inline]
+    pub fn at(&self, index: u16) -> Option<Name<'a>> {
+        if index < self.total {
+            self.record(index)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the best name record with the specified
+    /// [Name ID](https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-ids).
+    ///
+    /// Only records that can be decoded by `Name::name_utf8()` are checked.
+    /// Windows Unicode records have the highest priority, followed by
+    /// Unicode and then Macintosh ones.
+    ///
+    /// Unlike other iterator methods, always checks all the records.
+    pub fn get(&self, name_id: u16) -> Option<Name<'a>> {
+        let mut best: Option<(Name<'a>, u8)> = None;
+        for i in 0..self.total {
+            let name = try_opt_or!(self.record(i), None);
+            if name.name_id() != name_id || !name.is_supported_encoding() {
+                continue;
+            }
+
+            let priority = match name.platform_id() {
+                Some(PlatformId::Windows) => 2,
+                Some(PlatformId::Unicode) => 1,
+                _ => 0,
+            };
+
+            if best.map(|(_, p)| priority > p).unwrap_or(true) {
+                best = Some((name, priority));
+            }
+        }
+
+        best.map(|(name, _)| name)
+    }
+
+    /// Returns an iterator over name records with the specified platform and language.
+    ///
+    /// For example, Windows English (United States) names can be selected
+    /// via `names.by_language(PlatformId::Windows, 0x0409)`.
+    ///
+    /// Starts from the current iterator position.
+    #[inline]
+    pub fn by_language(self, platform_id: PlatformId, language_id: u16) -> LanguageNames<'a> {
+        LanguageNames { names: self, platform_id, language_id }
+    }
+
+    fn record(&self, index: u16) -> Option<Name<'a>> {
+        let start = raw::NameRecord::SIZE * usize::from(index);
+        let end = start + raw::NameRecord::SIZE;
+        let data = self.names.get(start..end)?;
+
+        Some(Name {
+            data: raw::NameRecord::new(data)?,
+            strings: self.storage,
+            lang_tags: self.lang_tags,
+        })
+    }
 }
 
 impl<'a> Iterator for Names<'a> {
@@ -239,13 +520,30 @@ impl<'a> Iterator for Names<'a> {
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        let start = raw::NameRecord::SIZE * n;
-        let end = start + raw::NameRecord::SIZE;
-        let data = self.names.get(start..end)?;
+        self.at(u16::try_from(n).ok()?)
+    }
+}
 
-        Some(Name {
-            data: raw::NameRecord::new(data)?,
-            strings: self.storage,
+
+/// An iterator over name records with a specific platform and language.
+///
+/// Created by `Names::by_language()`.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct LanguageNames<'a> {
+    names: Names<'a>,
+    platform_id: PlatformId,
+    language_id: u16,
+}
+
+impl<'a> Iterator for LanguageNames<'a> {
+    type Item = Name<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let platform_id = self.platform_id;
+        let language_id = self.language_id;
+        self.names.find(|name| {
+            name.platform_id() == Some(platform_id) && name.language_id() == language_id
         })
     }
 }
@@ -253,25 +551,328 @@ impl<'a> Iterator for Names<'a> {
 
 #[inline(never)]
 pub(crate) fn parse(data: &[u8]) -> Option<Names> {
-    // https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1
-    const LANG_TAG_RECORD_SIZE: u16 = 4;
-
     let mut s = Stream::new(data);
     let format: u16 = s.read()?;
     let count: u16 = s.read()?;
-    s.skip::<u16>(); // offset
-
-    if format == 0 {
-        let names_data = s.read_bytes(raw::NameRecord::SIZE * usize::from(count))?;
-        Some(Names::new(names_data, s.tail()?, count))
-    } else if format == 1 {
-        let lang_tag_count: u16 = s.read()?;
-        let lang_tag_len = lang_tag_count.checked_mul(LANG_TAG_RECORD_SIZE)?;
-
-        s.advance(usize::from(lang_tag_len)); // langTagRecords
-        let names_data = s.read_bytes(raw::NameRecord::SIZE * usize::from(count))?;
-        Some(Names::new(names_data, s.tail()?, count))
-    } else {
-        None
+    let storage_offset: Offset16 = s.read()?;
+
+    let names_data = s.read_bytes(raw::NameRecord::SIZE * usize::from(count))?;
+
+    let lang_tags = match format {
+        0 => LazyArray16::default(),
+        1 => {
+            // https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1
+            let lang_tag_count: u16 = s.read()?;
+            s.read_array16(lang_tag_count)?
+        }
+        _ => return None,
+    };
+
+    let storage = data.get(storage_offset.to_usize()..)?;
+    Some(Names::new(names_data, storage, lang_tags, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn mac_roman_name() {
+        let data = writer::convert(&[
+            UInt16(0), // format
+            UInt16(1), // count
+            UInt16(18), // offset
+            // NameRecord
+            UInt16(1), // platform ID - Macintosh
+            UInt16(0), // encoding ID - Roman
+            UInt16(0), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(5), // length
+            UInt16(0), // offset
+            // Storage
+            Raw(b"Caf\x8E\xA5"),
+        ]);
+
+        let name = parse(&data).unwrap().next().unwrap();
+        assert_eq!(name.name_utf8(), Some("Café•".to_string()));
+    }
+
+    #[test]
+    fn unsupported_mac_encoding() {
+        let data = writer::convert(&[
+            UInt16(0), // format
+            UInt16(1), // count
+            UInt16(18), // offset
+            // NameRecord
+            UInt16(1), // platform ID - Macintosh
+            UInt16(1), // encoding ID - Japanese
+            UInt16(0), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(2), // length
+            UInt16(0), // offset
+            // Storage
+            Raw(b"\x82\xA0"),
+        ]);
+
+        let name = parse(&data).unwrap().next().unwrap();
+        assert_eq!(name.name_utf8(), None);
+    }
+
+    #[test]
+    fn get_prefers_windows() {
+        let data = writer::convert(&[
+            UInt16(0), // format
+            UInt16(3), // count
+            UInt16(42), // offset
+            // NameRecord
+            UInt16(1), // platform ID - Macintosh
+            UInt16(0), // encoding ID - Roman
+            UInt16(0), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(2), // length
+            UInt16(0), // offset
+            // NameRecord
+            UInt16(3), // platform ID - Windows
+            UInt16(1), // encoding ID - Unicode BMP
+            UInt16(0x0409), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(4), // length
+            UInt16(2), // offset
+            // NameRecord
+            UInt16(3), // platform ID - Windows
+            UInt16(1), // encoding ID - Unicode BMP
+            UInt16(0x0409), // language ID
+            UInt16(name_id::FULL_NAME),
+            UInt16(4), // length
+            UInt16(2), // offset
+            // Storage
+            Raw(b"AB\0C\0D"),
+        ]);
+
+        let names = parse(&data).unwrap();
+        assert_eq!(names.get(name_id::FAMILY).unwrap().name_utf8(), Some("CD".to_string()));
+        assert_eq!(names.get(name_id::FAMILY).unwrap().language_id(), 0x0409);
+        assert!(names.get(name_id::FULL_NAME).is_some());
+        assert!(names.get(name_id::DESIGNER).is_none());
+    }
+
+    #[test]
+    fn format_1_lang_tags() {
+        let data = writer::convert(&[
+            UInt16(1), // format
+            UInt16(2), // count
+            UInt16(36), // offset
+            // NameRecord
+            UInt16(3), // platform ID - Windows
+            UInt16(1), // encoding ID - Unicode BMP
+            UInt16(0x8000), // language ID - first language-tag
+            UInt16(name_id::FAMILY),
+            UInt16(2), // length
+            UInt16(0), // offset
+            // NameRecord
+            UInt16(3), // platform ID - Windows
+            UInt16(1), // encoding ID - Unicode BMP
+            UInt16(0x8001), // language ID - out of bounds
+            UInt16(name_id::FULL_NAME),
+            UInt16(2), // length
+            UInt16(0), // offset
+            UInt16(1), // langTagCount
+            // LangTagRecord
+            UInt16(10), // length
+            UInt16(2), // offset
+            // Storage
+            Raw(b"\0A\0e\0n\0-\0U\0S"),
+        ]);
+
+        let mut names = parse(&data).unwrap();
+        let name = names.next().unwrap();
+        assert_eq!(name.name_utf8(), Some("A".to_string()));
+        assert_eq!(name.language_tag_utf8(), Some("en-US".to_string()));
+
+        let name = names.next().unwrap();
+        assert_eq!(name.name_utf8(), Some("A".to_string()));
+        assert_eq!(name.language_tag(), None);
+    }
+
+    #[test]
+    fn format_0_no_lang_tags() {
+        let data = writer::convert(&[
+            UInt16(0), // format
+            UInt16(1), // count
+            UInt16(18), // offset
+            // NameRecord
+            UInt16(3), // platform ID - Windows
+            UInt16(1), // encoding ID - Unicode BMP
+            UInt16(0x8000), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(2), // length
+            UInt16(0), // offset
+            // Storage
+            Raw(b"\0A"),
+        ]);
+
+        let name = parse(&data).unwrap().next().unwrap();
+        assert_eq!(name.language_tag(), None);
+    }
+
+    #[test]
+    fn lossy_utf16() {
+        let data = writer::convert(&[
+            UInt16(0), // format
+            UInt16(1), // count
+            UInt16(18), // offset
+            // NameRecord
+            UInt16(3), // platform ID - Windows
+            UInt16(1), // encoding ID - Unicode BMP
+            UInt16(0x0409), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(9), // length
+            UInt16(0), // offset
+            // Storage
+            UInt16(0x0041), // A
+            UInt16(0xDC00), // lone low surrogate
+            UInt16(0xD800), // lone high surrogate
+            UInt16(0x0042), // B
+            Raw(b"\0"), // odd byte
+        ]);
+
+        let name = parse(&data).unwrap().next().unwrap();
+        assert_eq!(name.name_utf8(), Some("A\u{FFFD}\u{FFFD}B".to_string()));
+        assert_eq!(name.chars().collect::<String>(), "A\u{FFFD}\u{FFFD}B");
+    }
+
+    #[test]
+    fn chars() {
+        let data = writer::convert(&[
+            UInt16(0), // format
+            UInt16(3), // count
+            UInt16(42), // offset
+            // NameRecord
+            UInt16(3), // platform ID - Windows
+            UInt16(1), // encoding ID - Unicode BMP
+            UInt16(0x0409), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(10), // length
+            UInt16(0), // offset
+            // NameRecord
+            UInt16(1), // platform ID - Macintosh
+            UInt16(0), // encoding ID - Roman
+            UInt16(0), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(2), // length
+            UInt16(10), // offset
+            // NameRecord
+            UInt16(1), // platform ID - Macintosh
+            UInt16(1), // encoding ID - Japanese
+            UInt16(0), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(2), // length
+            UInt16(10), // offset
+            // Storage
+            UInt16(0x0041), // A
+            UInt16(0xD83D), // 😀, high surrogate
+            UInt16(0xDE00), // 😀, low surrogate
+            UInt16(0xD800), // lone high surrogate
+            UInt16(0x0042), // B
+            Raw(b"C\xA5"),
+        ]);
+
+        let mut names = parse(&data).unwrap();
+        let chars: String = names.next().unwrap().chars().collect();
+        assert_eq!(chars, "A😀\u{FFFD}B");
+
+        let chars: String = names.next().unwrap().chars().collect();
+        assert_eq!(chars, "C•");
+
+        assert_eq!(names.next().unwrap().chars().count(), 0);
+    }
+
+    #[test]
+    fn random_access() {
+        let data = writer::convert(&[
+            UInt16(0), // format
+            UInt16(2), // count
+            UInt16(30), // offset
+            // NameRecord
+            UInt16(1), // platform ID - Macintosh
+            UInt16(0), // encoding ID - Roman
+            UInt16(0), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(1), // length
+            UInt16(0), // offset
+            // NameRecord
+            UInt16(1), // platform ID - Macintosh
+            UInt16(0), // encoding ID - Roman
+            UInt16(0), // language ID
+            UInt16(name_id::FULL_NAME),
+            UInt16(1), // length
+            UInt16(1), // offset
+            // Storage
+            Raw(b"AB"),
+        ]);
+
+        let mut names = parse(&data).unwrap();
+        assert_eq!(names.len(), 2);
+        assert!(!names.is_empty());
+        assert_eq!(names.at(1).unwrap().name_id(), name_id::FULL_NAME);
+        assert_eq!(names.at(0).unwrap().name_id(), name_id::FAMILY);
+        assert!(names.at(2).is_none());
+
+        // Random access should not affect the iterator.
+        assert_eq!(names.next().unwrap().name_id(), name_id::FAMILY);
+        assert_eq!(names.len(), 2);
+
+        assert!(Names::default().is_empty());
+    }
+
+    #[test]
+    fn by_language() {
+        let data = writer::convert(&[
+            UInt16(0), // format
+            UInt16(4), // count
+            UInt16(54), // offset
+            // NameRecord
+            UInt16(1), // platform ID - Macintosh
+            UInt16(0), // encoding ID - Roman
+            UInt16(0), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(1), // length
+            UInt16(0), // offset
+            // NameRecord
+            UInt16(3), // platform ID - Windows
+            UInt16(1), // encoding ID - Unicode BMP
+            UInt16(0x0407), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(2), // length
+            UInt16(0), // offset
+            // NameRecord
+            UInt16(3), // platform ID - Windows
+            UInt16(1), // encoding ID - Unicode BMP
+            UInt16(0x0409), // language ID
+            UInt16(name_id::FAMILY),
+            UInt16(2), // length
+            UInt16(0), // offset
+            // NameRecord
+            UInt16(3), // platform ID - Windows
+            UInt16(1), // encoding ID - Unicode BMP
+            UInt16(0x0409), // language ID
+            UInt16(name_id::FULL_NAME),
+            UInt16(2), // length
+            UInt16(0), // offset
+            // Storage
+            Raw(b"\0A"),
+        ]);
+
+        let names = parse(&data).unwrap();
+        let ids: std::vec::Vec<u16> = names.by_language(PlatformId::Windows, 0x0409)
+            .map(|name| name.name_id())
+            .collect();
+        assert_eq!(ids, &[name_id::FAMILY, name_id::FULL_NAME]);
+
+        assert_eq!(names.by_language(PlatformId::Macintosh, 0).count(), 1);
+        assert_eq!(names.by_language(PlatformId::Unicode, 0).count(), 0);
     }
 }
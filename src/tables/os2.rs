@@ -79,6 +79,7 @@ pub enum Width {
     Expanded,
     ExtraExpanded,
     UltraExpanded,
+    Other(u16),
 }
 
 impl Width {
@@ -95,6 +96,25 @@ impl Width {
             Width::Expanded         => 7,
             Width::ExtraExpanded    => 8,
             Width::UltraExpanded    => 9,
+            Width::Other(n)         => n,
+        }
+    }
+}
+
+impl From<u16> for Width {
+    #[inline]
+    fn from(value: u16) -> Self {
+        match value {
+            1 => Width::UltraCondensed,
+            2 => Width::ExtraCondensed,
+            3 => Width::Condensed,
+            4 => Width::SemiCondensed,
+            5 => Width::Normal,
+            6 => Width::SemiExpanded,
+            7 => Width::Expanded,
+            8 => Width::ExtraExpanded,
+            9 => Width::UltraExpanded,
+            _ => Width::Other(value),
         }
     }
 }
@@ -125,6 +145,42 @@ pub struct ScriptMetrics {
 }
 
 
+/// A set of [Unicode ranges](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#ur)
+/// declared by a font.
+///
+/// Provides only a coarse coverage check. `cmap` should be used to check individual characters.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct UnicodeRanges(u128);
+
+impl UnicodeRanges {
+    /// Checks that a range with the specified bit number is set.
+    ///
+    /// Bit numbers are in a 0..=127 range and are documented in the OS/2 specification.
+    /// For example, 0 is *Basic Latin* and 9 is *Cyrillic*.
+    #[inline]
+    pub fn contains(&self, bit: u8) -> bool {
+        bit < 128 && self.0 & (1 << bit) != 0
+    }
+}
+
+
+/// A set of [code pages](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#cpr)
+/// declared by a font.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct CodePages(u64);
+
+impl CodePages {
+    /// Checks that a code page with the specified bit number is set.
+    ///
+    /// Bit numbers are in a 0..=63 range and are documented in the OS/2 specification.
+    /// For example, 0 is *Latin 1* (1252) and 2 is *Cyrillic* (1251).
+    #[inline]
+    pub fn contains(&self, bit: u8) -> bool {
+        bit < 64 && self.0 & (1 << bit) != 0
+    }
+}
+
+
 // https://docs.microsoft.com/en-us/typography/opentype/spec/os2#fsselection
 #[derive(Clone, Copy)]
 struct SelectionFlags(u16);
@@ -159,7 +215,8 @@ impl<'a> Table<'a> {
             _ => return None,
         };
 
-        if data.len() != table_len {
+        // Tables can be padded, so only check that all the fields for the version are present.
+        if data.len() < table_len {
             return None;
         }
 
@@ -176,18 +233,31 @@ impl<'a> Table<'a> {
 
     #[inline]
     pub fn width(&self) -> Width {
-        match Stream::read_at(self.data, raw::US_WIDTH_CLASS_OFFSET).unwrap_or(0) {
-            1 => Width::UltraCondensed,
-            2 => Width::ExtraCondensed,
-            3 => Width::Condensed,
-            4 => Width::SemiCondensed,
-            5 => Width::Normal,
-            6 => Width::SemiExpanded,
-            7 => Width::Expanded,
-            8 => Width::ExtraExpanded,
-            9 => Width::UltraExpanded,
-            _ => Width::Normal,
+        Width::from(Stream::read_at(self.data, raw::US_WIDTH_CLASS_OFFSET).unwrap_or(0))
+    }
+
+    #[inline]
+    pub fn unicode_ranges(&self) -> UnicodeRanges {
+        let mut s = Stream::new_at(self.data, raw::UL_UNICODE_RANGE1_OFFSET).unwrap_or_default();
+        let mut n = 0;
+        for i in 0..4 {
+            let range: u32 = s.read().unwrap_or(0);
+            n |= u128::from(range) << (i * 32);
+        }
+
+        UnicodeRanges(n)
+    }
+
+    #[inline]
+    pub fn code_pages(&self) -> CodePages {
+        if self.version < 1 {
+            return CodePages::default();
         }
+
+        let mut s = Stream::new_at(self.data, raw::UL_CODE_PAGE_RANGE1_OFFSET).unwrap_or_default();
+        let range1: u32 = s.read().unwrap_or(0);
+        let range2: u32 = s.read().unwrap_or(0);
+        CodePages(u64::from(range2) << 32 | u64::from(range1))
     }
 
     #[inline]
@@ -215,7 +285,7 @@ impl<'a> Table<'a> {
     }
 
     #[inline]
-    pub(crate) fn is_use_typo_metrics(&self) -> bool {
+    pub fn is_use_typo_metrics(&self) -> bool {
         if self.version < 4 {
             false
         } else {
@@ -233,6 +303,15 @@ impl<'a> Table<'a> {
         }
     }
 
+    #[inline]
+    pub fn cap_height(&self) -> Option<i16> {
+        if self.version < 2 {
+            None
+        } else {
+            Stream::read_at(self.data, raw::S_CAP_HEIGHT_OFFSET)
+        }
+    }
+
     #[inline]
     pub fn strikeout_metrics(&self) -> LineMetrics {
         LineMetrics {
@@ -283,3 +362,120 @@ impl<'a> Table<'a> {
         Stream::read_at(self.data, raw::FS_SELECTION_OFFSET).unwrap_or(0)
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn table_data(version: u16, fs_selection: u16) -> std::vec::Vec<u8> {
+        let len = if version < 2 { 86 } else { 96 };
+        let mut data = writer::convert(&[UInt16(version)]);
+        data.resize(len, 0);
+        data[raw::FS_SELECTION_OFFSET..raw::FS_SELECTION_OFFSET + 2]
+            .copy_from_slice(&fs_selection.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn selection_flags() {
+        let data = table_data(4, 0x0001 | 0x0020 | 0x0080);
+        let table = Table::parse(&data).unwrap();
+        assert!(table.is_italic());
+        assert!(table.is_bold());
+        assert!(!table.is_regular());
+        assert!(table.is_use_typo_metrics());
+        assert!(!table.is_oblique());
+
+        let data = table_data(4, 0x0040 | 0x0200);
+        let table = Table::parse(&data).unwrap();
+        assert!(!table.is_italic());
+        assert!(!table.is_bold());
+        assert!(table.is_regular());
+        assert!(!table.is_use_typo_metrics());
+        assert!(table.is_oblique());
+    }
+
+    #[test]
+    fn ranges() {
+        let mut data = table_data(1, 0);
+        // Basic Latin (0), Cyrillic (9), Latin Extended Additional (29),
+        // CJK Unified Ideographs (59) and Variation Selectors (91).
+        data[42..58].copy_from_slice(&[
+            0x20, 0x00, 0x02, 0x01,
+            0x08, 0x00, 0x00, 0x00,
+            0x08, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+        // Latin 1 (0), Cyrillic (2) and US (63).
+        data[78..86].copy_from_slice(&[0x00, 0x00, 0x00, 0x05, 0x80, 0x00, 0x00, 0x00]);
+
+        let table = Table::parse(&data).unwrap();
+        let ranges = table.unicode_ranges();
+        let set: std::vec::Vec<u8> = (0..=255).filter(|bit| ranges.contains(*bit)).collect();
+        assert_eq!(set, &[0, 9, 29, 59, 91]);
+
+        let pages = table.code_pages();
+        let set: std::vec::Vec<u8> = (0..=255).filter(|bit| pages.contains(*bit)).collect();
+        assert_eq!(set, &[0, 2, 63]);
+    }
+
+    #[test]
+    fn heights() {
+        let mut data = table_data(2, 0);
+        data[86..90].copy_from_slice(&[0x01, 0xF4, 0x02, 0xBC]);
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.x_height(), Some(500));
+        assert_eq!(table.cap_height(), Some(700));
+
+        let data = table_data(1, 0);
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.x_height(), None);
+        assert_eq!(table.cap_height(), None);
+    }
+
+    #[test]
+    fn padded() {
+        let mut data = table_data(2, 0);
+        data.extend_from_slice(&[0, 0]);
+        assert!(Table::parse(&data).is_some());
+
+        data.truncate(94);
+        assert!(Table::parse(&data).is_none());
+    }
+
+    #[test]
+    fn code_pages_v0() {
+        let mut data = table_data(0, 0);
+        data.truncate(78);
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.code_pages(), CodePages::default());
+    }
+
+    #[test]
+    fn selection_flags_before_v4() {
+        // USE_TYPO_METRICS and OBLIQUE are defined only since version 4.
+        let data = table_data(1, 0x0080 | 0x0200);
+        let table = Table::parse(&data).unwrap();
+        assert!(!table.is_use_typo_metrics());
+        assert!(!table.is_oblique());
+    }
+
+    #[test]
+    fn weight() {
+        assert_eq!(Weight::from(100), Weight::Thin);
+        assert_eq!(Weight::from(900), Weight::Black);
+        assert_eq!(Weight::from(450), Weight::Other(450));
+        assert_eq!(Weight::from(450).to_number(), 450);
+    }
+
+    #[test]
+    fn width() {
+        assert_eq!(Width::from(1), Width::UltraCondensed);
+        assert_eq!(Width::from(9), Width::UltraExpanded);
+        assert_eq!(Width::from(0), Width::Other(0));
+        assert_eq!(Width::from(10).to_number(), 10);
+    }
+}
@@ -1,7 +1,9 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/post
 
 use crate::{LineMetrics, GlyphId};
-use crate::parser::{Stream, LazyArray16};
+use core::convert::TryFrom;
+
+use crate::parser::{Stream, LazyArray16, Fixed};
 use crate::raw::post as raw;
 
 
@@ -270,9 +272,12 @@ const MACINTOSH_NAMES: &[&str] = &[
 
 #[derive(Clone, Copy)]
 pub struct Table<'a> {
+    italic_angle: f32,
+    is_monospaced: bool,
     underline: LineMetrics,
     name_indexes: LazyArray16<'a, u16>,
     names: &'a [u8],
+    standard_offsets: LazyArray16<'a, i8>,
 }
 
 impl<'a> Table<'a> {
@@ -289,6 +294,9 @@ impl<'a> Table<'a> {
             return None;
         }
 
+        let italic_angle: Fixed = Stream::read_at(data, raw::ITALIC_ANGLE_OFFSET)?;
+        let is_fixed_pitch: u32 = Stream::read_at(data, raw::IS_FIXED_PITCH_OFFSET)?;
+
         let underline = LineMetrics {
             position: Stream::read_at(data, raw::UNDERLINE_POSITION_OFFSET)?,
             thickness: Stream::read_at(data, raw::UNDERLINE_THICKNESS_OFFSET)?,
@@ -296,22 +304,40 @@ impl<'a> Table<'a> {
 
         let mut name_indexes = LazyArray16::default();
         let mut names: &[u8] = &[];
+        let mut standard_offsets = LazyArray16::default();
 
-        // Only version 2.0 of the table has data at the end.
+        // Only versions 2.0 and 2.5 of the table have data at the end.
         if version == 0x00020000 {
             let mut s = Stream::new_at(data, raw::TABLE_SIZE)?;
             let count: u16 = s.read()?;
             name_indexes = s.read_array16(count)?;
             names = s.tail()?;
+        } else if version == 0x00025000 {
+            let mut s = Stream::new_at(data, raw::TABLE_SIZE)?;
+            let count: u16 = s.read()?;
+            standard_offsets = s.read_array16(count)?;
         }
 
         Some(Table {
+            italic_angle: italic_angle.0,
+            is_monospaced: is_fixed_pitch != 0,
             underline,
             name_indexes,
             names,
+            standard_offsets,
         })
     }
 
+    #[inline]
+    pub fn italic_angle(&self) -> f32 {
+        self.italic_angle
+    }
+
+    #[inline]
+    pub fn is_monospaced(&self) -> bool {
+        self.is_monospaced
+    }
+
     #[inline]
     pub fn underline_metrics(&self) -> LineMetrics {
         self.underline
@@ -319,6 +345,12 @@ impl<'a> Table<'a> {
 
     #[inline]
     pub fn glyph_name(&self, glyph_id: GlyphId) -> Option<&'a str> {
+        // Version 2.5 stores an offset to the Macintosh standard order for each glyph.
+        if let Some(offset) = self.standard_offsets.get(glyph_id.0) {
+            let index = i32::from(glyph_id.0) + i32::from(offset);
+            return MACINTOSH_NAMES.get(usize::try_from(index).ok()?).copied();
+        }
+
         let mut index = self.name_indexes.get(glyph_id.0)?;
 
         // 'If the name index is between 0 and 257, treat the name index
@@ -353,4 +385,131 @@ impl<'a> Table<'a> {
             None
         }
     }
+
+    /// Returns a glyph ID by its name.
+    ///
+    /// This is an O(n) operation, since names are not sorted.
+    /// Callers that need many lookups should cache the results.
+    pub fn glyph_index_by_name(&self, name: &str) -> Option<GlyphId> {
+        // A name can be either a Macintosh standard name or a custom one.
+        let mac_index = MACINTOSH_NAMES.iter().position(|n| *n == name);
+
+        if !self.standard_offsets.is_empty() {
+            let mac_index = mac_index?;
+            return self.standard_offsets.into_iter().enumerate().position(|(id, offset)| {
+                id as isize + isize::from(offset) == mac_index as isize
+            }).map(|id| GlyphId(id as u16));
+        }
+
+        let custom_index = self.custom_name_index(name);
+        if mac_index.is_none() && custom_index.is_none() {
+            return None;
+        }
+
+        self.name_indexes.into_iter().position(|index| {
+            let index = usize::from(index);
+            Some(index) == mac_index || Some(index) == custom_index
+        }).map(|id| GlyphId(id as u16))
+    }
+
+    // Returns an index of a name in the Pascal strings list,
+    // with the Macintosh names count already added.
+    fn custom_name_index(&self, name: &str) -> Option<usize> {
+        let mut s = Stream::new(self.names);
+        let mut i = MACINTOSH_NAMES.len();
+        while !s.at_end() {
+            let len: u8 = s.read()?;
+            if s.read_bytes(usize::from(len))? == name.as_bytes() {
+                return Some(i);
+            }
+
+            i += 1;
+        }
+
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn header(version: u32) -> std::vec::Vec<u8> {
+        writer::convert(&[
+            UInt32(version),
+            Int32(-12 << 16 | 0x8000), // italicAngle
+            Int16(-75), // underlinePosition
+            Int16(50), // underlineThickness
+            UInt32(1), // isFixedPitch
+            UInt32(0), // minMemType42
+            UInt32(0), // maxMemType42
+            UInt32(0), // minMemType1
+            UInt32(0), // maxMemType1
+        ])
+    }
+
+    #[test]
+    fn glyph_names_v2() {
+        let mut data = header(0x00020000);
+        data.extend_from_slice(&writer::convert(&[
+            UInt16(4), // numGlyphs
+            UInt16(0), // .notdef
+            UInt16(259), // custom 1
+            UInt16(258), // custom 0
+            UInt16(36), // A
+            UInt8(3),
+            Raw(b"foo"),
+            UInt8(3),
+            Raw(b"bar"),
+        ]));
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.glyph_name(GlyphId(0)), Some(".notdef"));
+        assert_eq!(table.glyph_name(GlyphId(1)), Some("bar"));
+        assert_eq!(table.glyph_name(GlyphId(2)), Some("foo"));
+        assert_eq!(table.glyph_name(GlyphId(3)), Some("A"));
+        assert_eq!(table.glyph_name(GlyphId(4)), None);
+
+        assert_eq!(table.glyph_index_by_name(".notdef"), Some(GlyphId(0)));
+        assert_eq!(table.glyph_index_by_name("bar"), Some(GlyphId(1)));
+        assert_eq!(table.glyph_index_by_name("foo"), Some(GlyphId(2)));
+        assert_eq!(table.glyph_index_by_name("A"), Some(GlyphId(3)));
+        assert_eq!(table.glyph_index_by_name("B"), None);
+        assert_eq!(table.glyph_index_by_name("baz"), None);
+    }
+
+    #[test]
+    fn glyph_names_v2_5() {
+        let mut data = header(0x00025000);
+        data.extend_from_slice(&writer::convert(&[
+            UInt16(4), // numGlyphs
+            Int8(0), // .notdef
+            Int8(35), // A
+            Int8(-1), // .null
+            Int8(-4), // out of bounds
+        ]));
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.glyph_name(GlyphId(0)), Some(".notdef"));
+        assert_eq!(table.glyph_name(GlyphId(1)), Some("A"));
+        assert_eq!(table.glyph_name(GlyphId(2)), Some(".null"));
+        assert_eq!(table.glyph_name(GlyphId(3)), None);
+        assert_eq!(table.glyph_name(GlyphId(4)), None);
+
+        assert_eq!(table.glyph_index_by_name("A"), Some(GlyphId(1)));
+        assert_eq!(table.glyph_index_by_name(".null"), Some(GlyphId(2)));
+        assert_eq!(table.glyph_index_by_name("B"), None);
+    }
+
+    #[test]
+    fn header_fields() {
+        let data = header(0x00030000);
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.italic_angle(), -11.5);
+        assert!(table.is_monospaced());
+        assert_eq!(table.underline_metrics(), LineMetrics { position: -75, thickness: 50 });
+    }
 }
@@ -4,101 +4,288 @@ use core::convert::TryFrom;
 use core::num::NonZeroU16;
 
 use crate::{GlyphId, GlyphImage, ImageFormat, Tag};
-use crate::parser::{Stream, FromData, Offset, Offset32};
+use crate::parser::{Stream, FromData, Offset, Offset32, LazyArray32};
 
-pub fn parse(
-    data: &[u8],
+pub fn parse<'a>(
+    data: &'a [u8],
     number_of_glyphs: NonZeroU16,
     glyph_id: GlyphId,
     pixels_per_em: u16,
     depth: u8,
-) -> Option<GlyphImage> {
-    if depth == 10 {
-        return None;
+) -> Option<GlyphImage<'a>> {
+    // Select the largest strike that is not larger than `pixels_per_em`
+    // or the smallest one when all strikes are larger.
+    let mut best: Option<RasterStrike> = None;
+    let mut smallest: Option<RasterStrike> = None;
+    for strike in strikes(data, number_of_glyphs) {
+        let ppem = strike.pixels_per_em;
+        let is_larger = match best {
+            Some(s) => ppem > s.pixels_per_em,
+            None => true,
+        };
+        if ppem <= pixels_per_em && is_larger {
+            best = Some(strike);
+        }
+
+        let is_smaller = match smallest {
+            Some(s) => ppem < s.pixels_per_em,
+            None => true,
+        };
+        if is_smaller {
+            smallest = Some(strike);
+        }
     }
 
-    let total_glyphs = u32::from(number_of_glyphs.get().checked_add(1)?);
+    best.or(smallest)?.glyph_image_impl(glyph_id, depth)
+}
 
+pub fn strikes(data: &[u8], number_of_glyphs: NonZeroU16) -> RasterStrikes<'_> {
     let mut s = Stream::new(data);
-    let version: u16 = s.read()?;
-    if version != 1 {
-        return None;
+    let version: Option<u16> = s.read();
+    if version != Some(1) {
+        return RasterStrikes::default();
     }
 
     s.skip::<u16>(); // flags
-    let count: u32 = s.read()?;
-    if count == 0 {
-        return None;
+    let offsets = s.read::<u32>().and_then(|count| s.read_array32::<Offset32>(count));
+
+    RasterStrikes {
+        data,
+        offsets: offsets.unwrap_or_default(),
+        index: 0,
+        number_of_glyphs: number_of_glyphs.get(),
     }
+}
 
-    let strikes = s.read_array32::<Offset32>(count)?;
-
-    // Select a best matching strike based on `pixels_per_em`.
-    let mut idx = 0;
-    let mut max_ppem = 0;
-    {
-        for (i, offset) in strikes.into_iter().enumerate() {
-            let mut s = Stream::new_at(data, offset.to_usize())?;
-            let ppem: u16 = s.read()?;
-            s.skip::<u16>(); // ppi
-
-            if (pixels_per_em <= ppem && ppem < max_ppem) ||
-                (pixels_per_em > max_ppem && ppem > max_ppem)
-            {
-                idx = i as u32;
-                max_ppem = ppem;
+
+/// An iterator over [bitmap strikes](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/sbix#strikes).
+///
+/// Malformed strikes are skipped.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct RasterStrikes<'a> {
+    data: &'a [u8],
+    offsets: LazyArray32<'a, Offset32>,
+    index: u32,
+    number_of_glyphs: u16,
+}
+
+impl<'a> Iterator for RasterStrikes<'a> {
+    type Item = RasterStrike<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.offsets.len() {
+            let offset = self.offsets.get(self.index)?.to_usize();
+            self.index += 1;
+
+            if let Some(strike) = RasterStrike::parse(self.data, offset, self.number_of_glyphs) {
+                return Some(strike);
             }
         }
+
+        None
     }
+}
+
+
+/// A bitmap strike, which stores glyph images for a specific size.
+#[derive(Clone, Copy)]
+#[allow(missing_debug_implementations)]
+pub struct RasterStrike<'a> {
+    // Glyph data offsets are from the beginning of the strike.
+    data: &'a [u8],
+    glyph_offsets: LazyArray32<'a, Offset32>,
+    pixels_per_em: u16,
+    resolution: u16,
+}
+
+impl<'a> RasterStrike<'a> {
+    fn parse(data: &'a [u8], offset: usize, number_of_glyphs: u16) -> Option<Self> {
+        let data = data.get(offset..)?;
+        let mut s = Stream::new(data);
+        let pixels_per_em: u16 = s.read()?;
+        let resolution: u16 = s.read()?;
+        // There is an extra offset for the end of the last glyph data.
+        let glyph_offsets = s.read_array32(u32::from(number_of_glyphs) + 1)?;
 
-    let offset = strikes.get(idx)?;
-    let mut s = Stream::new_at(data, offset.to_usize())?;
-    s.skip::<u16>(); // ppem
-    s.skip::<u16>(); // ppi
+        Some(RasterStrike {
+            data,
+            glyph_offsets,
+            pixels_per_em,
+            resolution,
+        })
+    }
+
+    /// Returns the number of pixels per em this strike was designed for.
+    #[inline]
+    pub fn pixels_per_em(&self) -> u16 {
+        self.pixels_per_em
+    }
 
-    let glyph_offsets = s.read_array32::<Offset32>(total_glyphs)?;
-    let start = glyph_offsets.get(u32::from(glyph_id.0))?.to_usize();
-    let end = glyph_offsets.get(u32::from(glyph_id.0.checked_add(1)?))?.to_usize();
+    /// Returns the device pixel density, in PPI, this strike was designed for.
+    #[inline]
+    pub fn resolution(&self) -> u16 {
+        self.resolution
+    }
 
-    if start == end {
-        // No bitmap data for that glyph.
-        return None;
+    /// Returns a glyph image from this strike.
+    ///
+    /// Returns `None` when a glyph has no image in this strike.
+    #[inline]
+    pub fn glyph_image(&self, glyph_id: GlyphId) -> Option<GlyphImage<'a>> {
+        self.glyph_image_impl(glyph_id, 0)
     }
 
-    let data_len = end.checked_sub(start)?.checked_sub(8)?; // 8 is a Glyph data header size.
-
-    let mut s = Stream::new_at(data, offset.to_usize() + start)?;
-    let x_offset: i16 = s.read()?;
-    let y_offset: i16 = s.read()?;
-    let image_type: Tag = s.read()?;
-    let image_data = s.read_bytes(data_len)?;
-
-    // We do ignore `pdf` and `mask` intentionally, because Apple docs state that:
-    // 'Support for the 'pdf ' and 'mask' data types and sbixDrawOutlines flag
-    // are planned for future releases of iOS and OS X.'
-    let format = match &image_type.to_bytes() {
-        b"png " => ImageFormat::PNG,
-        b"jpg " => ImageFormat::JPEG,
-        b"tiff" => ImageFormat::TIFF,
-        b"dupe" => {
-            // 'The special graphicType of 'dupe' indicates that
-            // the data field contains a glyph ID. The bitmap data for
-            // the indicated glyph should be used for the current glyph.'
-            let glyph_id = GlyphId::parse(image_data)?;
-            return parse(data, number_of_glyphs, glyph_id, pixels_per_em, depth + 1);
+    fn glyph_image_impl(&self, glyph_id: GlyphId, depth: u8) -> Option<GlyphImage<'a>> {
+        if depth == 10 {
+            return None;
         }
-        _ => {
+
+        let start = self.glyph_offsets.get(u32::from(glyph_id.0))?.to_usize();
+        let end = self.glyph_offsets.get(u32::from(glyph_id.0.checked_add(1)?))?.to_usize();
+
+        if start == end {
+            // No bitmap data for that glyph.
             return None;
         }
-    };
-
-    Some(GlyphImage {
-        x: i16::try_from(x_offset).ok(),
-        y: i16::try_from(y_offset).ok(),
-        width: None,
-        height: None,
-        pixels_per_em: max_ppem,
-        format,
-        data: image_data,
-    })
+
+        let data_len = end.checked_sub(start)?.checked_sub(8)?; // 8 is a Glyph data header size.
+
+        let mut s = Stream::new_at(self.data, start)?;
+        let x_offset: i16 = s.read()?;
+        let y_offset: i16 = s.read()?;
+        let image_type: Tag = s.read()?;
+        let image_data = s.read_bytes(data_len)?;
+
+        // We do ignore `pdf` and `mask` intentionally, because Apple docs state that:
+        // 'Support for the 'pdf ' and 'mask' data types and sbixDrawOutlines flag
+        // are planned for future releases of iOS and OS X.'
+        let format = match &image_type.to_bytes() {
+            b"png " => ImageFormat::PNG,
+            b"jpg " => ImageFormat::JPEG,
+            b"tiff" => ImageFormat::TIFF,
+            b"dupe" => {
+                // 'The special graphicType of 'dupe' indicates that
+                // the data field contains a glyph ID. The bitmap data for
+                // the indicated glyph should be used for the current glyph.'
+                let glyph_id = GlyphId::parse(image_data)?;
+                return self.glyph_image_impl(glyph_id, depth + 1);
+            }
+            _ => {
+                return None;
+            }
+        };
+
+        Some(GlyphImage {
+            x: i16::try_from(x_offset).ok(),
+            y: i16::try_from(y_offset).ok(),
+            width: None,
+            height: None,
+            pixels_per_em: self.pixels_per_em,
+            format,
+            data: image_data,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn table_data() -> std::vec::Vec<u8> {
+        writer::convert(&[
+            UInt16(1), // version
+            UInt16(0), // flags
+            UInt32(2), // numStrikes
+            UInt32(16), // strikeOffsets [0]
+            UInt32(56), // strikeOffsets [1]
+            // Strike 0
+            UInt16(32), // ppem
+            UInt16(72), // ppi
+            UInt32(16), // glyphDataOffsets [0]
+            UInt32(30), // glyphDataOffsets [1]
+            UInt32(40), // glyphDataOffsets [2]
+            // Glyph 0
+            Int16(1), // originOffsetX
+            Int16(-2), // originOffsetY
+            Raw(b"png "), // graphicType
+            Raw(b"\x89PNG\r\n"), // data
+            // Glyph 1
+            Int16(0), // originOffsetX
+            Int16(0), // originOffsetY
+            Raw(b"dupe"), // graphicType
+            UInt16(0), // data
+            // Strike 1
+            UInt16(64), // ppem
+            UInt16(72), // ppi
+            UInt32(16), // glyphDataOffsets [0]
+            UInt32(16), // glyphDataOffsets [1]
+            UInt32(16), // glyphDataOffsets [2]
+        ])
+    }
+
+    #[test]
+    fn png() {
+        let data = table_data();
+        let number_of_glyphs = NonZeroU16::new(2).unwrap();
+        let image = parse(&data, number_of_glyphs, GlyphId(0), 20, 0).unwrap();
+        assert_eq!(image.x, Some(1));
+        assert_eq!(image.y, Some(-2));
+        assert_eq!(image.pixels_per_em, 32);
+        assert_eq!(image.format, ImageFormat::PNG);
+        assert_eq!(image.data, b"\x89PNG\r\n");
+    }
+
+    #[test]
+    fn dupe() {
+        let data = table_data();
+        let number_of_glyphs = NonZeroU16::new(2).unwrap();
+        let image = parse(&data, number_of_glyphs, GlyphId(1), 32, 0).unwrap();
+        assert_eq!(image.format, ImageFormat::PNG);
+        assert_eq!(image.data, b"\x89PNG\r\n");
+    }
+
+    #[test]
+    fn strike_selection() {
+        let data = table_data();
+        let number_of_glyphs = NonZeroU16::new(2).unwrap();
+        let ppem = |pixels_per_em| {
+            parse(&data, number_of_glyphs, GlyphId(0), pixels_per_em, 0).map(|i| i.pixels_per_em)
+        };
+        assert_eq!(ppem(20), Some(32)); // the smallest strike
+        assert_eq!(ppem(32), Some(32));
+        assert_eq!(ppem(48), Some(32));
+        assert_eq!(ppem(63), Some(32));
+        // The 64 ppem strike has no glyph data.
+        assert_eq!(ppem(64), None);
+        assert_eq!(ppem(core::u16::MAX), None);
+        assert!(parse(&data, number_of_glyphs, GlyphId(2), 32, 0).is_none());
+    }
+
+    #[test]
+    fn strikes_list() {
+        let data = table_data();
+        let number_of_glyphs = NonZeroU16::new(2).unwrap();
+        let list: std::vec::Vec<_> = strikes(&data, number_of_glyphs)
+            .map(|s| (s.pixels_per_em(), s.resolution()))
+            .collect();
+        assert_eq!(list, &[(32, 72), (64, 72)]);
+
+        let mut iter = strikes(&data, number_of_glyphs);
+        let strike = iter.next().unwrap();
+        assert_eq!(strike.glyph_image(GlyphId(1)).unwrap().data, b"\x89PNG\r\n");
+        let strike = iter.next().unwrap();
+        assert!(strike.glyph_image(GlyphId(0)).is_none());
+    }
+
+    #[test]
+    fn strikes_unsupported_version() {
+        let data = writer::convert(&[UInt16(2), UInt16(0), UInt32(0)]);
+        let number_of_glyphs = NonZeroU16::new(2).unwrap();
+        assert_eq!(strikes(&data, number_of_glyphs).count(), 0);
+    }
 }
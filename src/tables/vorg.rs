@@ -1,5 +1,7 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/vorg
 
+use core::ops::Range;
+
 use crate::GlyphId;
 use crate::parser::{Stream, LazyArray16};
 use crate::raw::vorg as raw;
@@ -30,8 +32,107 @@ impl<'a> Table<'a> {
     }
 
     pub fn glyph_y_origin(&self, glyph_id: GlyphId) -> i16 {
-        self.origins.binary_search_by(|m| m.glyph_index().cmp(&glyph_id))
+        self.origins.binary_search_by_key(&glyph_id, |m| m.glyph_index())
             .map(|(_, m)| m.vert_origin_y())
             .unwrap_or(self.default_y)
     }
+
+    pub fn glyph_y_origins(&self, range: Range<GlyphId>) -> GlyphYOrigins<'a> {
+        // Find the first record that is not before the range start.
+        // This is the only search we have to do, since records are sorted by glyph ID.
+        let mut start = 0;
+        let mut end = self.origins.len();
+        while start < end {
+            let mid = start + (end - start) / 2;
+            match self.origins.get(mid) {
+                Some(m) if m.glyph_index() < range.start => start = mid + 1,
+                _ => end = mid,
+            }
+        }
+
+        GlyphYOrigins {
+            default_y: self.default_y,
+            origins: self.origins,
+            index: start,
+            glyph_id: range.start.0,
+            end: range.end.0,
+        }
+    }
+}
+
+
+/// An iterator over glyph vertical origins.
+///
+/// Yields an origin for each glyph ID in a range.
+#[derive(Clone, Copy, Default)]
+#[allow(missing_debug_implementations)]
+pub struct GlyphYOrigins<'a> {
+    default_y: i16,
+    origins: LazyArray16<'a, raw::VertOriginYMetrics>,
+    index: u16,
+    glyph_id: u16,
+    end: u16,
+}
+
+impl<'a> Iterator for GlyphYOrigins<'a> {
+    type Item = i16;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.glyph_id >= self.end {
+            return None;
+        }
+
+        let glyph_id = GlyphId(self.glyph_id);
+        self.glyph_id += 1;
+
+        match self.origins.get(self.index) {
+            Some(m) if m.glyph_index() == glyph_id => {
+                self.index += 1;
+                Some(m.vert_origin_y())
+            }
+            _ => Some(self.default_y),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::from(self.end.saturating_sub(self.glyph_id));
+        (len, Some(len))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn glyph_y_origins() {
+        let data = writer::convert(&[
+            UInt32(0x00010000), // version
+            Int16(880), // defaultVertOriginY
+            UInt16(3), // numVertOriginYMetrics
+            UInt16(2), Int16(800),
+            UInt16(3), Int16(810),
+            UInt16(6), Int16(820),
+        ]);
+
+        let table = Table::parse(&data).unwrap();
+        let origins: std::vec::Vec<i16> = table.glyph_y_origins(GlyphId(1)..GlyphId(8)).collect();
+        assert_eq!(origins, &[880, 800, 810, 880, 880, 820, 880]);
+
+        let origins: std::vec::Vec<i16> = table.glyph_y_origins(GlyphId(3)..GlyphId(4)).collect();
+        assert_eq!(origins, &[810]);
+
+        assert_eq!(table.glyph_y_origins(GlyphId(5)..GlyphId(5)).count(), 0);
+        assert_eq!(table.glyph_y_origins(GlyphId(5)..GlyphId(2)).count(), 0);
+
+        for id in 0..8 {
+            let origin = table.glyph_y_origins(GlyphId(id)..GlyphId(id + 1)).next();
+            assert_eq!(origin, Some(table.glyph_y_origin(GlyphId(id))));
+        }
+    }
 }
@@ -74,10 +74,14 @@ impl<'a> ItemVariationStore<'a> {
         inner_index: u16,
         coordinates: &[NormalizedCoord],
     ) -> Option<f32> {
+        // 'The high bit of wordDeltaCount indicates that the word deltas are 32-bit
+        // and the remaining deltas are 16-bit'.
+        const LONG_WORDS: u16 = 0x8000;
+
         let offset = self.data_offsets.get(outer_index)?;
         let mut s = Stream::new_at(self.data, usize::num_from(offset))?;
         let item_count: u16 = s.read()?;
-        let short_delta_count: u16 = s.read()?;
+        let word_delta_count: u16 = s.read()?;
         let region_index_count: u16 = s.read()?;
         let region_indices = s.read_array16(region_index_count)?;
 
@@ -85,20 +89,38 @@ impl<'a> ItemVariationStore<'a> {
             return None;
         }
 
-        let delta_set_len = usize::from(short_delta_count) + usize::from(region_index_count);
-        s.advance(usize::from(inner_index).checked_mul(delta_set_len)?);
+        let long_words = word_delta_count & LONG_WORDS != 0;
+        let word_delta_count = word_delta_count & !LONG_WORDS;
+        if word_delta_count > region_index_count {
+            return None;
+        }
+
+        let word_size = if long_words { 4 } else { 2 };
+        let delta_set_len = usize::from(word_delta_count) * word_size
+            + usize::from(region_index_count - word_delta_count) * (word_size / 2);
+        s.advance_checked(usize::from(inner_index).checked_mul(delta_set_len)?)?;
 
         let mut delta = 0.0;
         let mut i = 0;
-        while i < short_delta_count {
+        while i < word_delta_count {
             let idx = region_indices.get(i)?;
-            delta += f32::from(s.read::<i16>()?) * self.regions.evaluate_region(idx, coordinates);
+            let value = if long_words {
+                s.read::<i32>()? as f32
+            } else {
+                f32::from(s.read::<i16>()?)
+            };
+            delta += value * self.regions.evaluate_region(idx, coordinates);
             i += 1;
         }
 
         while i < region_index_count {
             let idx = region_indices.get(i)?;
-            delta += f32::from(s.read::<i8>()?) * self.regions.evaluate_region(idx, coordinates);
+            let value = if long_words {
+                f32::from(s.read::<i16>()?)
+            } else {
+                f32::from(s.read::<i8>()?)
+            };
+            delta += value * self.regions.evaluate_region(idx, coordinates);
             i += 1;
         }
 
@@ -120,13 +142,20 @@ impl<'a> VariationRegionList<'a> {
         index: u16,
         coordinates: &[NormalizedCoord],
     ) -> f32 {
+        let start = match index.checked_mul(self.axis_count) {
+            Some(v) => v,
+            None => return 0.0,
+        };
+
         let mut v = 1.0;
-        for (i, coord) in coordinates.iter().enumerate() {
-            let region = match self.regions.get(index * self.axis_count + i as u16) {
+        for i in 0..self.axis_count {
+            let region = match start.checked_add(i).and_then(|idx| self.regions.get(idx)) {
                 Some(r) => r,
                 None => return 0.0,
             };
 
+            // Missing coordinates are set to the default value.
+            let coord = coordinates.get(usize::from(i)).cloned().unwrap_or_default();
             let factor = region.evaluate_axis(coord.get());
             if factor == 0.0 {
                 return 0.0;
@@ -162,10 +191,77 @@ impl raw::RegionAxisCoordinatesRecord {
             return 0.0;
         }
 
+        let (coord, start, peak, end) = (i32::from(coord), i32::from(start),
+                                         i32::from(peak), i32::from(end));
         if coord < peak {
-            f32::from(coord - start) / f32::from(peak - start)
+            (coord - start) as f32 / (peak - start) as f32
         } else {
-            f32::from(end - coord) / f32::from(end - peak)
+            (end - coord) as f32 / (end - peak) as f32
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn store(item_data: &[writer::TtfType]) -> std::vec::Vec<u8> {
+        let mut data = writer::convert(&[
+            UInt16(1), // format
+            UInt32(12), // variationRegionListOffset
+            UInt16(1), // itemVariationDataCount
+            UInt32(40), // itemVariationDataOffsets[0]
+            // VariationRegionList
+            UInt16(2), // axisCount
+            UInt16(2), // regionCount
+            // Region 0
+            Int16(0), Int16(16384), Int16(16384), // axis 0
+            Int16(0), Int16(0), Int16(0), // axis 1
+            // Region 1
+            Int16(0), Int16(0), Int16(0), // axis 0
+            Int16(-16384), Int16(-16384), Int16(0), // axis 1
+        ]);
+        data.extend_from_slice(&writer::convert(item_data));
+        data
+    }
+
+    #[test]
+    fn delta() {
+        let data = store(&[
+            UInt16(2), // itemCount
+            UInt16(1), // wordDeltaCount
+            UInt16(2), // regionIndexCount
+            UInt16(0), UInt16(1), // regionIndexes
+            // Delta sets
+            Int16(200), Int8(-10),
+            Int16(-1000), Int8(20),
+        ]);
+
+        let store = ItemVariationStore::parse(Stream::new(&data)).unwrap();
+        let coords = [NormalizedCoord::from(0.5), NormalizedCoord::from(-0.25)];
+        assert_eq!(store.parse_delta(0, 0, &coords), Some(100.0 - 2.5));
+        assert_eq!(store.parse_delta(0, 1, &coords), Some(-500.0 + 5.0));
+        assert_eq!(store.parse_delta(0, 1, &coords[..1]), Some(-500.0));
+        assert_eq!(store.parse_delta(0, 2, &coords), None);
+        assert_eq!(store.parse_delta(1, 0, &coords), None);
+    }
+
+    #[test]
+    fn delta_long_words() {
+        let data = store(&[
+            UInt16(1), // itemCount
+            UInt16(0x8001), // wordDeltaCount: LONG_WORDS | 1
+            UInt16(2), // regionIndexCount
+            UInt16(0), UInt16(1), // regionIndexes
+            // Delta sets
+            Int32(100000), Int16(-1000),
+        ]);
+
+        let store = ItemVariationStore::parse(Stream::new(&data)).unwrap();
+        let coords = [NormalizedCoord::from(1.0), NormalizedCoord::from(-1.0)];
+        assert_eq!(store.parse_delta(0, 0, &coords), Some(100000.0 - 1000.0));
+    }
+}